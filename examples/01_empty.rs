@@ -2,6 +2,7 @@ use aurora::{
     BaseApp,
     GeometryManager,
     TextureManager,
+    MaterialManager,
     Renderer,
     WindowEvent,
     WindowSize,
@@ -18,6 +19,7 @@ impl <'app> BaseApp for Empty {
         _window_size: WindowSize,
         _geometry_manager: &mut GeometryManager,
         _texture_manager: &mut TextureManager,
+        _material_manager: &mut MaterialManager,
     ) -> Self {
         Self {}
     }
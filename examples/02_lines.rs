@@ -1,10 +1,19 @@
 use aurora::{ 
+    ArcDescription,
     BaseApp,
     Colour,
+    Curve,
+    CurveDescription,
+    DashPattern,
     GeometryManager,
     LineDescription,
+    LineJoin,
+    LineCap,
+    PolylineDescription,
     Point2,
+    Vector2,
     TextureManager,
+    MaterialManager,
     Renderer,
     RenderCommand,
     WindowEvent,
@@ -13,7 +22,9 @@ use aurora::{
 
 // Base structure for the application
 struct Lines {
-
+    zigzags: Vec<Vec<Point2>>,
+    square: Vec<Point2>,
+    dash_phase: f32,
 }
 
 // Implement the trait for the main application loop
@@ -22,23 +33,46 @@ impl BaseApp for Lines {
         _window_size: WindowSize,
         _geometry_manager: &mut GeometryManager,
         _texture_manager: &mut TextureManager,
+        _material_manager: &mut MaterialManager,
     ) -> Self {
-        Self {}
+        // A zigzag for each join and cap, spaced down the screen
+        let zigzags = [400.0, 500.0, 600.0].iter()
+            .map(|y| {
+                (0..6)
+                    .map(|i| Point2::new(100.0 + i as f32 * 50.0, if i % 2 == 0 { *y } else { *y + 60.0 }))
+                    .collect()
+            })
+            .collect();
+
+        // A closed shape has no caps
+        let square = vec![
+            Point2::new(550.0, 100.0),
+            Point2::new(700.0, 100.0),
+            Point2::new(700.0, 250.0),
+            Point2::new(550.0, 250.0),
+        ];
+
+        Self {
+            zigzags,
+            square,
+            dash_phase: 0.0,
+        }
     }
 
     fn handle_input(&mut self, _event: WindowEvent) {
 
     }
 
-    fn update(&mut self, _delta_t: f32) {
-
+    fn update(&mut self, delta_t: f32) {
+        // March the dashes along at 20 pixels a second
+        self.dash_phase += delta_t * 20.0;
     }
 
     fn resize(&mut self, _size: WindowSize) {
 
     }
 
-    fn draw<'draw, R: Renderer<'draw>>(&mut self, mut renderer: R) {
+    fn draw<'draw, R: Renderer<'draw>>(&'draw mut self, mut renderer: R) {
         // Clear the screen
         renderer.add(RenderCommand::Clear(
             Colour{
@@ -66,6 +100,7 @@ impl BaseApp for Lines {
                 b: 0.0,
                 a: 1.0,
             },
+            .. LineDescription::default()
         }));
 
         // Draw a second line
@@ -85,6 +120,146 @@ impl BaseApp for Lines {
                 b: 0.0,
                 a: 1.0,
             },
+            .. LineDescription::default()
+        }));
+
+        // Draw a gradient line
+        renderer.add(RenderCommand::DrawLine(LineDescription{
+            start: Point2::new(100.0, 200.0),
+            end: Point2::new(400.0, 400.0),
+            width: 4.0,
+            colour: Colour{
+                r: 1.0,
+                g: 0.5,
+                b: 0.0,
+                a: 1.0,
+            },
+            end_colour: Some(Colour{
+                r: 0.2,
+                g: 0.4,
+                b: 1.0,
+                a: 1.0,
+            }),
+            .. LineDescription::default()
+        }));
+
+        // Draw marching ants around a selection
+        let corners = [
+            Point2::new(500.0, 300.0),
+            Point2::new(750.0, 300.0),
+            Point2::new(750.0, 380.0),
+            Point2::new(500.0, 380.0),
+        ];
+        for i in 0..corners.len() {
+            renderer.add(RenderCommand::DrawLine(LineDescription{
+                start: corners[i],
+                end: corners[(i + 1) % corners.len()],
+                width: 1.0,
+                colour: Colour::WHITE,
+                dash: DashPattern::dashed(6.0, 4.0).with_phase(self.dash_phase),
+                .. LineDescription::default()
+            }));
+        }
+
+        // Draw a dotted and a dash-dot guide
+        renderer.add(RenderCommand::DrawLine(LineDescription{
+            start: Point2::new(500.0, 420.0),
+            end: Point2::new(750.0, 420.0),
+            width: 3.0,
+            colour: Colour::WHITE,
+            dash: DashPattern::dotted(3.0, 5.0),
+            .. LineDescription::default()
+        }));
+        renderer.add(RenderCommand::DrawLine(LineDescription{
+            start: Point2::new(500.0, 450.0),
+            end: Point2::new(750.0, 450.0),
+            width: 2.0,
+            colour: Colour::WHITE,
+            dash: DashPattern::dash_dot(12.0, 2.0, 4.0),
+            .. LineDescription::default()
+        }));
+
+        // Draw each of the joins with a matching cap
+        let styles = [
+            (LineJoin::Miter, LineCap::Butt),
+            (LineJoin::Round, LineCap::Round),
+            (LineJoin::Bevel, LineCap::Square),
+        ];
+
+        for (zigzag, (join, cap)) in self.zigzags.iter().zip(styles) {
+            renderer.add(RenderCommand::DrawPolyline(PolylineDescription{
+                points: zigzag,
+                width: 12.0,
+                colour: Colour{
+                    r: 0.2,
+                    g: 0.6,
+                    b: 1.0,
+                    a: 0.7,
+                },
+                join,
+                cap,
+                .. PolylineDescription::default()
+            }));
+
+            // Thin line along the centre to show where the points are
+            renderer.add(RenderCommand::DrawPolyline(PolylineDescription{
+                points: zigzag,
+                width: 1.0,
+                colour: Colour::WHITE,
+                .. PolylineDescription::default()
+            }));
+        }
+
+        // Draw a closed outline
+        renderer.add(RenderCommand::DrawPolyline(PolylineDescription{
+            points: &self.square,
+            width: 8.0,
+            colour: Colour{
+                r: 1.0,
+                g: 0.8,
+                b: 0.2,
+                a: 1.0,
+            },
+            join: LineJoin::Round,
+            closed: true,
+            .. PolylineDescription::default()
+        }));
+
+        // Draw a node graph style connection
+        renderer.add(RenderCommand::DrawCurve(CurveDescription{
+            curve: Curve::Cubic{
+                start: Point2::new(500.0, 500.0),
+                control_1: Point2::new(650.0, 500.0),
+                control_2: Point2::new(600.0, 650.0),
+                end: Point2::new(750.0, 650.0),
+            },
+            width: 3.0,
+            colour: Colour{
+                r: 0.4,
+                g: 1.0,
+                b: 0.6,
+                a: 1.0,
+            },
+            cap: LineCap::Round,
+            .. CurveDescription::default()
+        }));
+
+        // Draw a rotated elliptical arc
+        renderer.add(RenderCommand::DrawArc(ArcDescription{
+            centre: Point2::new(850.0, 200.0),
+            radii: Vector2::new(80.0, 40.0),
+            rotation: 0.5,
+            start_angle: 0.0,
+            end_angle: std::f32::consts::PI * 1.5,
+            width: 4.0,
+            colour: Colour{
+                r: 1.0,
+                g: 0.4,
+                b: 0.8,
+                a: 1.0,
+            },
+            cap: LineCap::Round,
+            .. ArcDescription::default()
         }));
     }
 }
@@ -3,10 +3,14 @@ use aurora::{
     Colour,
     GeometryManager,
     TwoDDescription,
+    TwoDStyle,
+    TwoDTypes,
     Point2,
     Vector2,
     TextureManager,
+    MaterialManager,
     TextureHandle,
+    TintMode,
     Renderer,
     RenderCommand,
     WindowEvent,
@@ -24,10 +28,16 @@ struct Rectangles {
 
 // Implement the trait for the main application loop
 impl BaseApp for Rectangles {
+    // Smooth the edges of the rotated shapes
+    fn sample_count() -> u32 {
+        4
+    }
+
     fn init(
         _window_size: WindowSize,
         _geometry_manager: &mut GeometryManager,
         texture_manager: &mut TextureManager,
+        _material_manager: &mut MaterialManager,
     ) -> Self {
 
         // Load a texture
@@ -101,15 +111,65 @@ impl BaseApp for Rectangles {
             .. TwoDDescription::default()
         }));
 
-        // Draw a textured rectangle
+        // Draw a textured rectangle, flashing white with the tint
         renderer.add(RenderCommand::Draw2D(TwoDDescription{
             position: Point2::new(700.0,100.0),
             size: Vector2::new(200.0,200.0),
+            colour: Colour{
+                a: (self.rotation * 6.0).sin().max(0.0) as f64,
+                .. Colour::WHITE
+            },
             texture: Some(self.bricks_texture),
+            tint_mode: TintMode::Replace,
             rotation: self.rotation,
             .. TwoDDescription::default()
         }));
 
+        // Outlined and bordered shapes
+        renderer.add(RenderCommand::Draw2D(TwoDDescription{
+            position: Point2::new(100.0,350.0),
+            size: Vector2::new(150.0,150.0),
+            style: TwoDStyle::Outlined,
+            border_colour: Colour::WHITE,
+            line_width: 2.0,
+            shape: TwoDTypes::Circle,
+            .. TwoDDescription::default()
+        }));
+
+        renderer.add(RenderCommand::Draw2D(TwoDDescription{
+            position: Point2::new(300.0,350.0),
+            size: Vector2::new(150.0,150.0),
+            colour: Colour{
+                r: 1.0,
+                g: 0.4,
+                b: 0.0,
+                a: 1.0,
+            },
+            style: TwoDStyle::FilledWithBorder,
+            border_colour: Colour::WHITE,
+            line_width: 6.0,
+            shape: TwoDTypes::Hexagon,
+            .. TwoDDescription::default()
+        }));
+
+        renderer.add(RenderCommand::Draw2D(TwoDDescription{
+            position: Point2::new(500.0,350.0),
+            size: Vector2::new(150.0,150.0),
+            colour: Colour{
+                r: 1.0,
+                g: 0.6,
+                b: 0.2,
+                a: 1.0,
+            },
+            texture: Some(self.squares_texture),
+            tint_mode: TintMode::Multiply,
+            style: TwoDStyle::FilledWithBorder,
+            border_colour: Colour::BLACK,
+            line_width: 4.0,
+            corner_radius: 0.2,
+            .. TwoDDescription::default()
+        }));
+
         // Add text
         renderer.add(RenderCommand::DrawText(
             Section::default()
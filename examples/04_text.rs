@@ -3,6 +3,7 @@ use aurora::{
     Colour,
     GeometryManager,
     TextureManager,
+    MaterialManager,
     Renderer,
     RenderCommand,
     WindowEvent,
@@ -22,6 +23,7 @@ impl BaseApp for TextExample {
         _window_size: WindowSize,
         _geometry_manager: &mut GeometryManager,
         _texture_manager: &mut TextureManager,
+        _material_manager: &mut MaterialManager,
     ) -> Self {
 
         Self {
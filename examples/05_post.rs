@@ -0,0 +1,159 @@
+use aurora::{
+    BaseApp,
+    Colour,
+    GeometryManager,
+    KeyCode,
+    KeyState,
+    PostEffect,
+    TwoDDescription,
+    TwoDTypes,
+    Point2,
+    Vector2,
+    TextureManager,
+    MaterialManager,
+    Renderer,
+    RenderCommand,
+    WindowEvent,
+    Section,
+    Text,
+    WindowSize,
+};
+
+// Custom pass that inverts the frame, params.x sets the amount
+const INVERT_SHADER: &str = "
+[[stage(fragment)]]
+fn fs_main(in: VertexOutput) -> [[location(0)]] vec4<f32> {
+    let col = textureSample(t_Source, s_Source, in.uv);
+    return vec4<f32>(mix(col.rgb, vec3<f32>(1.0) - col.rgb, post.params.x), col.a);
+}
+";
+
+// Base structure for the application
+struct PostProcessing {
+    rotation: f32,
+    effects_enabled: bool,
+    invert: bool,
+}
+
+// Implement the trait for the main application loop
+impl BaseApp for PostProcessing {
+    fn init(
+        _window_size: WindowSize,
+        _geometry_manager: &mut GeometryManager,
+        _texture_manager: &mut TextureManager,
+        _material_manager: &mut MaterialManager,
+    ) -> Self {
+        Self {
+            rotation: 0.0,
+            effects_enabled: true,
+            invert: false,
+        }
+    }
+
+    fn handle_input(&mut self, event: WindowEvent) {
+        // Space toggles the effects, I toggles the custom invert pass
+        if let WindowEvent::KeyboardInput { input, .. } = event {
+            if input.state == KeyState::Pressed {
+                match input.virtual_keycode {
+                    Some(KeyCode::Space) => self.effects_enabled = !self.effects_enabled,
+                    Some(KeyCode::I) => self.invert = !self.invert,
+                    _ => {},
+                }
+            }
+        }
+    }
+
+    fn update(&mut self, delta_t: f32) {
+        self.rotation += delta_t * 0.5;
+    }
+
+    fn resize(&mut self, _size: WindowSize) {
+
+    }
+
+    fn draw<'draw, R: Renderer<'draw>>(&mut self, mut renderer: R) {
+        // Clear the screen
+        renderer.add(RenderCommand::Clear(
+            Colour{
+                r: 0.1,
+                g: 0.05,
+                b: 0.15,
+                a: 1.0,
+            }
+        ));
+
+        // Draw some bright shapes for the bloom to pick up
+        renderer.add(RenderCommand::Draw2D(TwoDDescription{
+            position: Point2::new(150.0,150.0),
+            size: Vector2::new(200.0,200.0),
+            colour: Colour{
+                r: 1.0,
+                g: 0.9,
+                b: 0.2,
+                a: 1.0,
+            },
+            shape: TwoDTypes::Circle,
+            .. TwoDDescription::default()
+        }));
+
+        renderer.add(RenderCommand::Draw2D(TwoDDescription{
+            position: Point2::new(500.0,150.0),
+            size: Vector2::new(200.0,200.0),
+            colour: Colour{
+                r: 0.2,
+                g: 0.8,
+                b: 1.0,
+                a: 1.0,
+            },
+            rotation: self.rotation,
+            corner_radius: 0.2,
+            .. TwoDDescription::default()
+        }));
+
+        // Add text
+        renderer.add(RenderCommand::DrawText(
+            Section::default()
+                .add_text(
+                    Text::new("Space toggles effects, I toggles invert")
+                    .with_scale(30.0)
+                    .with_color(
+                        [1.0, 1.0, 1.0, 1.0]
+                    )
+                )
+                .with_screen_position((150.0, 450.0))
+        ));
+
+        // Post processing chain - applied in order
+        if self.effects_enabled {
+            renderer.add(RenderCommand::PostProcess(PostEffect::Bloom{
+                threshold: 0.6,
+                intensity: 1.2,
+                radius: 12.0,
+            }));
+            renderer.add(RenderCommand::PostProcess(PostEffect::ChromaticAberration{
+                offset: 3.0,
+            }));
+            renderer.add(RenderCommand::PostProcess(PostEffect::Crt{
+                scanline_intensity: 0.25,
+                curvature: 0.05,
+            }));
+            renderer.add(RenderCommand::PostProcess(PostEffect::Vignette{
+                radius: 0.8,
+                softness: 0.6,
+                colour: Colour::BLACK,
+            }));
+        }
+
+        if self.invert {
+            renderer.add(RenderCommand::PostProcess(PostEffect::Custom{
+                shader: INVERT_SHADER,
+                params: [1.0, 0.0, 0.0, 0.0],
+            }));
+        }
+    }
+}
+
+// Start the app
+fn main() {
+    aurora::run::<PostProcessing>("Post Processing Example");
+}
@@ -0,0 +1,179 @@
+use aurora::{
+    BaseApp,
+    Colour,
+    FillPathDescription,
+    FillRule,
+    GeometryManager,
+    LineJoin,
+    Path,
+    Point2,
+    StrokePathDescription,
+    Vector2,
+    TextureManager,
+    MaterialManager,
+    Renderer,
+    RenderCommand,
+    WindowEvent,
+    WindowSize,
+};
+
+// Base structure for the application
+struct Paths {
+    star: Path,
+    heart: Path,
+    badge: Path,
+}
+
+// Five pointed star drawn in one go, so the middle overlaps itself
+fn star(centre: Point2, radius: f32) -> Path {
+    let mut path = Path::new();
+
+    for i in 0..5 {
+        let angle = -std::f32::consts::FRAC_PI_2 + i as f32 * std::f32::consts::PI * 4.0 / 5.0;
+        let point = Point2::new(centre.x + radius * angle.cos(), centre.y + radius * angle.sin());
+
+        if i == 0 {
+            path.move_to(point);
+        } else {
+            path.line_to(point);
+        }
+    }
+
+    path.close();
+    path
+}
+
+// Implement the trait for the main application loop
+impl BaseApp for Paths {
+    // Path fills rely on multisampling for smooth edges
+    fn sample_count() -> u32 {
+        4
+    }
+
+    fn init(
+        _window_size: WindowSize,
+        _geometry_manager: &mut GeometryManager,
+        _texture_manager: &mut TextureManager,
+        _material_manager: &mut MaterialManager,
+    ) -> Self {
+        // A heart from cubic curves
+        let mut heart = Path::new();
+        heart.move_to(Point2::new(600.0, 180.0))
+            .cubic_to(Point2::new(600.0, 120.0), Point2::new(500.0, 120.0), Point2::new(500.0, 190.0))
+            .cubic_to(Point2::new(500.0, 250.0), Point2::new(580.0, 280.0), Point2::new(600.0, 320.0))
+            .cubic_to(Point2::new(620.0, 280.0), Point2::new(700.0, 250.0), Point2::new(700.0, 190.0))
+            .cubic_to(Point2::new(700.0, 120.0), Point2::new(600.0, 120.0), Point2::new(600.0, 180.0))
+            .close();
+
+        // A rounded badge with a circular hole cut out by drawing it the other way round
+        let mut badge = Path::new();
+        badge.move_to(Point2::new(820.0, 120.0))
+            .line_to(Point2::new(920.0, 120.0))
+            .quad_to(Point2::new(960.0, 120.0), Point2::new(960.0, 160.0))
+            .line_to(Point2::new(960.0, 260.0))
+            .quad_to(Point2::new(960.0, 300.0), Point2::new(920.0, 300.0))
+            .line_to(Point2::new(820.0, 300.0))
+            .quad_to(Point2::new(780.0, 300.0), Point2::new(780.0, 260.0))
+            .line_to(Point2::new(780.0, 160.0))
+            .quad_to(Point2::new(780.0, 120.0), Point2::new(820.0, 120.0))
+            .close()
+            .move_to(Point2::new(830.0, 210.0))
+            .arc_to(Vector2::new(40.0, 40.0), 0.0, false, false, Point2::new(910.0, 210.0))
+            .arc_to(Vector2::new(40.0, 40.0), 0.0, false, false, Point2::new(830.0, 210.0))
+            .close();
+
+        Self {
+            star: star(Point2::new(250.0, 220.0), 100.0),
+            heart,
+            badge,
+        }
+    }
+
+    fn handle_input(&mut self, _event: WindowEvent) {
+
+    }
+
+    fn update(&mut self, _delta_t: f32) {
+
+    }
+
+    fn resize(&mut self, _size: WindowSize) {
+
+    }
+
+    fn draw<'draw, R: Renderer<'draw>>(&'draw mut self, mut renderer: R) {
+        // Clear the screen
+        renderer.add(RenderCommand::Clear(
+            Colour{
+                r: 0.1,
+                g: 0.05,
+                b: 0.15,
+                a: 1.0,
+            }
+        ));
+
+        // Filling the star with the even odd rule leaves the overlapping middle empty
+        renderer.add(RenderCommand::FillPath(FillPathDescription{
+            path: &self.star,
+            colour: Colour{
+                r: 1.0,
+                g: 0.8,
+                b: 0.2,
+                a: 1.0,
+            },
+            fill_rule: FillRule::EvenOdd,
+            .. FillPathDescription::default()
+        }));
+
+        renderer.add(RenderCommand::StrokePath(StrokePathDescription{
+            path: &self.star,
+            width: 3.0,
+            colour: Colour::WHITE,
+            join: LineJoin::Round,
+            .. StrokePathDescription::default()
+        }));
+
+        // The heart filled and outlined
+        renderer.add(RenderCommand::FillPath(FillPathDescription{
+            path: &self.heart,
+            colour: Colour{
+                r: 0.9,
+                g: 0.1,
+                b: 0.3,
+                a: 1.0,
+            },
+            .. FillPathDescription::default()
+        }));
+
+        renderer.add(RenderCommand::StrokePath(StrokePathDescription{
+            path: &self.heart,
+            width: 4.0,
+            colour: Colour{
+                r: 1.0,
+                g: 0.6,
+                b: 0.7,
+                a: 1.0,
+            },
+            join: LineJoin::Round,
+            .. StrokePathDescription::default()
+        }));
+
+        // The badge with its hole
+        renderer.add(RenderCommand::FillPath(FillPathDescription{
+            path: &self.badge,
+            colour: Colour{
+                r: 0.2,
+                g: 0.6,
+                b: 1.0,
+                a: 1.0,
+            },
+            fill_rule: FillRule::NonZero,
+            .. FillPathDescription::default()
+        }));
+    }
+}
+
+// Start the app
+fn main() {
+    aurora::run::<Paths>("Paths Example");
+}
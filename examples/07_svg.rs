@@ -0,0 +1,106 @@
+use aurora::{
+    BaseApp,
+    Colour,
+    GeometryManager,
+    Point2,
+    SvgImage,
+    TextureManager,
+    MaterialManager,
+    Renderer,
+    RenderCommand,
+    WindowEvent,
+    WindowSize,
+};
+
+// A small icon as a designer might export it
+const ICON: &str = r##"
+<svg xmlns="http://www.w3.org/2000/svg" width="64" height="64" viewBox="0 0 32 32">
+    <defs>
+        <linearGradient id="sky" x1="0" y1="0" x2="0" y2="1">
+            <stop offset="0" stop-color="#4fc3f7"/>
+            <stop offset="1" stop-color="#1a237e"/>
+        </linearGradient>
+        <radialGradient id="sun" cx="0.5" cy="0.5" r="0.5">
+            <stop offset="0" stop-color="#fffde7"/>
+            <stop offset="0.6" stop-color="#ffeb3b"/>
+            <stop offset="1" stop-color="#ff9800" stop-opacity="0"/>
+        </radialGradient>
+    </defs>
+    <rect x="1" y="1" width="30" height="30" rx="6" fill="url(#sky)" stroke="#0d1b4a" stroke-width="1"/>
+    <circle cx="22" cy="10" r="6" fill="url(#sun)"/>
+    <g transform="translate(0 4)">
+        <path d="M2 26 L11 14 L17 21 L21 17 L30 26 Z" fill="#2e7d32"/>
+        <polyline points="9,17 11,14 13,17" fill="none" stroke="white" stroke-width="1.5" stroke-linejoin="round" stroke-linecap="round"/>
+    </g>
+    <ellipse cx="8" cy="8" rx="4" ry="2" style="fill: white; opacity: 0.8"/>
+</svg>
+"##;
+
+// Base structure for the application
+struct Svg {
+    icons: Vec<SvgImage>,
+}
+
+// Implement the trait for the main application loop
+impl BaseApp for Svg {
+    // Path fills rely on multisampling for smooth edges
+    fn sample_count() -> u32 {
+        4
+    }
+
+    fn init(
+        _window_size: WindowSize,
+        _geometry_manager: &mut GeometryManager,
+        _texture_manager: &mut TextureManager,
+        _material_manager: &mut MaterialManager,
+    ) -> Self {
+        // The same icon at a few sizes, staying sharp as it grows
+        let mut x = 50.0;
+        let icons = [1.0, 2.0, 4.0, 8.0].iter()
+            .map(|&scale| {
+                let mut icon = SvgImage::parse(ICON)
+                    .unwrap_or_else(|error| panic!("{}", error));
+                icon.set_placement(Point2::new(x, 100.0), scale);
+                x += icon.size().x * scale + 30.0;
+                icon
+            })
+            .collect();
+
+        Self {
+            icons,
+        }
+    }
+
+    fn handle_input(&mut self, _event: WindowEvent) {
+
+    }
+
+    fn update(&mut self, _delta_t: f32) {
+
+    }
+
+    fn resize(&mut self, _size: WindowSize) {
+
+    }
+
+    fn draw<'draw, R: Renderer<'draw>>(&'draw mut self, mut renderer: R) {
+        // Clear the screen
+        renderer.add(RenderCommand::Clear(
+            Colour{
+                r: 0.9,
+                g: 0.9,
+                b: 0.9,
+                a: 1.0,
+            }
+        ));
+
+        for icon in &self.icons {
+            icon.draw(&mut renderer);
+        }
+    }
+}
+
+// Start the app
+fn main() {
+    aurora::run::<Svg>("SVG Example");
+}
@@ -0,0 +1,129 @@
+use aurora::{
+    BaseApp,
+    Colour,
+    GeometryManager,
+    Point2,
+    TwoDDescription,
+    TwoDStyle,
+    TwoDTypes,
+    Vector2,
+    TextureManager,
+    MaterialManager,
+    Renderer,
+    RenderCommand,
+    WindowEvent,
+    WindowSize,
+};
+
+// Base structure for the application
+struct Shapes {
+    rotation: f32,
+}
+
+// Every shape, with a stretched size to show which ones fill it
+fn shapes() -> Vec<(TwoDTypes, Vector2)> {
+    let square = Vector2::new(70.0, 70.0);
+    let wide = Vector2::new(110.0, 70.0);
+
+    vec![
+        (TwoDTypes::Rectangle, wide),
+        (TwoDTypes::Circle, wide),
+        (TwoDTypes::Triangle, square),
+        (TwoDTypes::Hexagon, square),
+        (TwoDTypes::Ellipse, wide),
+        (TwoDTypes::Capsule, wide),
+        (TwoDTypes::Ring{thickness: 0.3}, square),
+        (TwoDTypes::Pie{start_angle: 0.0, end_angle: 4.5, inner_radius: 0.0}, square),
+        (TwoDTypes::Pie{start_angle: -2.5, end_angle: -0.6, inner_radius: 0.6}, square),
+        (TwoDTypes::Star{points: 5, inner_radius: 0.4}, square),
+        (TwoDTypes::Polygon{sides: 5}, square),
+        (TwoDTypes::Rhombus, wide),
+        (TwoDTypes::Arrow{shaft_width: 0.4, head_length: 0.4}, wide),
+        (TwoDTypes::Cross{thickness: 0.25}, square),
+        (TwoDTypes::RoundedRectangle{radii: [0.5, 0.0, 0.5, 0.0]}, wide),
+    ]
+}
+
+// Implement the trait for the main application loop
+impl BaseApp for Shapes {
+    fn init(
+        _window_size: WindowSize,
+        _geometry_manager: &mut GeometryManager,
+        _texture_manager: &mut TextureManager,
+        _material_manager: &mut MaterialManager,
+    ) -> Self {
+        Self {
+            rotation: 0.0,
+        }
+    }
+
+    fn handle_input(&mut self, _event: WindowEvent) {
+
+    }
+
+    fn update(&mut self, delta_t: f32) {
+        self.rotation += delta_t * 0.5;
+    }
+
+    fn resize(&mut self, _size: WindowSize) {
+
+    }
+
+    fn draw<'draw, R: Renderer<'draw>>(&'draw mut self, mut renderer: R) {
+        // Clear the screen
+        renderer.add(RenderCommand::Clear(
+            Colour{
+                r: 0.1,
+                g: 0.05,
+                b: 0.15,
+                a: 1.0,
+            }
+        ));
+
+        // A row of filled shapes above the same shapes outlined, slowly spinning
+        for (i, (shape, size)) in shapes().into_iter().enumerate() {
+            let column = (i % 5) as f32;
+            let row = (i / 5) as f32;
+            let position = Point2::new(30.0 + column * 150.0, 20.0 + row * 180.0);
+
+            renderer.add(RenderCommand::Draw2D(TwoDDescription{
+                position,
+                size,
+                colour: Colour{
+                    r: 0.2 + column as f64 * 0.2,
+                    g: 0.8 - row as f64 * 0.3,
+                    b: 0.6,
+                    a: 1.0,
+                },
+                style: TwoDStyle::FilledWithBorder,
+                border_colour: Colour::WHITE,
+                line_width: 3.0,
+                corner_radius: 0.3,
+                rotation: self.rotation,
+                shape,
+                .. TwoDDescription::default()
+            }));
+        }
+
+        for (i, (shape, size)) in shapes().into_iter().enumerate() {
+            let column = (i % 5) as f32;
+            let row = (i / 5) as f32;
+
+            renderer.add(RenderCommand::Draw2D(TwoDDescription{
+                position: Point2::new(30.0 + column * 150.0, 105.0 + row * 180.0),
+                size,
+                style: TwoDStyle::Outlined,
+                border_colour: Colour::WHITE,
+                line_width: 1.5,
+                corner_radius: 0.3,
+                shape,
+                .. TwoDDescription::default()
+            }));
+        }
+    }
+}
+
+// Start the app
+fn main() {
+    aurora::run::<Shapes>("Shapes Example");
+}
@@ -0,0 +1,148 @@
+use aurora::{
+    BaseApp,
+    Colour,
+    ColourStop,
+    Fill,
+    GeometryManager,
+    Point2,
+    TwoDDescription,
+    TwoDStyle,
+    TwoDTypes,
+    Vector2,
+    TextureManager,
+    MaterialManager,
+    Renderer,
+    RenderCommand,
+    WindowEvent,
+    WindowSize,
+};
+
+// Base structure for the application
+struct Gradients {
+    panel: Vec<ColourStop>,
+    glow: Vec<ColourStop>,
+    rainbow: Vec<ColourStop>,
+    spin: f32,
+}
+
+// Implement the trait for the main application loop
+impl BaseApp for Gradients {
+    fn sample_count() -> u32 {
+        4
+    }
+
+    fn init(
+        _window_size: WindowSize,
+        _geometry_manager: &mut GeometryManager,
+        _texture_manager: &mut TextureManager,
+        _material_manager: &mut MaterialManager,
+    ) -> Self {
+        let panel = vec![
+            ColourStop{offset: 0.0, colour: Colour{r: 0.25, g: 0.3, b: 0.45, a: 1.0}},
+            ColourStop{offset: 1.0, colour: Colour{r: 0.05, g: 0.05, b: 0.12, a: 1.0}},
+        ];
+
+        let glow = vec![
+            ColourStop{offset: 0.0, colour: Colour{r: 1.0, g: 0.95, b: 0.6, a: 1.0}},
+            ColourStop{offset: 0.5, colour: Colour{r: 1.0, g: 0.4, b: 0.0, a: 0.8}},
+            ColourStop{offset: 1.0, colour: Colour{r: 1.0, g: 0.0, b: 0.0, a: 0.0}},
+        ];
+
+        let rainbow = [Colour::RED, Colour::GREEN, Colour::BLUE, Colour::RED].iter()
+            .enumerate()
+            .map(|(i, &colour)| ColourStop{offset: i as f32 / 3.0, colour})
+            .collect();
+
+        Self {
+            panel,
+            glow,
+            rainbow,
+            spin: 0.0,
+        }
+    }
+
+    fn handle_input(&mut self, _event: WindowEvent) {
+
+    }
+
+    fn update(&mut self, delta_t: f32) {
+        self.spin += delta_t;
+    }
+
+    fn resize(&mut self, _size: WindowSize) {
+
+    }
+
+    fn draw<'draw, R: Renderer<'draw>>(&'draw mut self, mut renderer: R) {
+        // Clear the screen
+        renderer.add(RenderCommand::Clear(
+            Colour{
+                r: 0.02,
+                g: 0.02,
+                b: 0.05,
+                a: 1.0,
+            }
+        ));
+
+        // A panel shaded from top to bottom
+        renderer.add(RenderCommand::Draw2D(TwoDDescription{
+            position: Point2::new(50.0, 50.0),
+            size: Vector2::new(300.0, 200.0),
+            fill: Fill::LinearGradient{
+                start: Point2::new(0.0, 0.0),
+                end: Point2::new(0.0, 1.0),
+                stops: &self.panel,
+            },
+            style: TwoDStyle::FilledWithBorder,
+            border_colour: Colour{r: 0.5, g: 0.6, b: 0.8, a: 1.0},
+            line_width: 2.0,
+            corner_radius: 0.1,
+            .. TwoDDescription::default()
+        }));
+
+        // A glowing sun fading out to nothing
+        renderer.add(RenderCommand::Draw2D(TwoDDescription{
+            position: Point2::new(450.0, 50.0),
+            size: Vector2::new(200.0, 200.0),
+            fill: Fill::RadialGradient{
+                centre: Point2::new(0.5, 0.5),
+                radius: 0.5,
+                stops: &self.glow,
+            },
+            shape: TwoDTypes::Circle,
+            .. TwoDDescription::default()
+        }));
+
+        // A spinning colour wheel
+        renderer.add(RenderCommand::Draw2D(TwoDDescription{
+            position: Point2::new(100.0, 320.0),
+            size: Vector2::new(200.0, 200.0),
+            fill: Fill::ConicGradient{
+                centre: Point2::new(0.5, 0.5),
+                start_angle: self.spin,
+                stops: &self.rainbow,
+            },
+            shape: TwoDTypes::Ring{thickness: 0.4},
+            .. TwoDDescription::default()
+        }));
+
+        // A gradient that turns with its shape
+        renderer.add(RenderCommand::Draw2D(TwoDDescription{
+            position: Point2::new(450.0, 320.0),
+            size: Vector2::new(200.0, 200.0),
+            fill: Fill::LinearGradient{
+                start: Point2::new(0.0, 0.0),
+                end: Point2::new(1.0, 1.0),
+                stops: &self.rainbow,
+            },
+            shape: TwoDTypes::Star{points: 6, inner_radius: 0.5},
+            rotation: self.spin * 0.3,
+            .. TwoDDescription::default()
+        }));
+    }
+}
+
+// Start the app
+fn main() {
+    aurora::run::<Gradients>("Gradients Example");
+}
@@ -0,0 +1,124 @@
+use aurora::{
+    BaseApp,
+    Colour,
+    GeometryManager,
+    Glow,
+    Point2,
+    Shadow,
+    TwoDDescription,
+    TwoDStyle,
+    TwoDTypes,
+    Vector2,
+    TextureManager,
+    MaterialManager,
+    Renderer,
+    RenderCommand,
+    WindowEvent,
+    WindowSize,
+};
+
+// Base structure for the application
+struct Effects {
+    time: f32,
+}
+
+// Implement the trait for the main application loop
+impl BaseApp for Effects {
+    fn sample_count() -> u32 {
+        4
+    }
+
+    fn init(
+        _window_size: WindowSize,
+        _geometry_manager: &mut GeometryManager,
+        _texture_manager: &mut TextureManager,
+        _material_manager: &mut MaterialManager,
+    ) -> Self {
+        Self {
+            time: 0.0,
+        }
+    }
+
+    fn handle_input(&mut self, _event: WindowEvent) {
+
+    }
+
+    fn update(&mut self, delta_t: f32) {
+        self.time += delta_t;
+    }
+
+    fn resize(&mut self, _size: WindowSize) {
+
+    }
+
+    fn draw<'draw, R: Renderer<'draw>>(&'draw mut self, mut renderer: R) {
+        // Clear the screen
+        renderer.add(RenderCommand::Clear(
+            Colour{
+                r: 0.85,
+                g: 0.87,
+                b: 0.9,
+                a: 1.0,
+            }
+        ));
+
+        // Cards lifted off the page by softer and further shadows
+        for i in 0..3 {
+            let lift = (i + 1) as f32;
+
+            renderer.add(RenderCommand::Draw2D(TwoDDescription{
+                position: Point2::new(60.0 + i as f32 * 240.0, 60.0),
+                size: Vector2::new(200.0, 140.0),
+                colour: Colour::WHITE,
+                corner_radius: 0.1,
+                shadow: Some(Shadow{
+                    offset: Vector2::new(0.0, 3.0 * lift),
+                    blur: 6.0 * lift,
+                    colour: Colour{r: 0.0, g: 0.0, b: 0.0, a: 0.35},
+                }),
+                .. TwoDDescription::default()
+            }));
+        }
+
+        // A button glowing in and out
+        let pulse = 0.5 + 0.5 * (self.time * 3.0).sin();
+        renderer.add(RenderCommand::Draw2D(TwoDDescription{
+            position: Point2::new(100.0, 300.0),
+            size: Vector2::new(240.0, 70.0),
+            colour: Colour{r: 0.1, g: 0.5, b: 1.0, a: 1.0},
+            style: TwoDStyle::FilledWithBorder,
+            border_colour: Colour{r: 0.6, g: 0.8, b: 1.0, a: 1.0},
+            line_width: 2.0,
+            shape: TwoDTypes::Capsule,
+            glow: Some(Glow{
+                radius: 10.0 + 15.0 * pulse,
+                colour: Colour{r: 0.2, g: 0.6, b: 1.0, a: 0.8},
+            }),
+            .. TwoDDescription::default()
+        }));
+
+        // A turning star with both, the shadow stays below as it turns
+        renderer.add(RenderCommand::Draw2D(TwoDDescription{
+            position: Point2::new(480.0, 260.0),
+            size: Vector2::new(200.0, 200.0),
+            colour: Colour{r: 1.0, g: 0.8, b: 0.1, a: 1.0},
+            shape: TwoDTypes::Star{points: 5, inner_radius: 0.5},
+            rotation: self.time * 0.5,
+            shadow: Some(Shadow{
+                offset: Vector2::new(8.0, 12.0),
+                blur: 10.0,
+                colour: Colour{r: 0.0, g: 0.0, b: 0.0, a: 0.4},
+            }),
+            glow: Some(Glow{
+                radius: 20.0,
+                colour: Colour{r: 1.0, g: 0.9, b: 0.3, a: 0.6},
+            }),
+            .. TwoDDescription::default()
+        }));
+    }
+}
+
+// Start the app
+fn main() {
+    aurora::run::<Effects>("Effects Example");
+}
@@ -0,0 +1,112 @@
+use aurora::{
+    BaseApp,
+    BlendMode,
+    Colour,
+    GeometryManager,
+    LineDescription,
+    Point2,
+    TwoDDescription,
+    TwoDTypes,
+    Vector2,
+    TextureManager,
+    MaterialManager,
+    Renderer,
+    RenderCommand,
+    WindowEvent,
+    WindowSize,
+};
+
+// Base structure for the application
+struct Blending {
+    time: f32,
+}
+
+// Implement the trait for the main application loop
+impl BaseApp for Blending {
+    fn sample_count() -> u32 {
+        4
+    }
+
+    fn init(
+        _window_size: WindowSize,
+        _geometry_manager: &mut GeometryManager,
+        _texture_manager: &mut TextureManager,
+        _material_manager: &mut MaterialManager,
+    ) -> Self {
+        Self {
+            time: 0.0,
+        }
+    }
+
+    fn handle_input(&mut self, _event: WindowEvent) {
+
+    }
+
+    fn update(&mut self, delta_t: f32) {
+        self.time += delta_t;
+    }
+
+    fn resize(&mut self, _size: WindowSize) {
+
+    }
+
+    fn draw<'draw, R: Renderer<'draw>>(&'draw mut self, mut renderer: R) {
+        // Clear the screen
+        renderer.add(RenderCommand::Clear(
+            Colour{
+                r: 0.1,
+                g: 0.1,
+                b: 0.1,
+                a: 1.0,
+            }
+        ));
+
+        // A light band across the middle to show the modes over both dark and light
+        renderer.add(RenderCommand::Draw2D(TwoDDescription{
+            position: Point2::new(0.0, 200.0),
+            size: Vector2::new(800.0, 120.0),
+            colour: Colour{r: 0.8, g: 0.8, b: 0.8, a: 1.0},
+            .. TwoDDescription::default()
+        }));
+
+        // Three overlapping circles for each mode
+        let colours = [Colour::RED, Colour::GREEN, Colour::BLUE];
+        for (column, blend_mode) in BlendMode::ALL.iter().enumerate() {
+            let centre = Point2::new(70.0 + column as f32 * 130.0, 260.0);
+
+            for (i, colour) in colours.iter().enumerate() {
+                let angle = i as f32 * std::f32::consts::PI * 2.0 / 3.0 - std::f32::consts::FRAC_PI_2;
+
+                renderer.add(RenderCommand::Draw2D(TwoDDescription{
+                    position: Point2::new(centre.x - 40.0 + 25.0 * angle.cos(), centre.y - 40.0 + 25.0 * angle.sin()),
+                    size: Vector2::new(80.0, 80.0),
+                    colour: Colour{a: 0.7, .. *colour},
+                    shape: TwoDTypes::Circle,
+                    blend_mode: *blend_mode,
+                    .. TwoDDescription::default()
+                }));
+            }
+        }
+
+        // Sparks adding up to white where they cross
+        for i in 0..40 {
+            let angle = i as f32 * 0.157 + self.time * 0.5;
+            let centre = Point2::new(400.0, 480.0);
+
+            renderer.add(RenderCommand::DrawLine(LineDescription{
+                start: centre,
+                end: Point2::new(centre.x + 200.0 * angle.cos(), centre.y + 100.0 * angle.sin()),
+                width: 4.0,
+                colour: Colour{r: 1.0, g: 0.5, b: 0.1, a: 0.3},
+                end_colour: Some(Colour{r: 1.0, g: 0.2, b: 0.0, a: 0.0}),
+                blend_mode: BlendMode::Additive,
+                .. LineDescription::default()
+            }));
+        }
+    }
+}
+
+// Start the app
+fn main() {
+    aurora::run::<Blending>("Blend Modes Example");
+}
@@ -0,0 +1,195 @@
+use aurora::{
+    BaseApp,
+    Colour,
+    GeometryManager,
+    MaterialHandle,
+    Point2,
+    TwoDDescription,
+    TwoDStyle,
+    TwoDTypes,
+    Vector2,
+    TextureManager,
+    MaterialManager,
+    Renderer,
+    RenderCommand,
+    WindowEvent,
+    WindowSize,
+};
+
+// Ripples the first texture, with the time in the first draw param
+const WATER: &str = "
+fn material(input: MaterialInput) -> vec4<f32> {
+    let t = input.params.x;
+    let ripple = vec2<f32>(
+        sin(input.uv.y * 20.0 + t * 3.0),
+        cos(input.uv.x * 16.0 + t * 2.0)
+    ) * 0.01;
+
+    let water = textureSample(t_Material0, s_Material, input.uv * 2.0 + ripple + vec2<f32>(t * 0.05, 0.0));
+    let tint = material_uniform.params[0];
+    return vec4<f32>(water.rgb * tint.rgb, 1.0);
+}
+";
+
+// Burns away through noise as the first draw param goes from 0 to 1, with a glowing edge
+const DISSOLVE: &str = "
+fn hash(p: vec2<f32>) -> f32 {
+    return fract(sin(dot(p, vec2<f32>(127.1, 311.7))) * 43758.5453);
+}
+
+fn noise(p: vec2<f32>) -> f32 {
+    let i = floor(p);
+    let f = fract(p);
+    let u = f * f * (vec2<f32>(3.0) - 2.0 * f);
+    return mix(
+        mix(hash(i), hash(i + vec2<f32>(1.0, 0.0)), u.x),
+        mix(hash(i + vec2<f32>(0.0, 1.0)), hash(i + vec2<f32>(1.0, 1.0)), u.x),
+        u.y
+    );
+}
+
+fn material(input: MaterialInput) -> vec4<f32> {
+    let amount = input.params.x;
+    let n = noise(input.uv * 8.0) * 0.6 + noise(input.uv * 24.0) * 0.4;
+    let edge = material_uniform.params[0];
+
+    if (n < amount) {
+        return vec4<f32>(0.0);
+    }
+    if (n < amount + 0.05) {
+        return edge;
+    }
+    return input.colour;
+}
+";
+
+// Scrolling scan lines and flicker over the shape's colour
+const HOLOGRAM: &str = "
+fn material(input: MaterialInput) -> vec4<f32> {
+    let t = input.params.x;
+    let lines = 0.6 + 0.4 * sin(input.position.y * 1.5 + t * 10.0);
+    let flicker = 0.85 + 0.15 * sin(t * 37.0) * sin(t * 23.0);
+    let rim = clamp(1.0 + input.distance / 12.0, 0.0, 1.0);
+
+    let strength = clamp(lines * flicker * 0.6 + rim * 0.4, 0.0, 1.0);
+    return input.colour * strength;
+}
+";
+
+// Base structure for the application
+struct Materials {
+    water: MaterialHandle,
+    dissolve: MaterialHandle,
+    hologram: MaterialHandle,
+    time: f32,
+}
+
+// Implement the trait for the main application loop
+impl BaseApp for Materials {
+    fn sample_count() -> u32 {
+        4
+    }
+
+    fn init(
+        _window_size: WindowSize,
+        _geometry_manager: &mut GeometryManager,
+        texture_manager: &mut TextureManager,
+        material_manager: &mut MaterialManager,
+    ) -> Self {
+        let squares_texture = texture_manager.create_texture_from_file("resources/texture/Squares.png");
+
+        let water = material_manager.create_material(WATER, &[squares_texture])
+            .unwrap_or_else(|error| panic!("{}", error));
+        material_manager.set_params(water, [
+            [0.4, 0.7, 1.0, 1.0],
+            [0.0; 4],
+            [0.0; 4],
+            [0.0; 4],
+        ]);
+
+        let dissolve = material_manager.create_material(DISSOLVE, &[])
+            .unwrap_or_else(|error| panic!("{}", error));
+        material_manager.set_params(dissolve, [
+            [1.0, 0.5, 0.0, 1.0],
+            [0.0; 4],
+            [0.0; 4],
+            [0.0; 4],
+        ]);
+
+        let hologram = material_manager.create_material(HOLOGRAM, &[])
+            .unwrap_or_else(|error| panic!("{}", error));
+
+        Self {
+            water,
+            dissolve,
+            hologram,
+            time: 0.0,
+        }
+    }
+
+    fn handle_input(&mut self, _event: WindowEvent) {
+
+    }
+
+    fn update(&mut self, delta_t: f32) {
+        self.time += delta_t;
+    }
+
+    fn resize(&mut self, _size: WindowSize) {
+
+    }
+
+    fn draw<'draw, R: Renderer<'draw>>(&'draw mut self, mut renderer: R) {
+        // Clear the screen
+        renderer.add(RenderCommand::Clear(
+            Colour{
+                r: 0.05,
+                g: 0.05,
+                b: 0.1,
+                a: 1.0,
+            }
+        ));
+
+        // A rounded pool of water
+        renderer.add(RenderCommand::Draw2D(TwoDDescription{
+            position: Point2::new(50.0, 50.0),
+            size: Vector2::new(300.0, 200.0),
+            material: Some(self.water),
+            material_params: [self.time, 0.0, 0.0, 0.0],
+            style: TwoDStyle::FilledWithBorder,
+            border_colour: Colour{r: 0.8, g: 0.9, b: 1.0, a: 1.0},
+            line_width: 3.0,
+            corner_radius: 0.2,
+            .. TwoDDescription::default()
+        }));
+
+        // A card burning away and back again
+        let burn = 0.5 - 0.5 * (self.time * 0.8).cos();
+        renderer.add(RenderCommand::Draw2D(TwoDDescription{
+            position: Point2::new(450.0, 50.0),
+            size: Vector2::new(250.0, 200.0),
+            colour: Colour{r: 0.9, g: 0.85, b: 0.7, a: 1.0},
+            material: Some(self.dissolve),
+            material_params: [burn * 1.1, 0.0, 0.0, 0.0],
+            corner_radius: 0.05,
+            .. TwoDDescription::default()
+        }));
+
+        // A hologram star glowing in the dark
+        renderer.add(RenderCommand::Draw2D(TwoDDescription{
+            position: Point2::new(275.0, 320.0),
+            size: Vector2::new(250.0, 250.0),
+            colour: Colour{r: 0.2, g: 1.0, b: 0.9, a: 1.0},
+            material: Some(self.hologram),
+            material_params: [self.time, 0.0, 0.0, 0.0],
+            shape: TwoDTypes::Star{points: 5, inner_radius: 0.45},
+            rotation: self.time * 0.3,
+            .. TwoDDescription::default()
+        }));
+    }
+}
+
+// Start the app
+fn main() {
+    aurora::run::<Materials>("Materials Example");
+}
@@ -0,0 +1,176 @@
+use aurora::{
+    BaseApp,
+    Colour,
+    GeometryManager,
+    MaskMode,
+    Point2,
+    TextureHandle,
+    TwoDDescription,
+    TwoDStyle,
+    TwoDTypes,
+    Vector2,
+    TextureManager,
+    MaterialManager,
+    Renderer,
+    RenderCommand,
+    WindowEvent,
+    WindowSize,
+};
+
+// Base structure for the application
+struct Masks {
+    squares_texture: TextureHandle,
+    time: f32,
+}
+
+// Implement the trait for the main application loop
+impl BaseApp for Masks {
+    // Mask edges are hard, multisampling smooths them
+    fn sample_count() -> u32 {
+        4
+    }
+
+    fn init(
+        _window_size: WindowSize,
+        _geometry_manager: &mut GeometryManager,
+        texture_manager: &mut TextureManager,
+        _material_manager: &mut MaterialManager,
+    ) -> Self {
+        let squares_texture = texture_manager.create_texture_from_file("resources/texture/Squares.png");
+
+        Self {
+            squares_texture,
+            time: 0.0,
+        }
+    }
+
+    fn handle_input(&mut self, _event: WindowEvent) {
+
+    }
+
+    fn update(&mut self, delta_t: f32) {
+        self.time += delta_t;
+    }
+
+    fn resize(&mut self, _size: WindowSize) {
+
+    }
+
+    fn draw<'draw, R: Renderer<'draw>>(&'draw mut self, mut renderer: R) {
+        // Clear the screen
+        renderer.add(RenderCommand::Clear(
+            Colour{
+                r: 0.9,
+                g: 0.9,
+                b: 0.92,
+                a: 1.0,
+            }
+        ));
+
+        // An avatar cut to a circle, with a ring around it
+        renderer.add(RenderCommand::BeginMask);
+        renderer.add(RenderCommand::Draw2D(TwoDDescription{
+            position: Point2::new(60.0, 60.0),
+            size: Vector2::new(160.0, 160.0),
+            colour: Colour::WHITE,
+            shape: TwoDTypes::Circle,
+            .. TwoDDescription::default()
+        }));
+        renderer.add(RenderCommand::EndMask(MaskMode::Inside));
+        renderer.add(RenderCommand::Draw2D(TwoDDescription{
+            position: Point2::new(60.0, 60.0),
+            size: Vector2::new(160.0, 160.0),
+            colour: Colour::WHITE,
+            texture: Some(self.squares_texture),
+            .. TwoDDescription::default()
+        }));
+        renderer.add(RenderCommand::ClearMask);
+
+        renderer.add(RenderCommand::Draw2D(TwoDDescription{
+            position: Point2::new(56.0, 56.0),
+            size: Vector2::new(168.0, 168.0),
+            colour: Colour{r: 0.2, g: 0.5, b: 1.0, a: 1.0},
+            shape: TwoDTypes::Ring{thickness: 0.05},
+            .. TwoDDescription::default()
+        }));
+
+        // A reveal transition - a growing circle shows the blue scene over the orange one
+        let reveal = 0.5 - 0.5 * (self.time * 0.8).cos();
+        let radius = 10.0 + reveal * 250.0;
+        let centre = Point2::new(560.0, 140.0);
+
+        renderer.add(RenderCommand::BeginMask);
+        renderer.add(RenderCommand::Draw2D(TwoDDescription{
+            position: Point2::new(centre.x - radius, centre.y - radius),
+            size: Vector2::new(radius * 2.0, radius * 2.0),
+            colour: Colour::WHITE,
+            shape: TwoDTypes::Circle,
+            .. TwoDDescription::default()
+        }));
+
+        for (mode, colour) in [
+            (MaskMode::Outside, Colour{r: 1.0, g: 0.6, b: 0.2, a: 1.0}),
+            (MaskMode::Inside, Colour{r: 0.2, g: 0.4, b: 0.9, a: 1.0}),
+        ] {
+            renderer.add(RenderCommand::EndMask(mode));
+            renderer.add(RenderCommand::Draw2D(TwoDDescription{
+                position: Point2::new(420.0, 40.0),
+                size: Vector2::new(280.0, 200.0),
+                colour,
+                .. TwoDDescription::default()
+            }));
+            renderer.add(RenderCommand::Draw2D(TwoDDescription{
+                position: Point2::new(500.0, 80.0),
+                size: Vector2::new(120.0, 120.0),
+                colour: Colour::WHITE,
+                shape: TwoDTypes::Star{points: 5, inner_radius: 0.5},
+                rotation: if mode == MaskMode::Inside { self.time } else { -self.time },
+                .. TwoDDescription::default()
+            }));
+        }
+        renderer.add(RenderCommand::ClearMask);
+
+        // A rounded scroll view, with rows sliding under its corners
+        let view_position = Point2::new(200.0, 300.0);
+        let view_size = Vector2::new(400.0, 260.0);
+
+        renderer.add(RenderCommand::Draw2D(TwoDDescription{
+            position: view_position,
+            size: view_size,
+            colour: Colour::WHITE,
+            style: TwoDStyle::FilledWithBorder,
+            border_colour: Colour{r: 0.6, g: 0.6, b: 0.65, a: 1.0},
+            line_width: 2.0,
+            corner_radius: 0.15,
+            .. TwoDDescription::default()
+        }));
+
+        renderer.add(RenderCommand::BeginMask);
+        renderer.add(RenderCommand::Draw2D(TwoDDescription{
+            position: Point2::new(view_position.x + 2.0, view_position.y + 2.0),
+            size: Vector2::new(view_size.x - 4.0, view_size.y - 4.0),
+            colour: Colour::WHITE,
+            corner_radius: 0.15,
+            .. TwoDDescription::default()
+        }));
+        renderer.add(RenderCommand::EndMask(MaskMode::Inside));
+
+        let row_height = 50.0;
+        let scroll = (self.time * 40.0) % (row_height * 2.0);
+        for row in 0..8 {
+            let shade = if row % 2 == 0 { 0.75 } else { 0.85 };
+            renderer.add(RenderCommand::Draw2D(TwoDDescription{
+                position: Point2::new(view_position.x, view_position.y - scroll + row as f32 * row_height),
+                size: Vector2::new(view_size.x, row_height - 6.0),
+                colour: Colour{r: shade, g: shade, b: 0.95, a: 1.0},
+                .. TwoDDescription::default()
+            }));
+        }
+        renderer.add(RenderCommand::ClearMask);
+    }
+}
+
+// Start the app
+fn main() {
+    aurora::run::<Masks>("Masks Example");
+}
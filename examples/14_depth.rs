@@ -0,0 +1,121 @@
+use aurora::{
+    BaseApp,
+    Colour,
+    GeometryManager,
+    LineDescription,
+    Point2,
+    TextureHandle,
+    TwoDDescription,
+    TwoDTypes,
+    Vector2,
+    TextureManager,
+    MaterialManager,
+    Renderer,
+    RenderCommand,
+    WindowEvent,
+    WindowSize,
+};
+
+// Number of sprites going round the carousel
+const N_SPRITES: usize = 24;
+
+// Base structure for the application
+struct Depth {
+    squares_texture: TextureHandle,
+    time: f32,
+}
+
+// Implement the trait for the main application loop
+impl BaseApp for Depth {
+    fn sample_count() -> u32 {
+        4
+    }
+
+    fn init(
+        _window_size: WindowSize,
+        _geometry_manager: &mut GeometryManager,
+        texture_manager: &mut TextureManager,
+        _material_manager: &mut MaterialManager,
+    ) -> Self {
+        let squares_texture = texture_manager.create_texture_from_file("resources/texture/Squares.png");
+
+        Self {
+            squares_texture,
+            time: 0.0,
+        }
+    }
+
+    fn handle_input(&mut self, _event: WindowEvent) {
+
+    }
+
+    fn update(&mut self, delta_t: f32) {
+        self.time += delta_t;
+    }
+
+    fn resize(&mut self, _size: WindowSize) {
+
+    }
+
+    fn draw<'draw, R: Renderer<'draw>>(&'draw mut self, mut renderer: R) {
+        // Clear the screen
+        renderer.add(RenderCommand::Clear(
+            Colour{
+                r: 0.1,
+                g: 0.1,
+                b: 0.15,
+                a: 1.0,
+            }
+        ));
+
+        // A carousel of sprites, submitted in the same order every frame however they turn.
+        // The depth puts the ones at the front over the ones behind.
+        let centre = Point2::new(400.0, 300.0);
+        for i in 0..N_SPRITES {
+            let angle = self.time * 0.5 + i as f32 * std::f32::consts::TAU / N_SPRITES as f32;
+            let nearness = 0.5 + 0.5 * angle.sin();
+            let size = 50.0 + 60.0 * nearness;
+            let position = Point2::new(
+                centre.x + angle.cos() * 300.0 - size / 2.0,
+                centre.y + angle.sin() * 80.0 - size / 2.0,
+            );
+
+            renderer.add(RenderCommand::Draw2D(TwoDDescription{
+                position,
+                size: Vector2::new(size, size),
+                colour: Colour::WHITE,
+                texture: if i % 2 == 0 { Some(self.squares_texture) } else { None },
+                shape: if i % 2 == 0 { TwoDTypes::Rectangle } else { TwoDTypes::Circle },
+                corner_radius: 0.1,
+                depth: Some(1.0 - nearness),
+                .. TwoDDescription::default()
+            }));
+
+            // A post holding each sprite up, going behind the sprites nearer than it
+            renderer.add(RenderCommand::DrawLine(LineDescription{
+                start: Point2::new(position.x + size / 2.0, position.y + size),
+                end: Point2::new(position.x + size / 2.0, 560.0),
+                width: 2.0 + 2.0 * nearness,
+                colour: Colour{r: 0.6, g: 0.6, b: 0.7, a: 1.0},
+                depth: Some(1.0 - nearness + 0.001),
+                .. LineDescription::default()
+            }));
+        }
+
+        // A see through pane through the middle of the carousel, submitted last but still behind
+        // the sprites in front of it
+        renderer.add(RenderCommand::Draw2D(TwoDDescription{
+            position: Point2::new(150.0, 240.0),
+            size: Vector2::new(500.0, 120.0),
+            colour: Colour{r: 0.3, g: 0.8, b: 1.0, a: 0.4},
+            corner_radius: 0.2,
+            depth: Some(0.5),
+            .. TwoDDescription::default()
+        }));
+    }
+}
+
+// Start the app
+fn main() {
+    aurora::run::<Depth>("Depth Example");
+}
@@ -0,0 +1,132 @@
+use aurora::{
+    BaseApp,
+    Colour,
+    DrawList,
+    GeometryManager,
+    LineDescription,
+    Point2,
+    Transform2D,
+    TwoDDescription,
+    TwoDTypes,
+    Vector2,
+    TextureManager,
+    MaterialManager,
+    Renderer,
+    RenderCommand,
+    WindowEvent,
+    WindowSize,
+};
+
+// Tiles along each side of the map
+const MAP_TILES: usize = 224;
+const TILE_SIZE: f32 = 16.0;
+
+// Base structure for the application
+struct DrawLists {
+    map: DrawList,
+    time: f32,
+}
+
+// Implement the trait for the main application loop
+impl BaseApp for DrawLists {
+    fn init(
+        _window_size: WindowSize,
+        _geometry_manager: &mut GeometryManager,
+        _texture_manager: &mut TextureManager,
+        _material_manager: &mut MaterialManager,
+    ) -> Self {
+        // Record the map once, around 50k tiles and a grid of roads. It is uploaded the first time
+        // it is drawn and stays on the GPU after that.
+        let mut map = DrawList::new();
+        for x in 0..MAP_TILES {
+            for y in 0..MAP_TILES {
+                // Rolling hills of grass, with water in the dips
+                let height = (x as f32 * 0.11).sin() + (y as f32 * 0.07).cos() + ((x + y) as f32 * 0.05).sin();
+                let colour = if height < -0.8 {
+                    Colour{r: 0.1, g: 0.3, b: 0.7, a: 1.0}
+                } else {
+                    let shade = (0.4 + 0.15 * height) as f64;
+                    Colour{r: 0.2 * shade, g: shade, b: 0.15 * shade, a: 1.0}
+                };
+
+                map.add(RenderCommand::Draw2D(TwoDDescription{
+                    position: Point2::new(x as f32 * TILE_SIZE, y as f32 * TILE_SIZE),
+                    size: Vector2::new(TILE_SIZE, TILE_SIZE),
+                    colour,
+                    shape: TwoDTypes::Rectangle,
+                    .. TwoDDescription::default()
+                }));
+            }
+        }
+
+        let map_size = MAP_TILES as f32 * TILE_SIZE;
+        for i in (0..MAP_TILES).step_by(16) {
+            let along = i as f32 * TILE_SIZE;
+            map.add(RenderCommand::DrawLine(LineDescription{
+                start: Point2::new(along, 0.0),
+                end: Point2::new(along, map_size),
+                width: 4.0,
+                colour: Colour{r: 0.5, g: 0.45, b: 0.4, a: 1.0},
+                .. LineDescription::default()
+            }));
+            map.add(RenderCommand::DrawLine(LineDescription{
+                start: Point2::new(0.0, along),
+                end: Point2::new(map_size, along),
+                width: 4.0,
+                colour: Colour{r: 0.5, g: 0.45, b: 0.4, a: 1.0},
+                .. LineDescription::default()
+            }));
+        }
+
+        Self {
+            map,
+            time: 0.0,
+        }
+    }
+
+    fn handle_input(&mut self, _event: WindowEvent) {
+
+    }
+
+    fn update(&mut self, delta_t: f32) {
+        self.time += delta_t;
+    }
+
+    fn resize(&mut self, _size: WindowSize) {
+
+    }
+
+    fn draw<'draw, R: Renderer<'draw>>(&'draw mut self, mut renderer: R) {
+        // Clear the screen
+        renderer.add(RenderCommand::Clear(Colour::BLACK));
+
+        // Pan and zoom round the map by moving the whole list, without touching its tiles
+        let zoom = 0.6 + 0.3 * (self.time * 0.3).sin();
+        let pan = Vector2::new(
+            -1600.0 + 1200.0 * (self.time * 0.1).cos(),
+            -1600.0 + 1200.0 * (self.time * 0.13).sin(),
+        );
+        renderer.add(RenderCommand::DrawList(
+            &self.map,
+            Some(Transform2D{
+                translation: Vector2::new(400.0, 300.0) + pan * zoom,
+                scale: Vector2::new(zoom, zoom),
+                rotation: 0.1 * (self.time * 0.2).sin(),
+            }),
+        ));
+
+        // Anything drawn after the list is drawn in place as usual
+        renderer.add(RenderCommand::Draw2D(TwoDDescription{
+            position: Point2::new(390.0, 290.0),
+            size: Vector2::new(20.0, 20.0),
+            colour: Colour::RED,
+            shape: TwoDTypes::Circle,
+            .. TwoDDescription::default()
+        }));
+    }
+}
+
+// Start the app
+fn main() {
+    aurora::run::<DrawLists>("Draw Lists Example");
+}
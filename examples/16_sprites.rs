@@ -0,0 +1,88 @@
+use aurora::{
+    BaseApp,
+    Colour,
+    GeometryManager,
+    Point2,
+    TextureHandle,
+    TwoDDescription,
+    TwoDTypes,
+    Vector2,
+    TextureManager,
+    MaterialManager,
+    Renderer,
+    RenderCommand,
+    WindowEvent,
+    WindowSize,
+};
+
+// Far more sprites than fit in the instance buffers to start with
+const N_SPRITES: usize = 100_000;
+
+// Base structure for the application
+struct Sprites {
+    squares_texture: TextureHandle,
+    window_size: WindowSize,
+    time: f32,
+}
+
+// Implement the trait for the main application loop
+impl BaseApp for Sprites {
+    fn init(
+        window_size: WindowSize,
+        _geometry_manager: &mut GeometryManager,
+        texture_manager: &mut TextureManager,
+        _material_manager: &mut MaterialManager,
+    ) -> Self {
+        let squares_texture = texture_manager.create_texture_from_file("resources/texture/Squares.png");
+
+        Self {
+            squares_texture,
+            window_size,
+            time: 0.0,
+        }
+    }
+
+    fn handle_input(&mut self, _event: WindowEvent) {
+
+    }
+
+    fn update(&mut self, delta_t: f32) {
+        self.time += delta_t;
+    }
+
+    fn resize(&mut self, size: WindowSize) {
+        self.window_size = size;
+    }
+
+    fn draw<'draw, R: Renderer<'draw>>(&'draw mut self, mut renderer: R) {
+        // Clear the screen
+        renderer.add(RenderCommand::Clear(Colour::BLACK));
+
+        // Each sprite drifts round its own small circle, spread over the window
+        let width = self.window_size.width as f32;
+        let height = self.window_size.height as f32;
+        for i in 0..N_SPRITES {
+            let seed = i as f32;
+            let home = Point2::new(
+                (seed * 0.618_034).fract() * width,
+                (seed * 0.754_877).fract() * height,
+            );
+            let angle = self.time * (1.0 + (seed * 0.377).fract()) + seed;
+
+            renderer.add(RenderCommand::Draw2D(TwoDDescription{
+                position: Point2::new(home.x + angle.cos() * 10.0, home.y + angle.sin() * 10.0),
+                size: Vector2::new(6.0, 6.0),
+                colour: Colour::WHITE,
+                texture: Some(self.squares_texture),
+                shape: TwoDTypes::Rectangle,
+                rotation: angle,
+                .. TwoDDescription::default()
+            }));
+        }
+    }
+}
+
+// Start the app
+fn main() {
+    aurora::run::<Sprites>("Sprites Example");
+}
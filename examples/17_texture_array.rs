@@ -0,0 +1,118 @@
+use std::f32::consts::TAU;
+
+use aurora::{
+    BaseApp,
+    Colour,
+    GeometryManager,
+    Point2,
+    TextureHandle,
+    TwoDDescription,
+    TwoDTypes,
+    Vector2,
+    TextureManager,
+    MaterialManager,
+    Renderer,
+    RenderCommand,
+    WindowEvent,
+    WindowSize,
+};
+
+// Every texture is the same size, so they all fit in the texture array
+const TEXTURE_SIZE: u32 = 16;
+const N_TEXTURES: usize = 64;
+const N_SPRITES: usize = 20_000;
+
+// Base structure for the application
+struct TextureArray {
+    textures: Vec<TextureHandle>,
+    window_size: WindowSize,
+    time: f32,
+}
+
+// A checkerboard in its own colour for each texture
+fn checkerboard(index: usize) -> Vec<u8> {
+    let hue = index as f32 / N_TEXTURES as f32;
+    let colour = [
+        (hue * TAU).sin() * 0.5 + 0.5,
+        (hue * TAU + TAU / 3.0).sin() * 0.5 + 0.5,
+        (hue * TAU + 2.0 * TAU / 3.0).sin() * 0.5 + 0.5,
+    ];
+
+    let mut data = Vec::with_capacity((TEXTURE_SIZE * TEXTURE_SIZE * 4) as usize);
+    for y in 0..TEXTURE_SIZE {
+        for x in 0..TEXTURE_SIZE {
+            let shade = if (x / 4 + y / 4) % 2 == 0 { 1.0 } else { 0.5 };
+            data.extend(colour.iter().map(|channel| (channel * shade * 255.0) as u8));
+            data.push(255);
+        }
+    }
+
+    data
+}
+
+// Implement the trait for the main application loop
+impl BaseApp for TextureArray {
+    fn init(
+        window_size: WindowSize,
+        _geometry_manager: &mut GeometryManager,
+        texture_manager: &mut TextureManager,
+        _material_manager: &mut MaterialManager,
+    ) -> Self {
+        // Without the array every change of texture would start a new draw
+        texture_manager.use_texture_array(TEXTURE_SIZE, TEXTURE_SIZE, N_TEXTURES as u32);
+
+        let textures = (0..N_TEXTURES)
+            .map(|index| texture_manager.create_texture_from_data(checkerboard(index), TEXTURE_SIZE, TEXTURE_SIZE))
+            .collect();
+
+        Self {
+            textures,
+            window_size,
+            time: 0.0,
+        }
+    }
+
+    fn handle_input(&mut self, _event: WindowEvent) {
+
+    }
+
+    fn update(&mut self, delta_t: f32) {
+        self.time += delta_t;
+    }
+
+    fn resize(&mut self, size: WindowSize) {
+        self.window_size = size;
+    }
+
+    fn draw<'draw, R: Renderer<'draw>>(&'draw mut self, mut renderer: R) {
+        // Clear the screen
+        renderer.add(RenderCommand::Clear(Colour::BLACK));
+
+        // Neighbouring sprites use different textures, but are still drawn together
+        let width = self.window_size.width as f32;
+        let height = self.window_size.height as f32;
+        for i in 0..N_SPRITES {
+            let seed = i as f32;
+            let home = Point2::new(
+                (seed * 0.618_034).fract() * width,
+                (seed * 0.754_877).fract() * height,
+            );
+            let angle = self.time * (1.0 + (seed * 0.377).fract()) + seed;
+
+            renderer.add(RenderCommand::Draw2D(TwoDDescription{
+                position: Point2::new(home.x + angle.cos() * 10.0, home.y + angle.sin() * 10.0),
+                size: Vector2::new(12.0, 12.0),
+                colour: Colour::WHITE,
+                texture: Some(self.textures[i % N_TEXTURES]),
+                shape: TwoDTypes::Rectangle,
+                rotation: angle,
+                .. TwoDDescription::default()
+            }));
+        }
+    }
+}
+
+// Start the app
+fn main() {
+    aurora::run::<TextureArray>("Texture Array Example");
+}
@@ -1,6 +1,7 @@
 use crate::{
     GeometryManager, 
     TextureManager,
+    MaterialManager,
     Renderer,
     WindowSize,
 };
@@ -17,10 +18,16 @@ pub trait BaseApp: 'static + Sized {
     fn required_limits() -> wgpu::Limits {
         wgpu::Limits::default()
     }
+    // Number of samples per pixel for multisample anti-aliasing - 1 or 4.
+    // Any count above 1 uses 4.
+    fn sample_count() -> u32 {
+        1
+    }
     fn init(
         window_size: WindowSize,
         geometry_manager: &mut GeometryManager,
         texture_manger: &mut TextureManager,
+        material_manager: &mut MaterialManager,
     ) -> Self;
     fn handle_input(&mut self, event: WindowEvent);
     fn update(&mut self, delta_t: f32);
@@ -0,0 +1,77 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime};
+
+// Each check touches the disk so they are spread out
+const POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+// Polls files for changes to their modified time. Watching a directory covers everything in it,
+// including files added later.
+pub struct FileWatcher {
+    paths: Vec<PathBuf>,
+    modified: HashMap<PathBuf, SystemTime>,
+    last_poll: Instant,
+}
+
+impl FileWatcher {
+    pub fn new() -> Self {
+        Self {
+            paths: Vec::new(),
+            modified: HashMap::new(),
+            last_poll: Instant::now(),
+        }
+    }
+
+    pub fn watch(&mut self, path: &Path) {
+        if self.paths.iter().any(|watched| watched == path) {
+            return;
+        }
+
+        for file in files_in(path) {
+            if let Some(modified) = modified_time(&file) {
+                self.modified.insert(file, modified);
+            }
+        }
+        self.paths.push(path.to_owned());
+    }
+
+    // Files changed or added since last asked
+    pub fn changed(&mut self) -> Vec<PathBuf> {
+        if self.last_poll.elapsed() < POLL_INTERVAL {
+            return Vec::new();
+        }
+        self.last_poll = Instant::now();
+
+        let mut changed = Vec::new();
+        for path in &self.paths {
+            for file in files_in(path) {
+                if let Some(modified) = modified_time(&file) {
+                    if self.modified.insert(file.clone(), modified) != Some(modified) {
+                        changed.push(file);
+                    }
+                }
+            }
+        }
+
+        changed
+    }
+}
+
+fn files_in(path: &Path) -> Vec<PathBuf> {
+    if path.is_dir() {
+        std::fs::read_dir(path)
+            .into_iter()
+            .flatten()
+            .flatten()
+            .flat_map(|entry| files_in(&entry.path()))
+            .collect()
+    } else {
+        vec![path.to_owned()]
+    }
+}
+
+fn modified_time(file: &Path) -> Option<SystemTime> {
+    std::fs::metadata(file)
+        .and_then(|metadata| metadata.modified())
+        .ok()
+}
@@ -1,8 +1,11 @@
 mod baseapp;
 mod geometry;
+#[cfg(feature = "hot-reload")]
+mod hot_reload;
 mod material;
 mod renderer;
 mod setup;
+mod svg;
 mod start;
 
 // Re-exports
@@ -26,6 +29,7 @@ pub type MouseButton = winit::event::MouseButton;
 pub type GeometryHandle = usize;
 pub type RendererHandle = usize;
 pub type TextureHandle = usize;
+pub type MaterialHandle = usize;
 
 // Enums
 #[derive(Debug)]
@@ -44,9 +48,12 @@ pub use start::run;
 
 // Main Structures that need making externally available
 pub use geometry::GeometryManager;
-pub use material::TextureManager;
-pub use renderer::{RenderCommand, RendererInstance, LineDescription, TwoDDescription, TwoDTypes};
+pub use material::{TextureManager, MaterialManager, MaterialError};
+pub use svg::{SvgImage, SvgError};
+pub use renderer::{RenderCommand, RendererInstance, LineDescription, BlendMode, DashPattern, LineJoin, LineCap, PolylineDescription, Curve, CurveDescription, ArcDescription, CURVE_TOLERANCE, FillRule, FillPathDescription, StrokePathDescription, ColourStop, GradientShape, Gradient, Path, PathCommand, TwoDDescription, TwoDTypes, TwoDStyle, TintMode, Fill, Shadow, Glow, PostEffect, MaskMode, Transform2D, DrawList, ShaderError};
 
 // For internal use
-use renderer::{CommandManager, CommandProcessor, SectionManager};
-use material::Texture;
\ No newline at end of file
+use renderer::{CommandManager, CommandProcessor, SectionManager, material_shader};
+use material::{Texture, Material, MAX_MATERIAL_TEXTURES};
+#[cfg(feature = "hot-reload")]
+use hot_reload::FileWatcher;
\ No newline at end of file
@@ -1,7 +1,5 @@
 use std::collections::HashMap;
 
-use crate::Vector2;
-
 use super::{
     AssetHolder, 
     InternalHandle, 
@@ -12,9 +10,21 @@ use super::{
     util::{
         load_from_file,
         copy_raw_to_gpu,
+        write_raw_to_gpu,
+        premultiply_alpha,
     }
 };
 
+#[cfg(feature = "hot-reload")]
+use std::path::PathBuf;
+#[cfg(feature = "hot-reload")]
+use crate::FileWatcher;
+#[cfg(feature = "hot-reload")]
+use super::util::try_load_from_file;
+
+// Most layers a texture array can have everywhere
+const MAX_TEXTURE_ARRAY_LAYERS: u32 = 256;
+
 // Texture Manager
 pub struct TextureManager {
     sub_texture_map: HashMap<TextureHandle, SubTexture>,
@@ -23,7 +33,15 @@ pub struct TextureManager {
     next_texture_handle: TextureHandle,
     needs_loading: Vec<TextureHandle>,
     needs_preparing: bool,
-    max_texture_size: Vector2,
+
+    // Same size textures kept as layers of one texture, if in use
+    texture_array: Option<TextureArray>,
+
+    // Files textures were loaded from, watched for changes
+    #[cfg(feature = "hot-reload")]
+    texture_files: Vec<(PathBuf, TextureHandle)>,
+    #[cfg(feature = "hot-reload")]
+    file_watcher: FileWatcher,
 }
 
 impl TextureManager {
@@ -35,18 +53,75 @@ impl TextureManager {
             next_texture_handle: 0,
             needs_loading: Vec::new(),
             needs_preparing: false,
-            max_texture_size: Vector2::new(256.0, 256.0),
+            texture_array: None,
+
+            #[cfg(feature = "hot-reload")]
+            texture_files: Vec::new(),
+            #[cfg(feature = "hot-reload")]
+            file_watcher: FileWatcher::new(),
         }
     }
 
-    pub fn buffer_dimensions_required(&self) -> Vector2 {
-        self.max_texture_size
+    // Keep textures created from now on that are the size given as layers of one texture array, up
+    // to the number of layers given. Shapes drawn with any of them can be drawn together, where
+    // other textures each need a draw of their own. Materials and colour grades can't use textures
+    // in the array.
+    pub fn use_texture_array(&mut self, width: u32, height: u32, layers: u32) {
+        if self.texture_array.is_some() {
+            log::warn!("The texture array is already in use");
+            return;
+        }
+
+        if layers == 0 {
+            log::warn!("A texture array needs at least one layer");
+            return;
+        }
+
+        if layers > MAX_TEXTURE_ARRAY_LAYERS {
+            log::warn!("Texture arrays can have at most {} layers", MAX_TEXTURE_ARRAY_LAYERS);
+        }
+
+        // The array is made when the first texture is added to it, until then it is only its size
+        let holder = AssetHolder::Unprepared( RawTextureData{
+            width,
+            height,
+            data: Vec::new(),
+        });
+        let i_handle = self.next_internal_handle;
+        self.textures.insert(i_handle, holder);
+        self.next_internal_handle += 1;
+
+        self.texture_array = Some(TextureArray {
+            texture: i_handle,
+            width,
+            height,
+            layers: layers.min(MAX_TEXTURE_ARRAY_LAYERS),
+            next_layer: 0,
+            needs_loading: Vec::new(),
+        });
     }
 
-    pub fn create_texture_from_data(&mut self, raw_data: Vec<u8>, width: u32, height: u32) -> TextureHandle {
-        // Ensure biggest texture dimentions captured
-        self.max_texture_size.x = self.max_texture_size.x.max(width as f32);
-        self.max_texture_size.y = self.max_texture_size.y.max(height as f32);
+    // Data is 8 bit sRGB with straight alpha, which is premultiplied here for the renderer
+    pub fn create_texture_from_data(&mut self, mut raw_data: Vec<u8>, width: u32, height: u32) -> TextureHandle {
+        premultiply_alpha(&mut raw_data);
+
+        // Goes in the texture array if it fits and there is a layer free
+        if let Some(array) = &mut self.texture_array {
+            if array.width == width && array.height == height && array.next_layer < array.layers {
+                let layer = array.next_layer;
+                array.next_layer += 1;
+                array.needs_loading.push((layer, raw_data));
+                self.needs_preparing = true;
+
+                let sub_texture = SubTexture {
+                    texture: array.texture,
+                    texture_position: [0, 0],
+                    texture_size: [width, height],
+                    texture_layer: layer,
+                };
+                return self.add_sub_texture(sub_texture);
+            }
+        }
 
         let holder = AssetHolder::Unprepared( RawTextureData{
             width,
@@ -63,13 +138,51 @@ impl TextureManager {
             texture: i_handle,
             texture_position: [0, 0],
             texture_size: [width, height],
+            texture_layer: 0,
         };
         self.add_sub_texture(sub_texture)
     }
 
     pub fn create_texture_from_file(&mut self, filename: &str) -> TextureHandle {
         let (raw_data, width, height) = load_from_file(filename);
-        self.create_texture_from_data(raw_data, width, height)
+        let handle = self.create_texture_from_data(raw_data, width, height);
+
+        #[cfg(feature = "hot-reload")]
+        {
+            let path = PathBuf::from(filename);
+            self.file_watcher.watch(&path);
+            self.texture_files.push((path, handle));
+        }
+
+        handle
+    }
+
+    // Upload textures whose files have changed since they were loaded. Textures are only updated in
+    // place, so a file that changes size is skipped until the next run.
+    #[cfg(feature = "hot-reload")]
+    pub fn reload_changed_files(&mut self, queue: &wgpu::Queue) {
+        for path in self.file_watcher.changed() {
+            let (mut raw_data, width, height) = match try_load_from_file(&path) {
+                Some(loaded) => { loaded },
+                None => {
+                    log::warn!("Couldn't reload {}, keeping the previous texture", path.display());
+                    continue;
+                },
+            };
+            premultiply_alpha(&mut raw_data);
+
+            for (_, handle) in self.texture_files.iter().filter(|(file, _)| *file == path) {
+                let sub_texture = &self.sub_texture_map[handle];
+                if let Some(AssetHolder::Loaded(texture)) = self.textures.get(&sub_texture.texture) {
+                    if texture.get_size() == (width, height) {
+                        write_raw_to_gpu(queue, &texture.texture_buffer, &raw_data, width, height, sub_texture.texture_layer);
+                        log::info!("Reloaded {}", path.display());
+                    } else {
+                        log::warn!("{} changed size, restart to pick it up", path.display());
+                    }
+                }
+            }
+        }
     }
 
     pub fn create_sub_texture(&mut self, texture: TextureHandle, pos_x: u32, pos_y: u32, width: u32, height: u32) -> TextureHandle {
@@ -86,6 +199,7 @@ impl TextureManager {
                     texture: parent_sub_tex.texture,
                     texture_position: [new_pos_x, new_pos_y],
                     texture_size: [width, height],
+                    texture_layer: parent_sub_tex.texture_layer,
                 };
 
                 return self.add_sub_texture(new_sub_texture);
@@ -107,7 +221,8 @@ impl TextureManager {
         self.needs_preparing
     }
 
-    pub fn prepare(&mut self, device: &wgpu::Device, queue: &wgpu::Queue) {
+    // Upload the new textures, each with a bind group made with the layout given
+    pub fn prepare(&mut self, device: &wgpu::Device, queue: &wgpu::Queue, bind_group_layout: &wgpu::BindGroupLayout) {
         for h in &self.needs_loading {
             if let Some(holder) = self.textures.get_mut(&h) {
                 match holder {
@@ -115,12 +230,14 @@ impl TextureManager {
                         let (extent, texture, sampler) = copy_raw_to_gpu(device, queue, &raw_texture.data, raw_texture.width, raw_texture.height);
                         let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
     
-                        let texture = Texture {
+                        let mut texture = Texture {
                             texture_extent: extent,
                             texture_buffer: texture,
                             texture_view: view,
                             texture_sampler: sampler,
+                            bind_group: None,
                         };
+                        texture.bind_group = Some(texture.create_bind_group(device, bind_group_layout));
 
                         *holder = AssetHolder::Loaded(texture);
                     },
@@ -130,6 +247,26 @@ impl TextureManager {
          }
 
          self.needs_loading.clear();
+
+         // Layers are written to the texture array, which is made the first time it is needed
+         if let Some(array) = &mut self.texture_array {
+            if !array.needs_loading.is_empty() {
+                if let Some(holder) = self.textures.get_mut(&array.texture) {
+                    if let AssetHolder::Unprepared(_) = holder {
+                        let mut texture = Texture::new_array(device, array.width, array.height, array.layers);
+                        texture.bind_group = Some(texture.create_bind_group(device, bind_group_layout));
+                        *holder = AssetHolder::Loaded(texture);
+                    }
+
+                    if let AssetHolder::Loaded(texture) = holder {
+                        for (layer, raw_data) in array.needs_loading.drain(..) {
+                            write_raw_to_gpu(queue, &texture.texture_buffer, &raw_data, array.width, array.height, layer);
+                        }
+                    }
+                }
+            }
+         }
+
          self.needs_preparing = false;
     }
 
@@ -147,13 +284,15 @@ impl TextureManager {
         None
     }
 
-    pub fn get_tl_br_coords_for(&self, handle: &InternalHandle) -> Option<[f32; 4]> {
+    // Coords of the sub texture within the texture it is part of, from 0 to 1
+    pub fn get_tl_br_coords_for(&self, handle: &TextureHandle) -> Option<[f32; 4]> {
         if let Some(sub_texture) = self.sub_texture_map.get(handle) {
+            let (width, height) = self.get_texture_size(&sub_texture.texture)?;
             let tlbr = [
-                sub_texture.texture_position[0] as f32 / self.max_texture_size.x,
-                sub_texture.texture_position[1] as f32 / self.max_texture_size.y,
-                (sub_texture.texture_position[0] + sub_texture.texture_size[0]) as f32 / self.max_texture_size.x,
-                (sub_texture.texture_position[1] + sub_texture.texture_size[1]) as f32 / self.max_texture_size.y,
+                sub_texture.texture_position[0] as f32 / width as f32,
+                sub_texture.texture_position[1] as f32 / height as f32,
+                (sub_texture.texture_position[0] + sub_texture.texture_size[0]) as f32 / width as f32,
+                (sub_texture.texture_position[1] + sub_texture.texture_size[1]) as f32 / height as f32,
             ];
 
             return Some(tlbr);
@@ -162,7 +301,25 @@ impl TextureManager {
         None
     }
 
+    // Size of the texture, whether or not it has been loaded yet
+    fn get_texture_size(&self, handle: &InternalHandle) -> Option<(u32, u32)> {
+        match self.textures.get(handle)? {
+            AssetHolder::Unprepared(raw_texture) => { Some((raw_texture.width, raw_texture.height)) },
+            AssetHolder::Loaded(texture) => { Some(texture.get_size()) },
+        }
+    }
+
     pub fn drop(&mut self, handle: TextureHandle) {
         self.textures.remove(&handle);
     }
+}
+
+// Textures kept as the layers of one texture, and those waiting to be written to it
+struct TextureArray {
+    texture: InternalHandle,
+    width: u32,
+    height: u32,
+    layers: u32,
+    next_layer: u32,
+    needs_loading: Vec<(u32, Vec<u8>)>,
 }
\ No newline at end of file
@@ -0,0 +1,132 @@
+use std::collections::HashMap;
+use std::fmt;
+
+use crate::{MaterialHandle, TextureHandle, ShaderError, material_shader};
+
+// Most extra textures a material can sample
+pub const MAX_MATERIAL_TEXTURES: usize = 2;
+
+// A custom fill for 2D shapes, written as a WGSL material function
+pub struct Material {
+    pub shader: String,
+    // The full 2D shader built around it
+    pub source: String,
+    pub textures: Vec<TextureHandle>,
+    pub params: [[f32; 4]; 4],
+}
+
+// Why a material couldn't be created
+#[derive(Debug, Clone)]
+pub enum MaterialError {
+    Shader(ShaderError),
+    // The number of textures that were given
+    TooManyTextures(usize),
+}
+
+impl fmt::Display for MaterialError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MaterialError::Shader(error) => { write!(f, "{}", error) },
+            MaterialError::TooManyTextures(n) => {
+                write!(f, "Materials can have at most {} textures, {} were given", MAX_MATERIAL_TEXTURES, n)
+            },
+        }
+    }
+}
+
+impl std::error::Error for MaterialError {}
+
+impl From<ShaderError> for MaterialError {
+    fn from(error: ShaderError) -> Self {
+        MaterialError::Shader(error)
+    }
+}
+
+// Material Manager
+#[derive(Default)]
+pub struct MaterialManager {
+    materials: HashMap<MaterialHandle, Material>,
+    next_handle: MaterialHandle,
+    needs_preparing: Vec<MaterialHandle>,
+}
+
+impl MaterialManager {
+    pub fn new() -> Self {
+        Self {
+            materials: HashMap::new(),
+            next_handle: 0,
+            needs_preparing: Vec::new(),
+        }
+    }
+
+    // Register a material from the WGSL defining its material function, along with up to two
+    // textures it can sample as t_Material0 and t_Material1. Sub textures bind their whole parent.
+    // The shader can #include the built in snippets and use #define & #ifdef, and is checked here
+    // so mistakes come back as errors pointing at the line rather than panics when drawing.
+    pub fn create_material(&mut self, shader: &str, textures: &[TextureHandle]) -> Result<MaterialHandle, MaterialError> {
+        if textures.len() > MAX_MATERIAL_TEXTURES {
+            return Err(MaterialError::TooManyTextures(textures.len()));
+        }
+
+        let source = material_shader(shader)?;
+
+        let handle = self.next_handle;
+        self.materials.insert(handle, Material {
+            shader: shader.to_owned(),
+            source,
+            textures: textures.to_vec(),
+            params: [[0.0; 4]; 4],
+        });
+        self.next_handle += 1;
+        self.needs_preparing.push(handle);
+
+        Ok(handle)
+    }
+
+    // Set the uniform block the material's shader sees as material_uniform.params
+    pub fn set_params(&mut self, handle: MaterialHandle, params: [[f32; 4]; 4]) {
+        if let Some(material) = self.materials.get_mut(&handle) {
+            material.params = params;
+
+            if !self.needs_preparing.contains(&handle) {
+                self.needs_preparing.push(handle);
+            }
+        }
+    }
+
+    // Recompile every material against the current built in shaders, returning those whose source
+    // changed. Any that fail keep their last working source.
+    #[cfg(feature = "hot-reload")]
+    pub fn reload_shaders(&mut self) -> Vec<MaterialHandle> {
+        let mut reloaded = Vec::new();
+
+        for (handle, material) in self.materials.iter_mut() {
+            match material_shader(&material.shader) {
+                Ok(source) => {
+                    if source != material.source {
+                        material.source = source;
+                        reloaded.push(*handle);
+                    }
+                },
+                Err(error) => {
+                    log::error!("Keeping the previous material shader: {}", error);
+                },
+            }
+        }
+
+        reloaded
+    }
+
+    pub fn get_material(&self, handle: &MaterialHandle) -> Option<&Material> {
+        self.materials.get(handle)
+    }
+
+    pub fn needs_preparing(&self) -> bool {
+        !self.needs_preparing.is_empty()
+    }
+
+    // Materials created or changed since last asked
+    pub fn take_needs_preparing(&mut self) -> Vec<MaterialHandle> {
+        std::mem::take(&mut self.needs_preparing)
+    }
+}
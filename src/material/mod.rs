@@ -1,9 +1,11 @@
 mod manager;
+mod material_manager;
 mod texture;
 mod util;
 
 // For external use
 pub use manager::TextureManager;
+pub use material_manager::{MaterialManager, MaterialError, Material, MAX_MATERIAL_TEXTURES};
 pub use texture::Texture;
 
 // For internal use
@@ -1,7 +1,7 @@
 
 use super::{
     InternalHandle,
-    util::create_gpu_texture,
+    util::{create_gpu_texture, create_gpu_texture_array},
 };
 
 // Unprepared Texture
@@ -16,6 +16,8 @@ pub struct SubTexture {
     pub texture: InternalHandle,
     pub texture_position: [u32; 2],
     pub texture_size: [u32; 2],
+    // Layer of the texture array it is in, 0 for other textures
+    pub texture_layer: u32,
 }
 
 // Handling and holding Textures in memory
@@ -24,6 +26,9 @@ pub struct Texture {
     pub texture_buffer: wgpu::Texture,
     pub texture_view: wgpu::TextureView,
     pub texture_sampler: wgpu::Sampler,
+
+    // Bind group the 2D shapes draw with, made once the texture is loaded
+    pub bind_group: Option<wgpu::BindGroup>,
 }
 
 impl Texture {
@@ -36,9 +41,24 @@ impl Texture {
                 texture_buffer,
                 texture_view,
                 texture_sampler,
+                bind_group: None,
             }
     }
 
+    // Function for creating a texture with a number of same size layers
+    pub fn new_array(device: &wgpu::Device, width: u32, height: u32, layers: u32) -> Self {
+        let (texture_extent, texture_buffer, texture_sampler) = create_gpu_texture_array(device, width, height, layers);
+        let texture_view = texture_buffer.create_view(&wgpu::TextureViewDescriptor::default());
+
+        Self {
+            texture_extent,
+            texture_buffer,
+            texture_view,
+            texture_sampler,
+            bind_group: None,
+        }
+    }
+
     // Function for creating a depth texture
     pub fn create_depth_texture(device: &wgpu::Device, sc_desc: &wgpu::SurfaceConfiguration) -> Self {
         let depth_texture = device.create_texture(&wgpu::TextureDescriptor {
@@ -82,9 +102,160 @@ impl Texture {
             texture_buffer: depth_texture,
             texture_view: depth_view,
             texture_sampler: depth_sampler, 
+            bind_group: None,
         }
-    } 
-    
+    }
+
+    // Function for creating an offscreen colour target that can be rendered to and then sampled
+    pub fn create_render_target(device: &wgpu::Device, sc_desc: &wgpu::SurfaceConfiguration) -> Self {
+        let target_extent = wgpu::Extent3d {
+            width: sc_desc.width,
+            height: sc_desc.height,
+            depth_or_array_layers: 1,
+        };
+
+        let target_texture = device.create_texture(&wgpu::TextureDescriptor {
+            size: target_extent,
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: sc_desc.format,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_SRC | wgpu::TextureUsages::COPY_DST,
+            label: Some("Render Target"),
+        });
+
+        let target_view = target_texture.create_view(&wgpu::TextureViewDescriptor::default());
+        let target_sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+            label: Some("Render Target Sampler"),
+            address_mode_u: wgpu::AddressMode::ClampToEdge,
+            address_mode_v: wgpu::AddressMode::ClampToEdge,
+            address_mode_w: wgpu::AddressMode::ClampToEdge,
+            mag_filter: wgpu::FilterMode::Linear,
+            min_filter: wgpu::FilterMode::Linear,
+            mipmap_filter: wgpu::FilterMode::Nearest,
+            lod_min_clamp: -100.0,
+            lod_max_clamp: 100.0,
+            compare: None,
+            anisotropy_clamp: None,
+            border_color: None,
+        });
+
+        Self {
+            texture_extent: target_extent,
+            texture_buffer: target_texture,
+            texture_view: target_view,
+            texture_sampler: target_sampler,
+            bind_group: None,
+        }
+    }
+
+    // Function for creating a multisampled colour target that is resolved into the frame
+    pub fn create_multisampled_target(device: &wgpu::Device, sc_desc: &wgpu::SurfaceConfiguration, sample_count: u32) -> Self {
+        let target_extent = wgpu::Extent3d {
+            width: sc_desc.width,
+            height: sc_desc.height,
+            depth_or_array_layers: 1,
+        };
+
+        let target_texture = device.create_texture(&wgpu::TextureDescriptor {
+            size: target_extent,
+            mip_level_count: 1,
+            sample_count,
+            dimension: wgpu::TextureDimension::D2,
+            format: sc_desc.format,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
+            label: Some("Multisampled Target"),
+        });
+
+        // Multisampled textures can't be sampled, but every texture carries a sampler
+        let target_view = target_texture.create_view(&wgpu::TextureViewDescriptor::default());
+        let target_sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+            label: Some("Multisampled Target Sampler"),
+            ..Default::default()
+        });
+
+        Self {
+            texture_extent: target_extent,
+            texture_buffer: target_texture,
+            texture_view: target_view,
+            texture_sampler: target_sampler,
+            bind_group: None,
+        }
+    }
+
+    // Function for creating the stencil buffer shapes are masked with, sampled to match the colour target
+    pub fn create_mask_target(device: &wgpu::Device, sc_desc: &wgpu::SurfaceConfiguration, sample_count: u32) -> Self {
+        let target_extent = wgpu::Extent3d {
+            width: sc_desc.width,
+            height: sc_desc.height,
+            depth_or_array_layers: 1,
+        };
+
+        let target_texture = device.create_texture(&wgpu::TextureDescriptor {
+            size: target_extent,
+            mip_level_count: 1,
+            sample_count,
+            dimension: wgpu::TextureDimension::D2,
+            format: wgpu::TextureFormat::Depth24PlusStencil8,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
+            label: Some("Mask Target"),
+        });
+
+        // Only ever attached, but every texture carries a sampler
+        let target_view = target_texture.create_view(&wgpu::TextureViewDescriptor::default());
+        let target_sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+            label: Some("Mask Target Sampler"),
+            ..Default::default()
+        });
+
+        Self {
+            texture_extent: target_extent,
+            texture_buffer: target_texture,
+            texture_view: target_view,
+            texture_sampler: target_sampler,
+            bind_group: None,
+        }
+    }
+
+    // Function for creating the texture gradients are baked into, one gradient per row
+    pub fn create_gradient_ramp(device: &wgpu::Device, width: u32, rows: u32) -> Self {
+        let ramp_extent = wgpu::Extent3d {
+            width,
+            height: rows,
+            depth_or_array_layers: 1,
+        };
+
+        let ramp_texture = device.create_texture(&wgpu::TextureDescriptor {
+            size: ramp_extent,
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: wgpu::TextureFormat::Rgba8UnormSrgb,
+            usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST,
+            label: Some("Gradient Ramp"),
+        });
+
+        let ramp_view = ramp_texture.create_view(&wgpu::TextureViewDescriptor::default());
+        let ramp_sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+            label: Some("Gradient Ramp Sampler"),
+            address_mode_u: wgpu::AddressMode::ClampToEdge,
+            address_mode_v: wgpu::AddressMode::ClampToEdge,
+            address_mode_w: wgpu::AddressMode::ClampToEdge,
+            mag_filter: wgpu::FilterMode::Linear,
+            min_filter: wgpu::FilterMode::Linear,
+            mipmap_filter: wgpu::FilterMode::Nearest,
+            ..Default::default()
+        });
+
+        Self {
+            texture_extent: ramp_extent,
+            texture_buffer: ramp_texture,
+            texture_view: ramp_view,
+            texture_sampler: ramp_sampler,
+            bind_group: None,
+        }
+    }
+
     // Return the size of the texture
     pub fn get_size(&self) -> (u32, u32) {
         (self.texture_extent.width, self.texture_extent.height)
@@ -105,6 +276,35 @@ impl Texture {
         &self.texture_sampler
     }
 
+    // Bind the texture and its sampler with the layout given. It is bound as an array, so that
+    // textures with one layer and texture arrays are drawn the same way.
+    pub fn create_bind_group(&self, device: &wgpu::Device, layout: &wgpu::BindGroupLayout) -> wgpu::BindGroup {
+        let array_view = self.texture_buffer.create_view(&wgpu::TextureViewDescriptor {
+            dimension: Some(wgpu::TextureViewDimension::D2Array),
+            ..Default::default()
+        });
+
+        device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("Texture Bind Group"),
+            layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: wgpu::BindingResource::TextureView(&array_view),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: wgpu::BindingResource::Sampler(&self.texture_sampler),
+                },
+            ],
+        })
+    }
+
+    // Return the bind group, if the texture has one
+    pub fn get_bind_group(&self) -> Option<&wgpu::BindGroup> {
+        self.bind_group.as_ref()
+    }
+
     // Return the extent
     pub fn get_extent(&self) -> wgpu::Extent3d {
         self.texture_extent.clone()
@@ -16,12 +16,31 @@ pub fn load_from_file(filename: &str) -> (Vec<u8>, u32, u32) {
     (buffer, reader.info().width, reader.info().height)
 }
 
+// As load_from_file, but a file being written as it is reloaded is skipped rather than a panic
+#[cfg(feature = "hot-reload")]
+pub fn try_load_from_file(filename: &std::path::Path) -> Option<(Vec<u8>, u32, u32)> {
+    let texture_file = std::fs::File::open(filename).ok()?;
+    let decoder = png::Decoder::new( texture_file );
+
+    let mut reader = decoder.read_info().ok()?;
+
+    let mut buffer = vec![0; reader.output_buffer_size()];
+    reader.next_frame(&mut buffer).ok()?;
+
+    Some((buffer, reader.info().width, reader.info().height))
+}
+
 pub fn create_gpu_texture(device: &wgpu::Device, width: u32, height: u32) -> (wgpu::Extent3d, wgpu::Texture, wgpu::Sampler) {
+    create_gpu_texture_array(device, width, height, 1)
+}
+
+// As create_gpu_texture, with a number of same size layers
+pub fn create_gpu_texture_array(device: &wgpu::Device, width: u32, height: u32, layers: u32) -> (wgpu::Extent3d, wgpu::Texture, wgpu::Sampler) {
     // Create Extent
     let texture_extent = wgpu::Extent3d {
         width,
         height,
-        depth_or_array_layers: 1,
+        depth_or_array_layers: layers,
     };
 
     // Create the Texture Buffer
@@ -59,29 +78,75 @@ pub fn copy_raw_to_gpu(device: &wgpu::Device, queue: &wgpu::Queue, raw_data: &Ve
     let (texture_extent, texture_buffer, texture_sampler) = create_gpu_texture(device, width, height);
 
     // Write the Texture Buffer
+    write_raw_to_gpu(queue, &texture_buffer, raw_data, width, height, 0);
+
+    (
+        texture_extent,
+        texture_buffer,
+        texture_sampler,
+    )
+}
+
+// Overwrite the whole of a layer of an existing texture
+pub fn write_raw_to_gpu(queue: &wgpu::Queue, texture_buffer: &wgpu::Texture, raw_data: &[u8], width: u32, height: u32, layer: u32) {
+    let texture_extent = wgpu::Extent3d {
+        width,
+        height,
+        depth_or_array_layers: 1,
+    };
+
     queue.write_texture(
         wgpu::ImageCopyTexture {
-            texture: &texture_buffer,
+            texture: texture_buffer,
             mip_level: 0,
             origin: wgpu::Origin3d {
                 x: 0,
                 y: 0,
-                z: 0,
+                z: layer,
             },
             aspect: wgpu::TextureAspect::All,
         },
         raw_data, 
         wgpu::ImageDataLayout{
             offset: 0,
-            bytes_per_row: core::num::NonZeroU32::new(4 * width),
-            rows_per_image: core::num::NonZeroU32::new(height),
+            bytes_per_row: core::num::NonZeroU32::new(4 * texture_extent.width),
+            rows_per_image: core::num::NonZeroU32::new(texture_extent.height),
         }, 
         texture_extent,
     );
+}
 
-    (
-        texture_extent,
-        texture_buffer,
-        texture_sampler,
-    )
-}
\ No newline at end of file
+// Multiply the colour of each sRGB pixel by its alpha, working in linear space as the GPU will
+pub fn premultiply_alpha(data: &mut [u8]) {
+    for pixel in data.chunks_exact_mut(4) {
+        // Nothing to do for fully opaque pixels
+        if pixel[3] == 255 {
+            continue;
+        }
+
+        let alpha = pixel[3] as f32 / 255.0;
+
+        for channel in &mut pixel[0..3] {
+            *channel = linear_to_srgb(srgb_to_linear(*channel) * alpha);
+        }
+    }
+}
+
+fn srgb_to_linear(value: u8) -> f32 {
+    let value = value as f32 / 255.0;
+    if value <= 0.04045 {
+        value / 12.92
+    } else {
+        ((value + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+fn linear_to_srgb(value: f32) -> u8 {
+    let value = value.clamp(0.0, 1.0);
+    let srgb = if value <= 0.0031308 {
+        value * 12.92
+    } else {
+        1.055 * value.powf(1.0 / 2.4) - 0.055
+    };
+    (srgb * 255.0).round() as u8
+}
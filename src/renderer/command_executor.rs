@@ -1,197 +1,298 @@
-use crate::Colour;
-use super::{CommandManager, SectionManager, PipelineManager, TextureManager, InternalCommands, MAX_INSTANCES};
+use std::ops::Range;
+
+use crate::BlendMode;
+use super::{MaskState, DrawPass, CommandManager, SectionManager, PipelineManager, ShapePass, ShapeDraw, PostPipeline, TextureManager, MaterialManager, InternalCommands, TwoDBatchKey, LineInstance, TwoDInstance};
 
 pub struct CommandExecutor<'ce, 'frame> {
     device: &'ce wgpu::Device,
     queue:  &'ce wgpu::Queue,
-    frame: &'ce wgpu::SurfaceTexture,
     frame_view: &'ce wgpu::TextureView,
 
 
     command_manager: &'ce CommandManager,
     section_manager: &'ce mut SectionManager<'frame>,
     pipeline_manager: &'ce mut PipelineManager,
+    post_pipeline: &'ce mut PostPipeline,
     texture_manager: &'ce mut TextureManager,
+    material_manager: &'ce mut MaterialManager,
+
+    mask: MaskState,
+}
 
-    clear_colour: Option<&'ce Colour>,
+// The view of the frame being drawn to and the managers a frame is built from
+pub struct FrameResources<'ce, 'frame> {
+    pub frame_view: &'ce wgpu::TextureView,
 
-    line_instances_on_gpu: Option<(usize, usize)>,
-    two_d_instances_on_gpu: Option<(usize, usize)>,
-    _three_d_instances_on_gpu: Option<(usize, usize)>,
+    pub command_manager: &'ce CommandManager,
+    pub section_manager: &'ce mut SectionManager<'frame>,
+    pub pipeline_manager: &'ce mut PipelineManager,
+    pub post_pipeline: &'ce mut PostPipeline,
+    pub texture_manager: &'ce mut TextureManager,
+    pub material_manager: &'ce mut MaterialManager,
 }
 
 impl <'ce, 'frame: 'ce> CommandExecutor<'ce, 'frame> {
     pub fn new(
         device: &'ce wgpu::Device,
         queue:  &'ce wgpu::Queue,
-        frame: &'ce wgpu::SurfaceTexture,
-        frame_view: &'ce wgpu::TextureView,
-
-        command_manager: &'ce CommandManager,
-        section_manager: &'ce mut SectionManager<'frame>,
-        pipeline_manager: &'ce mut PipelineManager,
-        texture_manager: &'ce mut TextureManager,
+        resources: FrameResources<'ce, 'frame>,
     ) -> Self {
+        let FrameResources {
+            frame_view,
+            command_manager,
+            section_manager,
+            pipeline_manager,
+            post_pipeline,
+            texture_manager,
+            material_manager,
+        } = resources;
+
         Self {
             device,
             queue,
-            frame,
             frame_view,
 
             command_manager,
             section_manager,
             pipeline_manager,
+            post_pipeline,
             texture_manager,
+            material_manager,
 
-            clear_colour: None,
-
-            line_instances_on_gpu: None,
-            two_d_instances_on_gpu: None,
-            _three_d_instances_on_gpu: None,
+            mask: MaskState::Off,
         }   
     }
 
     pub fn build_frame(&mut self) {
-        // First check if buffers need preparing
-        self.pipeline_manager.prepare_buffers(
-            self.device, 
-            self.texture_manager.buffer_dimensions_required(),
-        );
-
         // Check if Textures need loading
         if self.texture_manager.needs_preparing() {
-            self.texture_manager.prepare(self.device, self.queue);
+            self.texture_manager.prepare(
+                self.device,
+                self.queue,
+                self.pipeline_manager.texture_bind_group_layout(),
+            );
         }
 
-        // Loop through commands
+        // Materials are built once their textures are loaded
+        if self.material_manager.needs_preparing() {
+            self.pipeline_manager.prepare_materials(
+                self.device,
+                self.queue,
+                self.material_manager,
+                self.texture_manager,
+            );
+        }
+
+        // Every instance in the frame is uploaded in one go, with the depth tested ones after the
+        // batched ones, and each draw uses its own range of them
+        let depth_scene = DepthScene::new(self.command_manager);
+        self.pipeline_manager.update_line_instances(
+            self.device,
+            self.queue,
+            &[self.command_manager.get_line_instances(0, self.command_manager.n_line_instances()), &depth_scene.line_instances],
+        );
+        self.pipeline_manager.update_two_d_instances(
+            self.device,
+            self.queue,
+            &[self.command_manager.get_two_d_instances(0, self.command_manager.n_two_d_instance()), &depth_scene.two_d_instances],
+        );
+
+        // Tessellated geometry is small enough to upload in one go
+        if self.command_manager.n_triangle_vertices() > 0 {
+            self.pipeline_manager.update_triangle_vertices(
+                self.device,
+                self.queue,
+                self.command_manager.get_triangle_vertices(),
+            );
+        }
+
+        // Gradients are baked into a texture with a row each
+        if !self.command_manager.get_gradient_ramps().is_empty() {
+            self.pipeline_manager.update_gradient_ramps(
+                self.device,
+                self.queue,
+                self.command_manager.get_gradient_ramps(),
+            );
+        }
+
+        // Draw lists that have been dropped no longer need their instances on the GPU
+        let command_manager = self.command_manager;
+        self.pipeline_manager.retain_draw_lists(|id| command_manager.get_draw_list(id).is_some());
+
+        // If there are any post effects the scene is rendered offscreen first
+        let post_effects = self.command_manager.post_effects();
+        if !post_effects.is_empty() {
+            self.post_pipeline.prepare(self.device, post_effects);
+        }
+
+        let target_view = if post_effects.is_empty() {
+            self.frame_view
+        } else {
+            self.post_pipeline.scene_view()
+        };
+
+        // Everything for the frame is recorded into one encoder and submitted together
+        let mut encoder = self.device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+            label: Some("Frame Encoder"),
+        });
+
+        // Draw lists are uploaded if they have changed, and their transforms are written in the
+        // order they are drawn, after the one for drawing in place
+        let mut transforms = Vec::new();
         for cmd in self.command_manager.commands() {
+            if let InternalCommands::DrawList{id, transform} = cmd {
+                if let Some(cached) = self.command_manager.get_draw_list(*id) {
+                    let list_commands = &cached.commands;
+                    self.pipeline_manager.update_draw_list(
+                        self.device,
+                        *id,
+                        cached.revision,
+                        list_commands.get_line_instances(0, list_commands.n_line_instances()),
+                        list_commands.get_two_d_instances(0, list_commands.n_two_d_instance()),
+                    );
+                }
+                transforms.push(*transform);
+            }
+        }
+        self.pipeline_manager.update_transforms(self.device, self.queue, &transforms);
+        let mut n_transforms = 0;
 
-            // Change the load opp depending on if a clear colour has been set
-            let load_op = if let Some(c) = self.clear_colour.take() {
-                wgpu::LoadOp::Clear(c.clone())
-            } else {
-                wgpu::LoadOp::Load
-            };
+        // Consecutive shape draws share a render pass, which applies any clears before them
+        let mut shape_pass = ShapePass::new();
 
-            // Match the commands
+        // Loop through commands
+        for cmd in self.command_manager.commands() {
             match cmd {
                 InternalCommands::Clear{colour} => {
-                    self.clear_colour = Some(colour);
+                    if !shape_pass.draws.is_empty() {
+                        end_pass(self.pipeline_manager, self.device, &mut encoder, target_view, self.texture_manager, &mut shape_pass);
+                    }
+                    shape_pass.load_op = wgpu::LoadOp::Clear(*colour);
                 },
 
-                InternalCommands::DrawLinesBatch{line_instance_start, line_instance_end} => {
-                    // Create local variables
-                    let mut load_start_id = *line_instance_start;
-                    let load_end_id = *line_instance_end;
-                    
-                    // Count the number of instances that require rendereing
-                    let mut n_instances_remaining = line_instance_end - line_instance_start;
-
-                    // Not all might fit in a single render pass due to limits on buffer size and therefore
-                    // number of instances that can be rendered... so we will loop.
-                    while n_instances_remaining > 0 {
-                        if let Some((start_id, end_id)) = instances_to_load(
-                            (load_start_id, load_end_id), 
-                            self.line_instances_on_gpu, 
-                            self.command_manager.n_line_instances()
-                        ) {
-
-                            // Write instances to the GPU
-                            self.pipeline_manager.update_line_instances(
-                                self.queue, 
-                                self.command_manager.get_line_instances(load_start_id, load_end_id));
-
-                            // Count how many - will end the loop if n_instances_remaining reaches zero
-                            n_instances_remaining -= end_id - start_id;
-                            
-                            // Prep for next loop if required
-                            if n_instances_remaining != 0 {
-                                load_start_id = end_id + 1;
-                            }
-
-                            // Update the records
-                            self.line_instances_on_gpu = Some((start_id, end_id));
-
-                        } else {
-                            // Everything required already on GPU - cancel the loop
-                            n_instances_remaining = 0;
-                        }
+                InternalCommands::SetMask{mask} => {
+                    self.mask = *mask;
+                },
+
+                InternalCommands::DrawLinesBatch{line_instance_start, line_instance_end, blend_mode} => {
+                    shape_pass.draws.push(ShapeDraw::Lines {
+                        instances: *line_instance_start as u32..*line_instance_end as u32,
+                        blend_mode: *blend_mode,
+                        pass: DrawPass::Masked(self.mask),
+                        draw_list: None,
+                        transform_offset: 0,
+                    });
+                },
+
+                InternalCommands::DrawTwoDBatch{instance_start, instance_end, texture, material, blend_mode} => {
+                    shape_pass.draws.push(ShapeDraw::TwoD {
+                        instances: *instance_start as u32..*instance_end as u32,
+                        texture: *texture,
+                        material: *material,
+                        blend_mode: *blend_mode,
+                        pass: DrawPass::Masked(self.mask),
+                        draw_list: None,
+                        transform_offset: 0,
+                    });
+                },
+
+                InternalCommands::DrawTrianglesBatch{vertex_start, vertex_end} => {
+                    shape_pass.draws.push(ShapeDraw::Triangles {
+                        vertices: *vertex_start as u32..*vertex_end as u32,
+                        mask: self.mask,
+                    });
+                },
 
-                        // Render instances
-                        self.pipeline_manager.render_lines(
-                            self.device, 
-                            self.queue, 
-                            self.frame_view,
-                            *line_instance_start as u32, 
-                            *line_instance_end as u32, 
-                            load_op,
-                        );
+                InternalCommands::DrawDepthScene => {
+                    if !shape_pass.draws.is_empty() {
+                        end_pass(self.pipeline_manager, self.device, &mut encoder, target_view, self.texture_manager, &mut shape_pass);
+                    }
+                    shape_pass.clear_depth = true;
+
+                    for draw in &depth_scene.draws {
+                        match draw {
+                            DepthDraw::Lines{pass, blend_mode, instances} => {
+                                shape_pass.draws.push(ShapeDraw::Lines {
+                                    instances: instances.clone(),
+                                    blend_mode: *blend_mode,
+                                    pass: *pass,
+                                    draw_list: None,
+                                    transform_offset: 0,
+                                });
+                            },
+
+                            DepthDraw::TwoD{pass, key: (texture, material, blend_mode), instances} => {
+                                shape_pass.draws.push(ShapeDraw::TwoD {
+                                    instances: instances.clone(),
+                                    texture: *texture,
+                                    material: *material,
+                                    blend_mode: *blend_mode,
+                                    pass: *pass,
+                                    draw_list: None,
+                                    transform_offset: 0,
+                                });
+                            },
+                        }
                     }
                 },
 
-                InternalCommands::DrawTwoDBatch{instance_start, instance_end, texture} => {
-                    // Create local variables
-                    let mut batch_start_id = *instance_start;
-                    let batch_end_id = *instance_end;
-                    
-                    // Count the number of instances that require rendereing
-                    let mut n_remaining_in_batch = (batch_end_id - batch_start_id) as i32;
-
-                    // Get the texture
-                    let texture_for_instances = if let Some(texture_handle) = texture {
-                        self.texture_manager.get_texture(texture_handle)
-                    } else {
-                        None
+                InternalCommands::DrawList{id, ..} => {
+                    // Every draw list has its own transform, even if it is dropped
+                    n_transforms += 1;
+                    let transform_offset = self.pipeline_manager.transform_offset(n_transforms);
+
+                    let cached = match self.command_manager.get_draw_list(*id) {
+                        Some(cached) => cached,
+                        None => { continue; },
                     };
 
-                    // Not all might fit in a single render pass due to limits on buffer size and therefore
-                    // number of instances that can be rendered... so we will loop.
-                    while n_remaining_in_batch > 0 {
-                        if let Some((to_load_start_id, to_load_end_id)) = instances_to_load(
-                            (batch_start_id, batch_end_id), 
-                            self.two_d_instances_on_gpu, 
-                            self.command_manager.n_two_d_instance()
-                        ) {
-
-                            // Write instances to the GPU
-                            self.pipeline_manager.update_two_d_instances(
-                                self.queue, 
-                                self.command_manager.get_two_d_instances(to_load_start_id, to_load_end_id));
-
-                            // Count how many - will end the loop if n_instances_remaining reaches zero
-                            n_remaining_in_batch -= (to_load_end_id - to_load_start_id) as i32;
-                            
-                            // Prep for next loop if required
-                            if n_remaining_in_batch > 0 {
-                                batch_start_id = to_load_end_id + 1;
-                            }
-
-                            // Update the records
-                            self.two_d_instances_on_gpu = Some((to_load_start_id, to_load_end_id));
-
-                        } else {
-                            // Everything required already on GPU - cancel the loop
-                            n_remaining_in_batch = 0;
+                    // Drawn from the list's own instances on the GPU
+                    for list_cmd in cached.commands.commands() {
+                        match list_cmd {
+                            InternalCommands::DrawLinesBatch{line_instance_start, line_instance_end, blend_mode} => {
+                                shape_pass.draws.push(ShapeDraw::Lines {
+                                    instances: *line_instance_start as u32..*line_instance_end as u32,
+                                    blend_mode: *blend_mode,
+                                    pass: DrawPass::Masked(self.mask),
+                                    draw_list: Some(*id),
+                                    transform_offset,
+                                });
+                            },
+
+                            InternalCommands::DrawTwoDBatch{instance_start, instance_end, texture, material, blend_mode} => {
+                                shape_pass.draws.push(ShapeDraw::TwoD {
+                                    instances: *instance_start as u32..*instance_end as u32,
+                                    texture: *texture,
+                                    material: *material,
+                                    blend_mode: *blend_mode,
+                                    pass: DrawPass::Masked(self.mask),
+                                    draw_list: Some(*id),
+                                    transform_offset,
+                                });
+                            },
+
+                            _ => {},
                         }
+                    }
+                },
 
-                        // Render instances
-                        self.pipeline_manager.render_2d(
-                            self.device, 
-                            self.queue, 
-                            self.frame_view,
-                            *instance_start as u32, 
-                            *instance_end as u32,
-                            texture_for_instances,
-                            load_op,
-                        );
+                InternalCommands::ClearMask => {
+                    if !shape_pass.draws.is_empty() {
+                        end_pass(self.pipeline_manager, self.device, &mut encoder, target_view, self.texture_manager, &mut shape_pass);
                     }
+                    shape_pass.clear_mask = true;
+                    self.mask = MaskState::Off;
                 },
 
                 InternalCommands::DrawTextBatch{section_start, section_end} => {
+                    // Text goes over everything drawn before it
+                    if !shape_pass.is_empty() {
+                        end_pass(self.pipeline_manager, self.device, &mut encoder, target_view, self.texture_manager, &mut shape_pass);
+                    }
                     self.pipeline_manager.render_sections(
-                        self.device, 
-                        self.queue, 
-                        self.frame_view, 
+                        self.device,
+                        &mut encoder,
+                        target_view,
                         self.section_manager.get_sections(*section_start, *section_end)
                     );
                 }
@@ -199,19 +300,193 @@ impl <'ce, 'frame: 'ce> CommandExecutor<'ce, 'frame> {
                 _ => {},
             }
         }
+
+        // Record what is left, including a clear that nothing was drawn after
+        if !shape_pass.is_empty() {
+            end_pass(self.pipeline_manager, self.device, &mut encoder, target_view, self.texture_manager, &mut shape_pass);
+        }
+
+        // Run the effects chain over the scene and into the frame
+        if !post_effects.is_empty() {
+            self.post_pipeline.apply(
+                self.device,
+                self.queue,
+                &mut encoder,
+                self.frame_view,
+                post_effects,
+                self.texture_manager,
+            );
+        }
+
+        self.pipeline_manager.submit(self.queue, encoder);
+    }
+}
+
+// Record the pass so far and start a new one
+fn end_pass(
+    pipeline_manager: &mut PipelineManager,
+    device: &wgpu::Device,
+    encoder: &mut wgpu::CommandEncoder,
+    target_view: &wgpu::TextureView,
+    texture_manager: &TextureManager,
+    shape_pass: &mut ShapePass,
+) {
+    pipeline_manager.render_shapes(
+        device,
+        encoder,
+        target_view,
+        texture_manager,
+        shape_pass,
+    );
+    *shape_pass = ShapePass::new();
+}
+
+// Depth tested instances in the order they are drawn, and the draws that use them
+struct DepthScene {
+    line_instances: Vec<LineInstance>,
+    two_d_instances: Vec<TwoDInstance>,
+    draws: Vec<DepthDraw>,
+}
+
+enum DepthDraw {
+    Lines {
+        pass: DrawPass,
+        blend_mode: BlendMode,
+        instances: Range<u32>,
+    },
+    TwoD {
+        pass: DrawPass,
+        key: TwoDBatchKey,
+        instances: Range<u32>,
+    },
+}
+
+impl DepthScene {
+    // Every opaque part goes first, grouped by what it is drawn with and front to back within each
+    // group so hidden parts are skipped. The rest are blended, so are drawn back to front across
+    // every group, with a new draw wherever the next part needs something different.
+    fn new(command_manager: &CommandManager) -> Self {
+        let line_instances = command_manager.get_depth_line_instances();
+        let two_d_instances = command_manager.get_depth_two_d_instances();
+
+        // They go after the batched instances on the GPU
+        let line_offset = command_manager.n_line_instances();
+        let two_d_offset = command_manager.n_two_d_instance();
+
+        let mut scene = Self {
+            line_instances: Vec::new(),
+            two_d_instances: Vec::new(),
+            draws: Vec::new(),
+        };
+
+        for (blend_mode, instances) in depth_groups(line_instances, |instance| instance.depth) {
+            let start = line_offset + scene.line_instances.len();
+            scene.line_instances.extend(instances.iter().rev());
+            scene.draws.push(DepthDraw::Lines {
+                pass: DrawPass::Opaque,
+                blend_mode,
+                instances: start as u32..(line_offset + scene.line_instances.len()) as u32,
+            });
+        }
+
+        for (key, instances) in depth_groups(two_d_instances, |instance| instance.depth) {
+            let start = two_d_offset + scene.two_d_instances.len();
+            scene.two_d_instances.extend(instances.iter().rev());
+            scene.draws.push(DepthDraw::TwoD {
+                pass: DrawPass::Opaque,
+                key,
+                instances: start as u32..(two_d_offset + scene.two_d_instances.len()) as u32,
+            });
+        }
+
+        // Stable, so equal depths keep the order they were submitted in
+        let mut parts: Vec<DepthPart> = line_instances.iter()
+            .map(|(blend_mode, instance)| DepthPart::Line(*blend_mode, *instance))
+            .chain(two_d_instances.iter().map(|(key, instance)| DepthPart::TwoD(*key, *instance)))
+            .collect();
+        parts.sort_by(|a, b| b.depth().partial_cmp(&a.depth()).unwrap_or(std::cmp::Ordering::Equal));
+
+        for part in parts {
+            scene.push_transparent(part, line_offset, two_d_offset);
+        }
+
+        scene
+    }
+
+    // Add a blended part, carrying on the last draw if it is drawn the same way
+    fn push_transparent(&mut self, part: DepthPart, line_offset: usize, two_d_offset: usize) {
+        match part {
+            DepthPart::Line(blend_mode, instance) => {
+                let end = (line_offset + self.line_instances.len()) as u32;
+                self.line_instances.push(instance);
+
+                match self.draws.last_mut() {
+                    Some(DepthDraw::Lines{pass: DrawPass::Transparent, blend_mode: last_blend_mode, instances})
+                        if *last_blend_mode == blend_mode && instances.end == end => {
+                        instances.end += 1;
+                    },
+                    _ => {
+                        self.draws.push(DepthDraw::Lines {
+                            pass: DrawPass::Transparent,
+                            blend_mode,
+                            instances: end..end + 1,
+                        });
+                    },
+                }
+            },
+
+            DepthPart::TwoD(key, instance) => {
+                let end = (two_d_offset + self.two_d_instances.len()) as u32;
+                self.two_d_instances.push(instance);
+
+                match self.draws.last_mut() {
+                    Some(DepthDraw::TwoD{pass: DrawPass::Transparent, key: last_key, instances})
+                        if *last_key == key && instances.end == end => {
+                        instances.end += 1;
+                    },
+                    _ => {
+                        self.draws.push(DepthDraw::TwoD {
+                            pass: DrawPass::Transparent,
+                            key,
+                            instances: end..end + 1,
+                        });
+                    },
+                }
+            },
+        }
+    }
+}
+
+// A depth tested line or 2D shape, along with what it is drawn with
+enum DepthPart {
+    Line(BlendMode, LineInstance),
+    TwoD(TwoDBatchKey, TwoDInstance),
+}
+
+impl DepthPart {
+    fn depth(&self) -> f32 {
+        match self {
+            DepthPart::Line(_, instance) => { instance.depth },
+            DepthPart::TwoD(_, instance) => { instance.depth },
+        }
     }
 }
 
-fn instances_to_load(range_requested: (usize, usize), on_gpu: Option<(usize, usize)>, total_instances: usize) -> Option<(usize, usize)> {
-    
-    // Check if anything is on gpu
-    if let Some((on_gpu_start_id, on_gpu_end_id)) = on_gpu {
-        // If required instances are in the range of what is on the GPU -> do nothing
-        if range_requested.0 >= on_gpu_start_id && range_requested.1 <= on_gpu_end_id { return None; }
+// Group instances by what they need to share, in the order each group was first used, and sort
+// each group back to front
+fn depth_groups<K: Copy + PartialEq, I: Copy>(instances: &[(K, I)], depth: impl Fn(&I) -> f32) -> Vec<(K, Vec<I>)> {
+    let mut groups: Vec<(K, Vec<I>)> = Vec::new();
+    for (key, instance) in instances {
+        match groups.iter_mut().find(|(group_key, _)| group_key == key) {
+            Some((_, group)) => { group.push(*instance); },
+            None => { groups.push((*key, vec![*instance])); },
+        }
     }
 
-    // Else get all the instances that are possible starting from the first requested
-    let new_end = (range_requested.0 + MAX_INSTANCES).min(total_instances); 
+    // Stable, so equal depths keep the order they were submitted in
+    for (_, group) in &mut groups {
+        group.sort_by(|a, b| depth(b).partial_cmp(&depth(a)).unwrap_or(std::cmp::Ordering::Equal));
+    }
 
-    Some((range_requested.0, new_end))
-} 
\ No newline at end of file
+    groups
+}
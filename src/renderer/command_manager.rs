@@ -1,6 +1,28 @@
-use crate::{Colour};
+use std::collections::HashMap;
 
-use super::{LineInstance, TwoDInstance, ThreeDInstance};
+use crate::{BlendMode, Colour, MaterialHandle};
+
+use super::{FillRule, LineJoin, LineCap, MaskState, Transform2D, LineInstance, TwoDInstance, ThreeDInstance, TriangleVertex, PostEffect, gradient::GradientRamp};
+
+// What 2D instances need in common to be drawn together - texture, material and blend mode
+pub type TwoDBatchKey = (Option<usize>, Option<MaterialHandle>, BlendMode);
+
+// What a path's triangles depend on besides its shape, with floats kept as their bits
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PathGeometryKey {
+    Fill {
+        revision: u64,
+        tolerance: u32,
+        fill_rule: FillRule,
+    },
+    Stroke {
+        revision: u64,
+        tolerance: u32,
+        width: u32,
+        join: LineJoin,
+        cap: LineCap,
+    },
+}
 
 // These are the internally stored commands that allow us batch renderpasses together
 pub enum InternalCommands {
@@ -10,16 +32,36 @@ pub enum InternalCommands {
     DrawLinesBatch{
         line_instance_start: usize,
         line_instance_end: usize,
+        blend_mode: BlendMode,
     },
     DrawTwoDBatch{
         instance_start: usize,
         instance_end: usize,
         texture: Option<usize>,
+        material: Option<MaterialHandle>,
+        blend_mode: BlendMode,
+    },
+    DrawTrianglesBatch{
+        vertex_start: usize,
+        vertex_end: usize,
     },
     DrawTextBatch{
         section_start: usize,
         section_end: usize,
     },
+    // Changes how the draws after it use the mask
+    SetMask{
+        mask: MaskState,
+    },
+    // Empties the mask and stops clipping
+    ClearMask,
+    // Every depth tested draw in the frame, put where the first was submitted
+    DrawDepthScene,
+    // The batches of a cached draw list
+    DrawList{
+        id: u64,
+        transform: Transform2D,
+    },
     None,
 }
 
@@ -29,6 +71,39 @@ pub struct CommandManager {
     line_instances: Vec<LineInstance>,
     two_d_instance: Vec<TwoDInstance>,
     three_d_instance: Vec<ThreeDInstance>,
+    triangle_vertices: Vec<TriangleVertex>,
+    gradient_ramps: Vec<GradientRamp>,
+
+    post_effects: Vec<PostEffect>,
+
+    // Whether the mask has been cleared for this frame
+    mask_started: bool,
+
+    // Depth tested draws are kept out of the batches, to be sorted when drawn
+    depth_line_instances: Vec<(BlendMode, LineInstance)>,
+    depth_two_d_instances: Vec<(TwoDBatchKey, TwoDInstance)>,
+
+    // Draw lists already processed into batches, kept while they are drawn each frame
+    draw_lists: HashMap<u64, CachedDrawList>,
+    draw_list_revision: u64,
+
+    // Tessellated paths, kept while they are drawn each frame
+    path_geometry: HashMap<PathGeometryKey, CachedGeometry>,
+}
+
+// A draw list's batches, along with what they were processed from
+pub struct CachedDrawList {
+    pub version: u64,
+    // Changes each time any list is processed, for the GPU to know when to upload again
+    pub revision: u64,
+    pub commands: CommandManager,
+    pub used: bool,
+}
+
+// A path's triangles in white, to be tinted by the colour it is drawn in
+struct CachedGeometry {
+    vertices: Vec<TriangleVertex>,
+    used: bool,
 }
 
 impl CommandManager {
@@ -36,9 +111,23 @@ impl CommandManager {
         Self { 
             command_list: Vec::new(),
             
-            line_instances: Vec::with_capacity(super::MAX_INSTANCES),
-            two_d_instance: Vec::with_capacity(super::MAX_INSTANCES),
-            three_d_instance: Vec::with_capacity(super::MAX_INSTANCES),
+            line_instances: Vec::with_capacity(super::INITIAL_INSTANCES),
+            two_d_instance: Vec::with_capacity(super::INITIAL_INSTANCES),
+            three_d_instance: Vec::with_capacity(super::INITIAL_INSTANCES),
+            triangle_vertices: Vec::new(),
+            gradient_ramps: Vec::new(),
+
+            post_effects: Vec::new(),
+
+            mask_started: false,
+
+            depth_line_instances: Vec::new(),
+            depth_two_d_instances: Vec::new(),
+
+            draw_lists: HashMap::new(),
+            draw_list_revision: 0,
+
+            path_geometry: HashMap::new(),
          }
     }
 
@@ -47,9 +136,16 @@ impl CommandManager {
     }
 
     pub fn push_command(&mut self, new_cmd: InternalCommands) {
+        if let InternalCommands::ClearMask = new_cmd {
+            self.mask_started = true;
+        }
         self.command_list.push(new_cmd);
     }
 
+    pub fn mask_started(&self) -> bool {
+        self.mask_started
+    }
+
     pub fn last(&self) -> Option<&InternalCommands> {
         self.command_list.last()
     }
@@ -88,6 +184,58 @@ impl CommandManager {
     }
 
 
+    // Triangle Vertex Functions - the vertices are written straight in by the tessellator
+    pub fn triangle_vertices_mut(&mut self) -> &mut Vec<TriangleVertex> {
+        &mut self.triangle_vertices
+    }
+
+    pub fn n_triangle_vertices(&self) -> usize {
+        self.triangle_vertices.len()
+    }
+
+    pub fn get_triangle_vertices(&self) -> &[TriangleVertex] {
+        &self.triangle_vertices
+    }
+
+    // Add a path's triangles in the premultiplied colour, only tessellating them if they weren't
+    // drawn last frame
+    pub fn push_path_geometry(
+        &mut self,
+        key: PathGeometryKey,
+        colour: [f32; 4],
+        tessellate: impl FnOnce(&mut Vec<TriangleVertex>),
+    ) {
+        let cached = self.path_geometry.entry(key).or_insert_with(|| {
+            let mut vertices = Vec::new();
+            tessellate(&mut vertices);
+            CachedGeometry {
+                vertices,
+                used: false,
+            }
+        });
+        cached.used = true;
+
+        self.triangle_vertices.extend(cached.vertices.iter().map(|vertex| {
+            let tint = vertex.colour;
+            TriangleVertex {
+                colour: [tint[0] * colour[0], tint[1] * colour[1], tint[2] * colour[2], tint[3] * colour[3]],
+                ..*vertex
+            }
+        }));
+    }
+
+
+    // Gradient Functions - each gradient in the frame gets a row of the ramp texture
+    pub fn push_gradient_ramp(&mut self, ramp: GradientRamp) -> u32 {
+        self.gradient_ramps.push(ramp);
+        (self.gradient_ramps.len() - 1) as u32
+    }
+
+    pub fn get_gradient_ramps(&self) -> &[GradientRamp] {
+        &self.gradient_ramps
+    }
+
+
     // Geometry Instance Functions
     pub fn push_geometry_instance(&mut self, instance: ThreeDInstance) -> usize {
         self.three_d_instance.push(instance);
@@ -98,12 +246,91 @@ impl CommandManager {
         self.three_d_instance.len()
     }
 
+    // Depth Tested Functions - the first depth tested draw places the scene they are all drawn in
+    pub fn push_depth_line_instance(&mut self, blend_mode: BlendMode, instance: LineInstance) {
+        self.start_depth_scene();
+        self.depth_line_instances.push((blend_mode, instance));
+    }
+
+    pub fn push_depth_two_d_instance(&mut self, key: TwoDBatchKey, instance: TwoDInstance) {
+        self.start_depth_scene();
+        self.depth_two_d_instances.push((key, instance));
+    }
+
+    fn start_depth_scene(&mut self) {
+        if self.depth_line_instances.is_empty() && self.depth_two_d_instances.is_empty() {
+            self.command_list.push(InternalCommands::DrawDepthScene);
+        }
+    }
+
+    pub fn get_depth_line_instances(&self) -> &[(BlendMode, LineInstance)] {
+        &self.depth_line_instances
+    }
+
+    pub fn get_depth_two_d_instances(&self) -> &[(TwoDBatchKey, TwoDInstance)] {
+        &self.depth_two_d_instances
+    }
+
+    // Draw List Functions
+    // Keep a draw list's batches, replacing any it had before
+    pub fn cache_draw_list(&mut self, id: u64, version: u64, commands: CommandManager) {
+        self.draw_list_revision += 1;
+        self.draw_lists.insert(id, CachedDrawList {
+            version,
+            revision: self.draw_list_revision,
+            commands,
+            used: false,
+        });
+    }
+
+    pub fn get_draw_list(&self, id: u64) -> Option<&CachedDrawList> {
+        self.draw_lists.get(&id)
+    }
+
+    // Draw a cached draw list, keeping it for the next frame
+    pub fn push_draw_list(&mut self, id: u64, transform: Transform2D) {
+        if let Some(cached) = self.draw_lists.get_mut(&id) {
+            cached.used = true;
+            self.push_command(InternalCommands::DrawList{id, transform});
+        }
+    }
+
+    // Post Processing Functions
+    pub fn push_post_effect(&mut self, effect: PostEffect) {
+        self.post_effects.push(effect);
+    }
+
+    pub fn post_effects(&self) -> &[PostEffect] {
+        &self.post_effects
+    }
+
     pub fn clear(&mut self) {
         self.command_list.clear();
 
         self.line_instances.clear();
         self.two_d_instance.clear();
         self.three_d_instance.clear();
+        self.triangle_vertices.clear();
+        self.gradient_ramps.clear();
+
+        self.post_effects.clear();
+
+        self.mask_started = false;
+
+        self.depth_line_instances.clear();
+        self.depth_two_d_instances.clear();
+
+        // Lists not drawn last frame are dropped
+        self.draw_lists.retain(|_, cached| cached.used);
+        for cached in self.draw_lists.values_mut() {
+            cached.used = false;
+        }
+
+        // As are paths
+        self.path_geometry.retain(|_, cached| cached.used);
+        for cached in self.path_geometry.values_mut() {
+            cached.used = false;
+        }
     }
 }
 
@@ -1,12 +1,26 @@
+use crate::{BlendMode, Colour, Point2};
+
 use super::{
+    Curve,
+    Gradient,
+    LineJoin,
+    LineCap,
     LineInstance,
     TwoDInstance,
+    TintMode,
     InternalCommands,
+    PathGeometryKey,
+    MaskMode,
+    MaskState,
+    Transform2D,
     RenderCommand,
     CommandManager,
     TextureManager,
     Renderer,
     SectionManager,
+    flatten,
+    gradient,
+    tessellation,
 };
 
 pub struct CommandProcessor<'frame, 'cmd, 'sm> {
@@ -31,41 +45,181 @@ impl <'frame, 'cmd, 'sm> CommandProcessor<'frame, 'cmd,'sm> {
     pub fn process_cmd(&mut self, new_cmd: RenderCommand<'cmd>) {
         match new_cmd {
             RenderCommand::Clear(colour) => {
+                // Targets hold premultiplied colours like everything drawn into them
                 self.command_manager.push_command(InternalCommands::Clear{
-                    colour,
+                    colour: Colour{
+                        r: colour.r * colour.a,
+                        g: colour.g * colour.a,
+                        b: colour.b * colour.a,
+                        a: colour.a,
+                    },
                 });
             },
 
             RenderCommand::DrawLine(line_desc) => {
+                // Solid colour lines just end in the same colour
+                let end_colour = line_desc.end_colour.unwrap_or(line_desc.colour);
+
                 // Create Line Instance
                 let new_line_instance = LineInstance {
                     position_1: [line_desc.start.x, line_desc.start.y],
                     position_2: [line_desc.end.x, line_desc.end.y],
-                    line_colour: [
-                        line_desc.colour.r as f32, 
-                        line_desc.colour.g as f32, 
-                        line_desc.colour.b as f32, 
-                        line_desc.colour.a as f32
-                        ],
+                    line_colour: gpu_colour(line_desc.colour, line_desc.blend_mode),
                     line_width: line_desc.width,
+                    end_colour: gpu_colour(end_colour, line_desc.blend_mode),
+                    dash: line_desc.dash.to_array(),
+                    dash_phase: line_desc.dash.phase,
+                    depth: line_desc.depth.unwrap_or(0.0).clamp(0.0, 1.0),
                 };
 
+                // Depth tested lines are sorted instead of batched
+                if line_desc.depth.is_some() {
+                    self.command_manager.push_depth_line_instance(line_desc.blend_mode, new_line_instance);
+                    return;
+                }
+
                 // Push new instance
                 let line_index = self.command_manager.push_line_instance(new_line_instance);
 
-                // Check if can be batched with last command
-                if let Some(InternalCommands::DrawLinesBatch{line_instance_end, ..}) = self.command_manager.last_mut() {
-                    *line_instance_end += 1;
+                // Check if can be batched with last command - only when blending the same way
+                match self.command_manager.last_mut() {
+                    Some(InternalCommands::DrawLinesBatch{line_instance_end, blend_mode, ..}) if *blend_mode == line_desc.blend_mode => {
+                        *line_instance_end += 1;
+                    },
 
-                // If not then create a new batch command
-                } else {
-                    let new_line_batch = InternalCommands::DrawLinesBatch {
-                        line_instance_start: line_index,
-                        line_instance_end: line_index + 1,
-                    };
+                    // If not then create a new batch command
+                    _ => {
+                        let new_line_batch = InternalCommands::DrawLinesBatch {
+                            line_instance_start: line_index,
+                            line_instance_end: line_index + 1,
+                            blend_mode: line_desc.blend_mode,
+                        };
+
+                        self.command_manager.push_command(new_line_batch);
+                    },
+                }
+            },
+
+            RenderCommand::DrawPolyline(desc) => {
+                self.stroke(
+                    desc.points,
+                    &StrokeStyle {
+                        width: desc.width,
+                        colour: desc.colour,
+                        join: desc.join,
+                        cap: desc.cap,
+                        closed: desc.closed,
+                    },
+                );
+            },
+
+            RenderCommand::DrawCurve(desc) => {
+                // Flatten the curve into a polyline
+                let mut points = Vec::new();
+                match desc.curve {
+                    Curve::Quadratic{start, control, end} => {
+                        points.push(start);
+                        flatten::flatten_quadratic(start, control, end, desc.tolerance, &mut points);
+                    },
+                    Curve::Cubic{start, control_1, control_2, end} => {
+                        points.push(start);
+                        flatten::flatten_cubic(start, control_1, control_2, end, desc.tolerance, &mut points);
+                    },
+                }
+
+                self.stroke(
+                    &points,
+                    &StrokeStyle {
+                        width: desc.width,
+                        colour: desc.colour,
+                        join: LineJoin::Round,
+                        cap: desc.cap,
+                        closed: false,
+                    },
+                );
+            },
+
+            RenderCommand::DrawArc(desc) => {
+                // Flatten the arc into a polyline
+                let mut points = Vec::new();
+                flatten::flatten_arc(
+                    desc.centre,
+                    desc.radii,
+                    desc.rotation,
+                    desc.start_angle,
+                    desc.end_angle,
+                    desc.tolerance,
+                    &mut points,
+                );
+
+                // A full turn joins back up with itself
+                let closed = (desc.end_angle - desc.start_angle).abs() >= std::f32::consts::PI * 2.0;
+
+                self.stroke(
+                    &points,
+                    &StrokeStyle {
+                        width: desc.width,
+                        colour: desc.colour,
+                        join: LineJoin::Round,
+                        cap: desc.cap,
+                        closed,
+                    },
+                );
+            },
+
+            RenderCommand::FillPath(desc) => {
+                let vertex_start = self.command_manager.n_triangle_vertices();
+
+                let key = PathGeometryKey::Fill {
+                    revision: desc.path.revision(),
+                    tolerance: desc.tolerance.to_bits(),
+                    fill_rule: desc.fill_rule,
+                };
+                self.command_manager.push_path_geometry(key, premultiply(desc.colour), |vertices| {
+                    let sub_paths = desc.path.flatten(desc.tolerance);
+                    let polygons: Vec<&[Point2]> = sub_paths.iter()
+                        .map(|sub_path| sub_path.points.as_slice())
+                        .collect();
+
+                    tessellation::fill_polygons(&polygons, desc.fill_rule, [1.0; 4], vertices);
+                });
 
-                    self.command_manager.push_command(new_line_batch);
+                if let Some(fill_gradient) = desc.gradient {
+                    self.paint_gradient(vertex_start, fill_gradient);
                 }
+
+                self.batch_triangles(vertex_start);
+            },
+
+            RenderCommand::StrokePath(desc) => {
+                let vertex_start = self.command_manager.n_triangle_vertices();
+
+                let key = PathGeometryKey::Stroke {
+                    revision: desc.path.revision(),
+                    tolerance: desc.tolerance.to_bits(),
+                    width: desc.width.to_bits(),
+                    join: desc.join,
+                    cap: desc.cap,
+                };
+                self.command_manager.push_path_geometry(key, premultiply(desc.colour), |vertices| {
+                    for sub_path in desc.path.flatten(desc.tolerance) {
+                        tessellation::stroke_polyline(
+                            &sub_path.points,
+                            desc.width,
+                            [1.0; 4],
+                            desc.join,
+                            desc.cap,
+                            sub_path.closed,
+                            vertices,
+                        );
+                    }
+                });
+
+                if let Some(stroke_gradient) = desc.gradient {
+                    self.paint_gradient(vertex_start, stroke_gradient);
+                }
+
+                self.batch_triangles(vertex_start);
             },
 
             RenderCommand::Draw2D(desc) => {
@@ -76,46 +230,86 @@ impl <'frame, 'cmd, 'sm> CommandProcessor<'frame, 'cmd,'sm> {
                     None
                 };                
 
+                // Bake any gradient into the ramp texture
+                let ramp_row = match desc.fill.stops() {
+                    Some(stops) => { self.command_manager.push_gradient_ramp(gradient::build_ramp(stops)) },
+                    None => { 0 },
+                };
+
+                // Shadow and glow, left transparent when not wanted
+                let (shadow_colour, shadow_offset, shadow_blur) = match desc.shadow {
+                    Some(shadow) => {
+                        (
+                            gpu_colour(shadow.colour, desc.blend_mode),
+                            [shadow.offset.x, shadow.offset.y],
+                            shadow.blur,
+                        )
+                    },
+                    None => { ([0.0; 4], [0.0; 2], 0.0) },
+                };
+
+                let (glow_colour, glow_radius) = match desc.glow {
+                    Some(glow) => {
+                        (
+                            gpu_colour(glow.colour, desc.blend_mode),
+                            glow.radius,
+                        )
+                    },
+                    None => { ([0.0; 4], 0.0) },
+                };
+
+                // Get the internal reference for the underlying texture, and the layer of it when it
+                // is the texture array. Everything in the array shares the one texture, so batches together.
+                let sub_texture = desc.texture.and_then(|texture_handle| self.texture_manager.get_sub_texture(&texture_handle));
+                let underlying_texture = sub_texture.map(|sub_texture| sub_texture.texture);
+                let texture_layer = sub_texture.map_or(0, |sub_texture| sub_texture.texture_layer);
+
                 // Create an instance
                 let instance = TwoDInstance {
                     position: [desc.position.x, desc.position.y],
                     size: [desc.size.x, desc.size.y],
-                    colour: [
-                        desc.colour.r as f32, 
-                        desc.colour.g as f32, 
-                        desc.colour.b as f32, 
-                        desc.colour.a as f32
-                        ],
+                    colour: gpu_colour(desc.colour, desc.blend_mode),
                     texture: texture_coords.unwrap_or( [0.0,0.0,0.0,0.0] ),
                     opacity: desc.opacity,
                     line_width: desc.line_width,   
                     corner_radius: desc.corner_radius,
                     rotation: desc.rotation,     
                     shape: desc.shape.to_int(),    
+                    border_colour: gpu_colour(desc.border_colour, desc.blend_mode),
+                    style: desc.style.to_int(),
+                    shape_params: desc.shape.to_params(),
+                    // Materials take their params in place of the fill
+                    fill_geometry: if desc.material.is_some() { desc.material_params } else { desc.fill.to_geometry() },
+                    fill: [desc.fill.to_int(), ramp_row],
+                    shadow_colour,
+                    glow_colour,
+                    effects: [shadow_offset[0], shadow_offset[1], shadow_blur, glow_radius],
+                    // Without a texture there is nothing to tint
+                    tint_mode: if texture_coords.is_some() { desc.tint_mode.to_int() } else { TintMode::ColourOnly.to_int() },
+                    texture_layer,
+                    depth: desc.depth.unwrap_or(0.0).clamp(0.0, 1.0),
                 };
 
+                // Depth tested shapes are sorted instead of batched
+                if desc.depth.is_some() {
+                    self.command_manager.push_depth_two_d_instance((underlying_texture, desc.material, desc.blend_mode), instance);
+                    return;
+                }
+
                 // Push new instance
                 let two_d_index = self.command_manager.push_two_d_instance(instance);
 
-                // Get the internal reference for the underlying texture
-                let underlying_texture = if let Some(texture_handle) = desc.texture {
-                    if let Some(sub_texture) = self.texture_manager.get_sub_texture(&texture_handle) {
-                        Some(sub_texture.texture)
-                    } else {
-                        None
-                    }
-                } else {
-                    None
-                };
-
-
                 // Flag if new command needed
                 let mut new_cmd_needed = false;
 
                 // Check if can be batched with last command
-                if let Some(InternalCommands::DrawTwoDBatch{instance_end, texture, ..}) = self.command_manager.last_mut() {
+                if let Some(InternalCommands::DrawTwoDBatch{instance_end, texture, material, blend_mode, ..}) = self.command_manager.last_mut() {
+                    // Batches are drawn with a single blend mode and material
+                    if *blend_mode != desc.blend_mode || *material != desc.material {
+                        new_cmd_needed = true;
+
                     // Current batch has no texture
-                    if texture.is_none() {
+                    } else if texture.is_none() {
                         *texture = underlying_texture;
                         *instance_end += 1;
 
@@ -137,6 +331,8 @@ impl <'frame, 'cmd, 'sm> CommandProcessor<'frame, 'cmd,'sm> {
                         instance_start: two_d_index,
                         instance_end: two_d_index + 1,
                         texture: underlying_texture,
+                        material: desc.material,
+                        blend_mode: desc.blend_mode,
                     };
 
                     self.command_manager.push_command(new_2d_batch);
@@ -160,14 +356,162 @@ impl <'frame, 'cmd, 'sm> CommandProcessor<'frame, 'cmd,'sm> {
 
                     self.command_manager.push_command(new_section_batch);
                 }
-            }
+            },
+
+            RenderCommand::PostProcess(effect) => {
+                // Effects are applied in order once the frame is complete
+                self.command_manager.push_post_effect(effect);
+            },
+
+            RenderCommand::BeginMask => {
+                self.start_mask();
+                self.command_manager.push_command(InternalCommands::SetMask{
+                    mask: MaskState::Write,
+                });
+            },
+
+            RenderCommand::EndMask(mode) => {
+                self.start_mask();
+                let mask = match mode {
+                    MaskMode::Inside => { MaskState::Inside },
+                    MaskMode::Outside => { MaskState::Outside },
+                };
+                self.command_manager.push_command(InternalCommands::SetMask{mask});
+            },
+
+            RenderCommand::ClearMask => {
+                // Nothing to clear until a mask has been started this frame
+                if self.command_manager.mask_started() {
+                    self.command_manager.push_command(InternalCommands::ClearMask);
+                }
+            },
+
+            RenderCommand::DrawList(list, transform) => {
+                // Nothing to draw
+                if list.is_empty() {
+                    return;
+                }
+
+                // Only processed again when the list has changed
+                let up_to_date = matches!(
+                    self.command_manager.get_draw_list(list.id()),
+                    Some(cached) if cached.version == list.version()
+                );
+
+                if !up_to_date {
+                    let mut list_commands = CommandManager::new();
+                    let mut list_sections = SectionManager::new();
+                    let mut processor = CommandProcessor::create(
+                        &mut list_commands,
+                        &mut list_sections,
+                        self.texture_manager,
+                    );
+                    for cmd in list.commands() {
+                        processor.process_cmd(cmd);
+                    }
+
+                    self.command_manager.cache_draw_list(list.id(), list.version(), list_commands);
+                }
+
+                self.command_manager.push_draw_list(list.id(), transform.unwrap_or_else(Transform2D::default));
+            },
 
         }
     }
 }
 
+impl <'frame, 'cmd, 'sm> CommandProcessor<'frame, 'cmd,'sm> {
+    // The mask is left over from the last frame until it is first cleared
+    fn start_mask(&mut self) {
+        if !self.command_manager.mask_started() {
+            self.command_manager.push_command(InternalCommands::ClearMask);
+        }
+    }
+
+    // Tessellate a line straight into the triangle vertices and batch it
+    fn stroke(&mut self, points: &[Point2], style: &StrokeStyle) {
+        let vertex_start = self.command_manager.n_triangle_vertices();
+
+        tessellation::stroke_polyline(
+            points,
+            style.width,
+            premultiply(style.colour),
+            style.join,
+            style.cap,
+            style.closed,
+            self.command_manager.triangle_vertices_mut(),
+        );
+
+        self.batch_triangles(vertex_start);
+    }
+
+    // Paint the triangle vertices pushed since the start with the gradient
+    fn paint_gradient(&mut self, vertex_start: usize, paint: &Gradient) {
+        let ramp_row = self.command_manager.push_gradient_ramp(gradient::build_ramp(&paint.stops));
+
+        gradient::paint_vertices(
+            &mut self.command_manager.triangle_vertices_mut()[vertex_start..],
+            &paint.shape,
+            ramp_row,
+        );
+    }
+
+    // Add any triangle vertices pushed since the start to the triangles batch
+    fn batch_triangles(&mut self, vertex_start: usize) {
+        let vertex_end = self.command_manager.n_triangle_vertices();
+
+        // Nothing to draw
+        if vertex_end == vertex_start {
+            return;
+        }
+
+        // Check if can be batched with last command
+        if let Some(InternalCommands::DrawTrianglesBatch{vertex_end: batch_end, ..}) = self.command_manager.last_mut() {
+            *batch_end = vertex_end;
+
+        // If not then create a new batch command
+        } else {
+            let new_triangles_batch = InternalCommands::DrawTrianglesBatch {
+                vertex_start,
+                vertex_end,
+            };
+
+            self.command_manager.push_command(new_triangles_batch);
+        }
+    }
+}
+
+// How a line is to be stroked
+struct StrokeStyle {
+    width: f32,
+    colour: Colour,
+    join: LineJoin,
+    cap: LineCap,
+    closed: bool,
+}
+
 impl <'frame, 'cmd, 'sm> Renderer<'cmd> for CommandProcessor<'frame, 'cmd, 'sm> {
     fn add(&mut self, cmd: RenderCommand<'cmd>) {
         self.process_cmd(cmd);
     }
-}
\ No newline at end of file
+}
+
+// Colours go to the GPU with their alpha multiplied in, so blending and filtering don't leave dark edges
+fn premultiply(colour: Colour) -> [f32; 4] {
+    [
+        (colour.r * colour.a) as f32,
+        (colour.g * colour.a) as f32,
+        (colour.b * colour.a) as f32,
+        colour.a as f32,
+    ]
+}
+
+// Colours drawn with the premultiplied blend mode have their alpha multiplied in already
+fn gpu_colour(colour: Colour, blend_mode: BlendMode) -> [f32; 4] {
+    match blend_mode {
+        BlendMode::Premultiplied => {
+            [colour.r as f32, colour.g as f32, colour.b as f32, colour.a as f32]
+        },
+        _ => { premultiply(colour) },
+    }
+}
@@ -0,0 +1,113 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use super::{RenderCommand, Renderer, Fill, LineDescription, TwoDDescription};
+
+// Each list gets its own id so its copy on the GPU can be found again
+static NEXT_DRAW_LIST_ID: AtomicU64 = AtomicU64::new(0);
+
+// Lines and 2D shapes recorded once and kept on the GPU between frames, drawn each frame with
+// RenderCommand::DrawList. Changing the list marks it dirty, and it is only uploaded again then.
+// Anything other than lines and 2D shapes is left out, and gradient fills and depth aren't kept -
+// shapes are filled solid and everything is drawn in the order it was added, with a warning when
+// either is dropped.
+pub struct DrawList {
+    id: u64,
+    version: u64,
+    items: Vec<DrawListItem>,
+}
+
+enum DrawListItem {
+    Line(LineDescription),
+    TwoD(TwoDDescription<'static>),
+}
+
+impl DrawList {
+    pub fn new() -> Self {
+        Self {
+            id: NEXT_DRAW_LIST_ID.fetch_add(1, Ordering::Relaxed),
+            version: 0,
+            items: Vec::new(),
+        }
+    }
+
+    // Remove everything ready to record the list again
+    pub fn clear(&mut self) {
+        self.items.clear();
+        self.mark_dirty();
+    }
+
+    // Number of lines and shapes recorded
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    pub fn id(&self) -> u64 {
+        self.id
+    }
+
+    // Changes every time the list does
+    pub fn version(&self) -> u64 {
+        self.version
+    }
+
+    // The recorded draws, ready to be processed like any others
+    pub fn commands(&self) -> impl Iterator<Item = RenderCommand<'static>> + '_ {
+        self.items.iter().map(|item| {
+            match item {
+                DrawListItem::Line(desc) => { RenderCommand::DrawLine(desc.clone()) },
+                DrawListItem::TwoD(desc) => { RenderCommand::Draw2D(desc.clone()) },
+            }
+        })
+    }
+
+    fn mark_dirty(&mut self) {
+        self.version += 1;
+    }
+}
+
+impl Default for DrawList {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Renderer<'static> for DrawList {
+    fn add(&mut self, cmd: RenderCommand<'static>) {
+        match cmd {
+            RenderCommand::DrawLine(desc) => {
+                if desc.depth.is_some() {
+                    log::warn!("Lines in a draw list aren't depth tested, they are drawn in the order they were added");
+                }
+                self.items.push(DrawListItem::Line(LineDescription {
+                    depth: None,
+                    ..desc
+                }));
+            },
+
+            RenderCommand::Draw2D(desc) => {
+                if !matches!(desc.fill, Fill::Solid) {
+                    log::warn!("Shapes in a draw list can't have gradient fills, they are filled solid");
+                }
+                if desc.depth.is_some() {
+                    log::warn!("Shapes in a draw list aren't depth tested, they are drawn in the order they were added");
+                }
+                self.items.push(DrawListItem::TwoD(TwoDDescription {
+                    fill: Fill::Solid,
+                    depth: None,
+                    ..desc
+                }));
+            },
+
+            _ => {
+                log::warn!("Only lines and 2D shapes can be kept in a draw list");
+                return;
+            },
+        }
+
+        self.mark_dirty();
+    }
+}
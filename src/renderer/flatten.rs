@@ -0,0 +1,166 @@
+use cgmath::{EuclideanSpace, InnerSpace};
+
+use crate::{Point2, Vector2};
+
+// Fewest segments used for a curve, so a tolerance that's too loose still looks like a curve
+const MIN_SEGMENTS: usize = 4;
+
+// Most segments used for a single curve, protecting against huge curves or a tolerance of zero
+const MAX_SEGMENTS: usize = 1024;
+
+// Approximate a quadratic bezier with straight segments, pushing every point after the start.
+// Segment counts come from Wang's formula so no point on the curve is further than the tolerance
+// in pixels from the segments.
+pub fn flatten_quadratic(
+    start: Point2,
+    control: Point2,
+    end: Point2,
+    tolerance: f32,
+    output: &mut Vec<Point2>,
+) {
+    let second_difference = (start.to_vec() - control.to_vec() * 2.0 + end.to_vec()).magnitude();
+    let n_segments = segment_count((0.25 * second_difference / tolerance).sqrt());
+
+    for i in 1..=n_segments {
+        let t = i as f32 / n_segments as f32;
+        let mt = 1.0 - t;
+
+        output.push(Point2::from_vec(
+            start.to_vec() * (mt * mt) + control.to_vec() * (2.0 * mt * t) + end.to_vec() * (t * t)
+        ));
+    }
+}
+
+// Approximate a cubic bezier with straight segments, pushing every point after the start
+pub fn flatten_cubic(
+    start: Point2,
+    control_1: Point2,
+    control_2: Point2,
+    end: Point2,
+    tolerance: f32,
+    output: &mut Vec<Point2>,
+) {
+    let second_difference = (start.to_vec() - control_1.to_vec() * 2.0 + control_2.to_vec()).magnitude()
+        .max((control_1.to_vec() - control_2.to_vec() * 2.0 + end.to_vec()).magnitude());
+    let n_segments = segment_count((0.75 * second_difference / tolerance).sqrt());
+
+    for i in 1..=n_segments {
+        let t = i as f32 / n_segments as f32;
+        let mt = 1.0 - t;
+
+        output.push(Point2::from_vec(
+            start.to_vec() * (mt * mt * mt)
+            + control_1.to_vec() * (3.0 * mt * mt * t)
+            + control_2.to_vec() * (3.0 * mt * t * t)
+            + end.to_vec() * (t * t * t)
+        ));
+    }
+}
+
+// Approximate an elliptical arc with straight segments, pushing every point including the start.
+// Angles are in radians, increasing clockwise on screen, and the ellipse is rotated about its centre.
+pub fn flatten_arc(
+    centre: Point2,
+    radii: Vector2,
+    rotation: f32,
+    start_angle: f32,
+    end_angle: f32,
+    tolerance: f32,
+    output: &mut Vec<Point2>,
+) {
+    let sweep = end_angle - start_angle;
+
+    // The step that keeps the chords within tolerance of a circle of the larger radius
+    let radius = radii.x.abs().max(radii.y.abs());
+    let step = if radius > tolerance {
+        2.0 * (1.0 - tolerance / radius).acos()
+    } else {
+        std::f32::consts::PI
+    };
+    let n_segments = segment_count(sweep.abs() / step.max(f32::EPSILON));
+
+    let (sin_r, cos_r) = rotation.sin_cos();
+    for i in 0..=n_segments {
+        let angle = start_angle + sweep * i as f32 / n_segments as f32;
+        let (sin_a, cos_a) = angle.sin_cos();
+
+        let x = radii.x * cos_a;
+        let y = radii.y * sin_a;
+
+        output.push(Point2::new(
+            centre.x + x * cos_r - y * sin_r,
+            centre.y + x * sin_r + y * cos_r,
+        ));
+    }
+}
+
+fn segment_count(n: f32) -> usize {
+    if n.is_finite() {
+        (n.ceil() as usize).clamp(MIN_SEGMENTS, MAX_SEGMENTS)
+    } else {
+        MAX_SEGMENTS
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn near_straight_curves_keep_the_minimum() {
+        let mut output = Vec::new();
+        flatten_quadratic(Point2::new(0.0, 0.0), Point2::new(5.0, 0.001), Point2::new(10.0, 0.0), 0.25, &mut output);
+        assert_eq!(output.len(), MIN_SEGMENTS);
+
+        output.clear();
+        flatten_cubic(Point2::new(0.0, 0.0), Point2::new(3.0, 0.0), Point2::new(6.0, 0.0), Point2::new(10.0, 0.0), 0.25, &mut output);
+        assert_eq!(output.len(), MIN_SEGMENTS);
+
+        // A tiny arc still gets the minimum, along with its start
+        output.clear();
+        flatten_arc(Point2::new(0.0, 0.0), Vector2::new(1.0, 1.0), 0.0, 0.0, 0.01, 0.25, &mut output);
+        assert_eq!(output.len(), MIN_SEGMENTS + 1);
+    }
+
+    #[test]
+    fn huge_curves_are_clamped() {
+        let mut output = Vec::new();
+        flatten_quadratic(Point2::new(0.0, 0.0), Point2::new(1.0e7, 1.0e7), Point2::new(2.0e7, 0.0), 0.25, &mut output);
+        assert_eq!(output.len(), MAX_SEGMENTS);
+
+        output.clear();
+        flatten_cubic(Point2::new(0.0, 0.0), Point2::new(1.0e7, 1.0e7), Point2::new(-1.0e7, 1.0e7), Point2::new(0.0, 0.0), 0.25, &mut output);
+        assert_eq!(output.len(), MAX_SEGMENTS);
+
+        output.clear();
+        flatten_arc(Point2::new(0.0, 0.0), Vector2::new(1.0e7, 1.0e7), 0.0, 0.0, std::f32::consts::TAU, 0.001, &mut output);
+        assert_eq!(output.len(), MAX_SEGMENTS + 1);
+
+        // No tolerance at all is as fine as it goes
+        output.clear();
+        flatten_quadratic(Point2::new(0.0, 0.0), Point2::new(5.0, 5.0), Point2::new(10.0, 0.0), 0.0, &mut output);
+        assert_eq!(output.len(), MAX_SEGMENTS);
+    }
+
+    #[test]
+    fn end_points_are_exact() {
+        let (start, end) = (Point2::new(0.3, 0.7), Point2::new(123.456, -78.9));
+
+        let mut output = Vec::new();
+        flatten_quadratic(start, Point2::new(50.1, 200.2), end, 0.1, &mut output);
+        assert_eq!(output.last(), Some(&end));
+        assert!(output.len() > MIN_SEGMENTS);
+
+        output.clear();
+        flatten_cubic(start, Point2::new(-40.0, 90.0), Point2::new(300.0, 10.0), end, 0.1, &mut output);
+        assert_eq!(output.last(), Some(&end));
+        assert!(output.len() > MIN_SEGMENTS);
+
+        // Arcs start on the start angle and finish on the end angle
+        output.clear();
+        flatten_arc(Point2::new(10.0, 20.0), Vector2::new(30.0, 15.0), 0.0, 0.0, std::f32::consts::PI, 0.1, &mut output);
+        assert_eq!(output.first(), Some(&Point2::new(40.0, 20.0)));
+        let last = output.last().unwrap();
+        assert!((last.x + 20.0).abs() < 1e-4 && (last.y - 20.0).abs() < 1e-4);
+    }
+}
@@ -0,0 +1,90 @@
+use cgmath::InnerSpace;
+
+use crate::Vector2;
+
+use super::{ColourStop, GradientShape, TriangleVertex};
+
+// Number of colours each gradient is baked down to
+pub const RAMP_WIDTH: usize = 256;
+
+// A gradient baked into a row of sRGB colours for the gradient ramp texture
+pub type GradientRamp = [[u8; 4]; RAMP_WIDTH];
+
+// Paint for anything that isn't a gradient - the type in the last slot is 0
+pub const SOLID_PAINT: [f32; 4] = [0.0; 4];
+
+// Gradient types as understood by the shaders
+const LINEAR_PAINT: f32 = 1.0;
+const RADIAL_PAINT: f32 = 2.0;
+
+// Bake the colour stops into a ramp, blending premultiplied colours in linear space then storing as sRGB
+pub fn build_ramp(stops: &[ColourStop]) -> GradientRamp {
+    let mut ramp = [[0; 4]; RAMP_WIDTH];
+
+    // Stops are used in offset order
+    let mut stops = stops.to_vec();
+    stops.sort_by(|a, b| a.offset.partial_cmp(&b.offset).unwrap_or(std::cmp::Ordering::Equal));
+
+    if stops.is_empty() {
+        return ramp;
+    }
+
+    for (i, texel) in ramp.iter_mut().enumerate() {
+        let t = i as f32 / (RAMP_WIDTH - 1) as f32;
+
+        // Find the stops either side
+        let next = stops.iter().position(|stop| stop.offset >= t).unwrap_or(stops.len() - 1);
+        let previous = next.saturating_sub(1);
+        let (a, b) = (&stops[previous], &stops[next]);
+
+        let span = b.offset - a.offset;
+        let blend = if span > 0.0 { ((t - a.offset) / span).clamp(0.0, 1.0) } else { 1.0 };
+
+        // Blending premultiplied stops keeps a fade to transparent from picking up the hidden colour
+        let lerp = |x: f64, y: f64| x + (y - x) * blend as f64;
+        let (a_alpha, b_alpha) = (a.colour.a.clamp(0.0, 1.0), b.colour.a.clamp(0.0, 1.0));
+        *texel = [
+            linear_to_srgb(lerp(a.colour.r * a_alpha, b.colour.r * b_alpha)),
+            linear_to_srgb(lerp(a.colour.g * a_alpha, b.colour.g * b_alpha)),
+            linear_to_srgb(lerp(a.colour.b * a_alpha, b.colour.b * b_alpha)),
+            (lerp(a_alpha, b_alpha) * 255.0).round() as u8,
+        ];
+    }
+
+    ramp
+}
+
+// Set the gradient position of each vertex so the shader can look up the ramp row
+pub fn paint_vertices(vertices: &mut [TriangleVertex], shape: &GradientShape, ramp_row: u32) {
+    for vertex in vertices {
+        let position = Vector2::new(vertex.position[0], vertex.position[1]);
+
+        vertex.paint = match *shape {
+            GradientShape::Linear{start, end} => {
+                // Distance along the line as a fraction of its length
+                let direction = Vector2::new(end.x - start.x, end.y - start.y);
+                let length_squared = direction.magnitude2().max(f32::EPSILON);
+                let t = (position - Vector2::new(start.x, start.y)).dot(direction) / length_squared;
+
+                [t, 0.0, ramp_row as f32, LINEAR_PAINT]
+            },
+            GradientShape::Radial{centre, radius} => {
+                // Offset from the centre in radii - the shader takes the length
+                let offset = (position - Vector2::new(centre.x, centre.y)) / radius.max(f32::EPSILON);
+
+                [offset.x, offset.y, ramp_row as f32, RADIAL_PAINT]
+            },
+        };
+    }
+}
+
+fn linear_to_srgb(value: f64) -> u8 {
+    let value = value.clamp(0.0, 1.0);
+    let encoded = if value <= 0.0031308 {
+        value * 12.92
+    } else {
+        1.055 * value.powf(1.0 / 2.4) - 0.055
+    };
+
+    (encoded * 255.0).round() as u8
+}
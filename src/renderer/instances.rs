@@ -8,6 +8,10 @@ pub struct LineInstance {
     pub position_2: [f32;2],
     pub line_colour: [f32;4],
     pub line_width: f32,
+    pub end_colour: [f32;4],
+    pub dash: [f32;4],
+    pub dash_phase: f32,
+    pub depth: f32,
 }
 
 impl <'a> LineInstance {
@@ -36,6 +40,26 @@ impl <'a> LineInstance {
                     offset: mem::size_of::<[f32; 8]>() as wgpu::BufferAddress,
                     shader_location: 7,
                 },
+                wgpu::VertexAttribute {
+                    format: wgpu::VertexFormat::Float32x4,
+                    offset: mem::size_of::<[f32; 9]>() as wgpu::BufferAddress,
+                    shader_location: 8,
+                },
+                wgpu::VertexAttribute {
+                    format: wgpu::VertexFormat::Float32x4,
+                    offset: mem::size_of::<[f32; 13]>() as wgpu::BufferAddress,
+                    shader_location: 9,
+                },
+                wgpu::VertexAttribute {
+                    format: wgpu::VertexFormat::Float32,
+                    offset: mem::size_of::<[f32; 17]>() as wgpu::BufferAddress,
+                    shader_location: 10,
+                },
+                wgpu::VertexAttribute {
+                    format: wgpu::VertexFormat::Float32,
+                    offset: mem::size_of::<[f32; 18]>() as wgpu::BufferAddress,
+                    shader_location: 11,
+                },
             ]
         }
     }
@@ -53,6 +77,17 @@ pub struct TwoDInstance {
     pub corner_radius: f32,     // 4
     pub rotation: f32,          // 4
     pub shape: u32,             // 4
+    pub border_colour: [f32;4], // 16
+    pub style: u32,             // 4
+    pub shape_params: [f32;4],  // 16
+    pub fill_geometry: [f32;4], // 16
+    pub fill: [u32;2],          // 8 Type & gradient ramp row
+    pub shadow_colour: [f32;4], // 16
+    pub glow_colour: [f32;4],   // 16
+    pub effects: [f32;4],       // 16 Shadow offset, shadow blur & glow radius
+    pub tint_mode: u32,         // 4
+    pub texture_layer: u32,     // 4
+    pub depth: f32,             // 4
 }
 
 impl <'a> TwoDInstance {
@@ -64,48 +99,132 @@ impl <'a> TwoDInstance {
                 wgpu::VertexAttribute {
                     format: wgpu::VertexFormat::Float32x2,
                     offset: 0,
-                    shader_location: 4,
+                    shader_location: 0,
                 },
                 wgpu::VertexAttribute {
                     format: wgpu::VertexFormat::Float32x2,
                     offset: mem::size_of::<[f32; 2]>() as wgpu::BufferAddress,
-                    shader_location: 5,
+                    shader_location: 1,
                 },
                 wgpu::VertexAttribute {
                     format: wgpu::VertexFormat::Float32x4,
                     offset: mem::size_of::<[f32; 4]>() as wgpu::BufferAddress,
-                    shader_location: 6,
+                    shader_location: 2,
                 },
                 wgpu::VertexAttribute {
                     format: wgpu::VertexFormat::Float32x4,
                     offset: mem::size_of::<[f32; 8]>() as wgpu::BufferAddress,
-                    shader_location: 7,
+                    shader_location: 3,
                 },
+                // Opacity, line width, corner radius & rotation together
                 wgpu::VertexAttribute {
-                    format: wgpu::VertexFormat::Float32,
+                    format: wgpu::VertexFormat::Float32x4,
                     offset: mem::size_of::<[f32; 12]>() as wgpu::BufferAddress,
+                    shader_location: 4,
+                },
+                wgpu::VertexAttribute {
+                    format: wgpu::VertexFormat::Uint32,
+                    offset: mem::size_of::<[f32; 16]>() as wgpu::BufferAddress,
+                    shader_location: 5,
+                },
+                wgpu::VertexAttribute {
+                    format: wgpu::VertexFormat::Float32x4,
+                    offset: mem::size_of::<[f32; 17]>() as wgpu::BufferAddress,
+                    shader_location: 6,
+                },
+                wgpu::VertexAttribute {
+                    format: wgpu::VertexFormat::Uint32,
+                    offset: mem::size_of::<[f32; 21]>() as wgpu::BufferAddress,
+                    shader_location: 7,
+                },
+                wgpu::VertexAttribute {
+                    format: wgpu::VertexFormat::Float32x4,
+                    offset: mem::size_of::<[f32; 22]>() as wgpu::BufferAddress,
                     shader_location: 8,
                 },
                 wgpu::VertexAttribute {
-                    format: wgpu::VertexFormat::Float32,
-                    offset: mem::size_of::<[f32; 13]>() as wgpu::BufferAddress,
+                    format: wgpu::VertexFormat::Float32x4,
+                    offset: mem::size_of::<[f32; 26]>() as wgpu::BufferAddress,
                     shader_location: 9,
                 },
                 wgpu::VertexAttribute {
-                    format: wgpu::VertexFormat::Float32,
-                    offset: mem::size_of::<[f32; 14]>() as wgpu::BufferAddress,
+                    format: wgpu::VertexFormat::Uint32x2,
+                    offset: mem::size_of::<[f32; 30]>() as wgpu::BufferAddress,
                     shader_location: 10,
                 },
                 wgpu::VertexAttribute {
-                    format: wgpu::VertexFormat::Float32,
-                    offset: mem::size_of::<[f32; 15]>() as wgpu::BufferAddress,
+                    format: wgpu::VertexFormat::Float32x4,
+                    offset: mem::size_of::<[f32; 32]>() as wgpu::BufferAddress,
                     shader_location: 11,
                 },
                 wgpu::VertexAttribute {
-                    format: wgpu::VertexFormat::Uint32,
-                    offset: mem::size_of::<[f32; 16]>() as wgpu::BufferAddress,
+                    format: wgpu::VertexFormat::Float32x4,
+                    offset: mem::size_of::<[f32; 36]>() as wgpu::BufferAddress,
                     shader_location: 12,
                 },
+                wgpu::VertexAttribute {
+                    format: wgpu::VertexFormat::Float32x4,
+                    offset: mem::size_of::<[f32; 40]>() as wgpu::BufferAddress,
+                    shader_location: 13,
+                },
+                // Tint mode & texture layer together
+                wgpu::VertexAttribute {
+                    format: wgpu::VertexFormat::Uint32x2,
+                    offset: mem::size_of::<[f32; 44]>() as wgpu::BufferAddress,
+                    shader_location: 14,
+                },
+                wgpu::VertexAttribute {
+                    format: wgpu::VertexFormat::Float32,
+                    offset: mem::size_of::<[f32; 46]>() as wgpu::BufferAddress,
+                    shader_location: 15,
+                },
+            ]
+        }
+    }
+}
+
+// Per vertex data for CPU tessellated geometry drawn as a triangle list
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Pod, Zeroable)]
+pub struct TriangleVertex {
+    pub position: [f32;2],      // 8
+    pub colour: [f32;4],        // 16
+    pub edge: [f32;2],          // 8 Pixel distance across the stroke & past the line end
+    pub half_width: f32,        // 4
+    pub paint: [f32;4],         // 16 Position in gradient space, gradient ramp row & gradient type
+}
+
+impl <'a> TriangleVertex {
+    pub fn desc() -> wgpu::VertexBufferLayout<'a> {
+        wgpu::VertexBufferLayout {
+            step_mode: wgpu::VertexStepMode::Vertex,
+            array_stride: mem::size_of::<TriangleVertex>() as wgpu::BufferAddress,
+            attributes: &[
+                wgpu::VertexAttribute {
+                    format: wgpu::VertexFormat::Float32x2,
+                    offset: 0,
+                    shader_location: 4,
+                },
+                wgpu::VertexAttribute {
+                    format: wgpu::VertexFormat::Float32x4,
+                    offset: mem::size_of::<[f32; 2]>() as wgpu::BufferAddress,
+                    shader_location: 5,
+                },
+                wgpu::VertexAttribute {
+                    format: wgpu::VertexFormat::Float32x2,
+                    offset: mem::size_of::<[f32; 6]>() as wgpu::BufferAddress,
+                    shader_location: 6,
+                },
+                wgpu::VertexAttribute {
+                    format: wgpu::VertexFormat::Float32,
+                    offset: mem::size_of::<[f32; 8]>() as wgpu::BufferAddress,
+                    shader_location: 7,
+                },
+                wgpu::VertexAttribute {
+                    format: wgpu::VertexFormat::Float32x4,
+                    offset: mem::size_of::<[f32; 9]>() as wgpu::BufferAddress,
+                    shader_location: 8,
+                },
             ]
         }
     }
@@ -1,25 +1,37 @@
 mod command_executor;
 mod command_manager;
 mod command_processor;
+mod draw_list;
+mod flatten;
+mod gradient;
 mod instances;
+mod path;
 mod pipelines;
 mod renderer;
 mod render_commands;
 mod section_manager;
+mod tessellation;
 
 // public for external use
 pub use renderer::RendererInstance;
 pub use command_manager::CommandManager;
 pub use command_processor::CommandProcessor;
-pub use render_commands::{RenderCommand, LineDescription, TwoDDescription, TwoDTypes};
+pub use render_commands::{RenderCommand, LineDescription, BlendMode, DashPattern, LineJoin, LineCap, PolylineDescription, Curve, CurveDescription, ArcDescription, CURVE_TOLERANCE, FillRule, FillPathDescription, StrokePathDescription, ColourStop, GradientShape, Gradient, TwoDDescription, TwoDTypes, TwoDStyle, TintMode, Fill, Shadow, Glow, PostEffect, MaskMode, Transform2D};
+pub use draw_list::DrawList;
+pub use path::{Path, PathCommand};
 pub use section_manager::SectionManager;
+pub use pipelines::{ShaderError, material_shader};
 
 // for internal use
-use super::{TextureManager, Texture, Section};
-use command_executor::CommandExecutor;
-use command_manager::InternalCommands;
-use instances::{LineInstance, TwoDInstance, ThreeDInstance};
-use pipelines::{PipelineManager, MAX_INSTANCES};
+use super::{TextureManager, MaterialManager, Material, MAX_MATERIAL_TEXTURES, Texture, Section};
+use command_executor::{CommandExecutor, FrameResources};
+use command_manager::{InternalCommands, TwoDBatchKey, PathGeometryKey};
+use render_commands::{MaskState, DrawPass};
+use instances::{LineInstance, TwoDInstance, ThreeDInstance, TriangleVertex};
+use gradient::{GradientRamp, RAMP_WIDTH};
+use pipelines::{PipelineManager, ShapePass, ShapeDraw, PostPipeline, INITIAL_INSTANCES};
+#[cfg(feature = "hot-reload")]
+use pipelines::SHADER_DIRECTORY;
 
 // Trait
 pub trait Renderer<'cmd> {
@@ -0,0 +1,379 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use cgmath::{EuclideanSpace, InnerSpace, Matrix3, SquareMatrix, Transform};
+
+use crate::{Point2, Vector2};
+
+use super::flatten;
+
+// The pieces a path is built from
+#[derive(Debug, Clone, Copy)]
+pub enum PathCommand {
+    MoveTo(Point2),
+    LineTo(Point2),
+    QuadTo {
+        control: Point2,
+        end: Point2,
+    },
+    CubicTo {
+        control_1: Point2,
+        control_2: Point2,
+        end: Point2,
+    },
+    // Elliptical arc to the end point, following the SVG arc parameters
+    ArcTo {
+        radii: Vector2,
+        rotation: f32,
+        large_arc: bool,
+        sweep: bool,
+        end: Point2,
+    },
+    Close,
+}
+
+// One continuous run of a path after it has been flattened into straight segments
+#[derive(Debug)]
+pub struct FlattenedPath {
+    pub points: Vec<Point2>,
+    pub closed: bool,
+}
+
+// Every change to any path gets its own revision, so its tessellation can be found again
+static NEXT_PATH_REVISION: AtomicU64 = AtomicU64::new(1);
+
+// Vector path made of lines, curves and arcs which can be filled or stroked.
+// Build the path once and keep it around to draw it every frame - its triangles are kept between
+// frames while it is drawn the same way and doesn't change.
+#[derive(Debug, Clone, Default)]
+pub struct Path {
+    commands: Vec<PathCommand>,
+    // Copies share a revision as they share a shape, empty paths are all 0
+    revision: u64,
+}
+
+impl Path {
+    pub const fn new() -> Self {
+        Self {
+            commands: Vec::new(),
+            revision: 0,
+        }
+    }
+
+    // Start a new sub path at the point
+    pub fn move_to(&mut self, point: Point2) -> &mut Self {
+        self.push(PathCommand::MoveTo(point))
+    }
+
+    pub fn line_to(&mut self, point: Point2) -> &mut Self {
+        self.push(PathCommand::LineTo(point))
+    }
+
+    pub fn quad_to(&mut self, control: Point2, end: Point2) -> &mut Self {
+        self.push(PathCommand::QuadTo{control, end})
+    }
+
+    pub fn cubic_to(&mut self, control_1: Point2, control_2: Point2, end: Point2) -> &mut Self {
+        self.push(PathCommand::CubicTo{control_1, control_2, end})
+    }
+
+    // Elliptical arc from the current point to the end. Of the four arcs that fit, large arc picks
+    // one of the two sweeping more than half way round and sweep picks one drawn clockwise.
+    pub fn arc_to(&mut self, radii: Vector2, rotation: f32, large_arc: bool, sweep: bool, end: Point2) -> &mut Self {
+        self.push(PathCommand::ArcTo{radii, rotation, large_arc, sweep, end})
+    }
+
+    // Join the current sub path back to its start
+    pub fn close(&mut self) -> &mut Self {
+        self.push(PathCommand::Close)
+    }
+
+    pub fn commands(&self) -> &[PathCommand] {
+        &self.commands
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.commands.is_empty()
+    }
+
+    // Changes every time the path does
+    pub fn revision(&self) -> u64 {
+        self.revision
+    }
+
+    fn push(&mut self, command: PathCommand) -> &mut Self {
+        self.commands.push(command);
+        self.revision = NEXT_PATH_REVISION.fetch_add(1, Ordering::Relaxed);
+        self
+    }
+
+    // Copy of the path moved by a 2D affine transform. Arcs are swapped for cubic curves as
+    // they don't stay elliptical arcs under every transform.
+    pub fn transformed(&self, transform: &Matrix3<f32>) -> Path {
+        let apply = |point: Point2| transform.transform_point(point);
+        let mut path = Path::new();
+        let mut start = Point2::new(0.0, 0.0);
+        let mut current = start;
+
+        for command in &self.commands {
+            match *command {
+                PathCommand::MoveTo(point) => {
+                    path.move_to(apply(point));
+                    start = point;
+                    current = point;
+                },
+                PathCommand::LineTo(point) => {
+                    path.line_to(apply(point));
+                    current = point;
+                },
+                PathCommand::QuadTo{control, end} => {
+                    path.quad_to(apply(control), apply(end));
+                    current = end;
+                },
+                PathCommand::CubicTo{control_1, control_2, end} => {
+                    path.cubic_to(apply(control_1), apply(control_2), apply(end));
+                    current = end;
+                },
+                PathCommand::ArcTo{radii, rotation, large_arc, sweep, end} => {
+                    match CentreArc::from_endpoints(current, radii, rotation, large_arc, sweep, end) {
+                        Some(arc) => {
+                            for [control_1, control_2, end] in arc.to_cubics() {
+                                path.cubic_to(apply(control_1), apply(control_2), apply(end));
+                            }
+                        },
+                        None => {
+                            path.line_to(apply(end));
+                        },
+                    }
+                    current = end;
+                },
+                PathCommand::Close => {
+                    path.close();
+                    current = start;
+                },
+            }
+        }
+
+        path
+    }
+
+    // Smallest box holding every point of the path, including curve control points
+    pub fn bounds(&self) -> Option<(Point2, Point2)> {
+        // Arcs are swapped for curves, which keep their control points close to the arc
+        let path = self.transformed(&Matrix3::identity());
+
+        let mut points = path.commands.into_iter().flat_map(|command| {
+            let points: Vec<Point2> = match command {
+                PathCommand::MoveTo(point) | PathCommand::LineTo(point) => { vec![point] },
+                PathCommand::QuadTo{control, end} => { vec![control, end] },
+                PathCommand::CubicTo{control_1, control_2, end} => { vec![control_1, control_2, end] },
+                PathCommand::ArcTo{..} | PathCommand::Close => { vec![] },
+            };
+            points
+        });
+
+        let first = points.next()?;
+        Some(points.fold((first, first), |(min, max), point| {
+            (
+                Point2::new(min.x.min(point.x), min.y.min(point.y)),
+                Point2::new(max.x.max(point.x), max.y.max(point.y)),
+            )
+        }))
+    }
+
+    // Break the path into sub paths of straight segments no further than the tolerance from the curves
+    pub fn flatten(&self, tolerance: f32) -> Vec<FlattenedPath> {
+        let mut sub_paths = Vec::new();
+        let mut points: Vec<Point2> = Vec::new();
+        let mut start = Point2::new(0.0, 0.0);
+
+        // Move the points built so far out as a sub path
+        let finish = |points: &mut Vec<Point2>, sub_paths: &mut Vec<FlattenedPath>, closed: bool| {
+            if points.len() > 1 {
+                sub_paths.push(FlattenedPath {
+                    points: std::mem::take(points),
+                    closed,
+                });
+            } else {
+                points.clear();
+            }
+        };
+
+        for command in &self.commands {
+            // Drawing without a move starts from wherever the last sub path finished
+            let current = points.last().copied().unwrap_or(start);
+            if points.is_empty() {
+                points.push(current);
+            }
+
+            match *command {
+                PathCommand::MoveTo(point) => {
+                    finish(&mut points, &mut sub_paths, false);
+                    start = point;
+                    points.push(point);
+                },
+                PathCommand::LineTo(point) => {
+                    points.push(point);
+                },
+                PathCommand::QuadTo{control, end} => {
+                    flatten::flatten_quadratic(current, control, end, tolerance, &mut points);
+                },
+                PathCommand::CubicTo{control_1, control_2, end} => {
+                    flatten::flatten_cubic(current, control_1, control_2, end, tolerance, &mut points);
+                },
+                PathCommand::ArcTo{radii, rotation, large_arc, sweep, end} => {
+                    match CentreArc::from_endpoints(current, radii, rotation, large_arc, sweep, end) {
+                        Some(arc) => {
+                            arc.flatten(end, tolerance, &mut points);
+                        },
+                        None => {
+                            // Nothing to draw or a straight line
+                            if (end - current).magnitude() >= f32::EPSILON {
+                                points.push(end);
+                            }
+                        },
+                    }
+                },
+                PathCommand::Close => {
+                    finish(&mut points, &mut sub_paths, true);
+                },
+            }
+        }
+
+        finish(&mut points, &mut sub_paths, false);
+
+        sub_paths
+    }
+}
+
+// An elliptical arc described by its centre and the angles it runs between
+struct CentreArc {
+    centre: Point2,
+    radii: Vector2,
+    rotation: f32,
+    start_angle: f32,
+    sweep_angle: f32,
+}
+
+impl CentreArc {
+    // Convert an SVG style arc into its centre and angles, none if it's really a straight line
+    fn from_endpoints(
+        start: Point2,
+        radii: Vector2,
+        rotation: f32,
+        large_arc: bool,
+        sweep: bool,
+        end: Point2,
+    ) -> Option<Self> {
+        let (mut rx, mut ry) = (radii.x.abs(), radii.y.abs());
+
+        if rx < f32::EPSILON || ry < f32::EPSILON || (end - start).magnitude() < f32::EPSILON {
+            return None;
+        }
+
+        // Move to a space where the ellipse is axis aligned and centred between the points
+        let (sin_r, cos_r) = rotation.sin_cos();
+        let half = (start - end) / 2.0;
+        let x1 = cos_r * half.x + sin_r * half.y;
+        let y1 = -sin_r * half.x + cos_r * half.y;
+
+        // Scale the radii up if they can't reach between the points
+        let lambda = (x1 * x1) / (rx * rx) + (y1 * y1) / (ry * ry);
+        if lambda > 1.0 {
+            rx *= lambda.sqrt();
+            ry *= lambda.sqrt();
+        }
+
+        // Centre in the aligned space
+        let numerator = rx * rx * ry * ry - rx * rx * y1 * y1 - ry * ry * x1 * x1;
+        let denominator = rx * rx * y1 * y1 + ry * ry * x1 * x1;
+        let sign = if large_arc == sweep { -1.0 } else { 1.0 };
+        let coefficient = sign * (numerator / denominator).max(0.0).sqrt();
+        let cx1 = coefficient * rx * y1 / ry;
+        let cy1 = -coefficient * ry * x1 / rx;
+
+        // Back to the real centre
+        let middle = (start.to_vec() + end.to_vec()) / 2.0;
+        let centre = Point2::new(
+            cos_r * cx1 - sin_r * cy1 + middle.x,
+            sin_r * cx1 + cos_r * cy1 + middle.y,
+        );
+
+        // Angles of the start and end around the ellipse
+        let start_angle = ((y1 - cy1) / ry).atan2((x1 - cx1) / rx);
+        let end_angle = ((-y1 - cy1) / ry).atan2((-x1 - cx1) / rx);
+
+        let mut sweep_angle = end_angle - start_angle;
+        if sweep && sweep_angle < 0.0 {
+            sweep_angle += std::f32::consts::PI * 2.0;
+        } else if !sweep && sweep_angle > 0.0 {
+            sweep_angle -= std::f32::consts::PI * 2.0;
+        }
+
+        Some(Self {
+            centre,
+            radii: Vector2::new(rx, ry),
+            rotation,
+            start_angle,
+            sweep_angle,
+        })
+    }
+
+    // Point on the ellipse at the angle
+    fn point_at(&self, angle: f32) -> Point2 {
+        let (sin_r, cos_r) = self.rotation.sin_cos();
+        let (sin_a, cos_a) = angle.sin_cos();
+        let (x, y) = (self.radii.x * cos_a, self.radii.y * sin_a);
+
+        Point2::new(
+            self.centre.x + x * cos_r - y * sin_r,
+            self.centre.y + x * sin_r + y * cos_r,
+        )
+    }
+
+    // Split into quarter turns or less, each close enough to a cubic curve
+    fn to_cubics(&self) -> Vec<[Point2; 3]> {
+        let n_pieces = (self.sweep_angle.abs() / std::f32::consts::FRAC_PI_2).ceil().max(1.0) as usize;
+        let step = self.sweep_angle / n_pieces as f32;
+
+        // Control points sit along the tangents at this fraction of the radius
+        let k = 4.0 / 3.0 * (step / 4.0).tan();
+        let (sin_r, cos_r) = self.rotation.sin_cos();
+        let tangent = |angle: f32| {
+            let (sin_a, cos_a) = angle.sin_cos();
+            let (x, y) = (-self.radii.x * sin_a * k, self.radii.y * cos_a * k);
+            Vector2::new(x * cos_r - y * sin_r, x * sin_r + y * cos_r)
+        };
+
+        (0..n_pieces)
+            .map(|i| {
+                let a0 = self.start_angle + step * i as f32;
+                let a1 = a0 + step;
+
+                [
+                    self.point_at(a0) + tangent(a0),
+                    self.point_at(a1) - tangent(a1),
+                    self.point_at(a1),
+                ]
+            })
+            .collect()
+    }
+
+    // Add the points along the arc after its start, which is already in the output, finishing
+    // exactly on the end point
+    fn flatten(&self, end: Point2, tolerance: f32, output: &mut Vec<Point2>) {
+        let mut arc_points = Vec::new();
+        flatten::flatten_arc(
+            self.centre,
+            self.radii,
+            self.rotation,
+            self.start_angle,
+            self.start_angle + self.sweep_angle,
+            tolerance,
+            &mut arc_points,
+        );
+
+        arc_points.pop();
+        output.extend(arc_points.into_iter().skip(1));
+        output.push(end);
+    }
+}
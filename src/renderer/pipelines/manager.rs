@@ -1,25 +1,61 @@
-use crate::Vector2;
-use super::{CommonUniform, SharedUniform, LineInstance, LinesPipeline, TwoDInstance, TwoDPipeline, Texture, TextPipeline, Section};
+use std::collections::HashMap;
+use std::ops::Range;
+
+use crate::{BlendMode, MaterialHandle};
+use super::{MaskState, DrawPass, Transform2D, CommonUniform, SharedUniform, LineInstance, LinesPipeline, TwoDInstance, TwoDPipeline, TwoDDraw, TriangleVertex, TrianglesPipeline, GradientRamp, RAMP_WIDTH, Texture, TextureManager, MaterialManager, MaterialPipeline, MAX_MATERIAL_TEXTURES, MsaaPipeline, TextPipeline, ShaderError, Section, util::{create_render_pass, create_gradient_bind_group_layout, create_gradient_bind_group, create_material_bind_group_layout, RenderTarget}};
 
 use wgpu::util::DeviceExt;
-pub const MAX_INSTANCES: usize = 500;
+// Number of instances the buffers start with - they grow to fit the frame
+pub const INITIAL_INSTANCES: usize = 1024;
+
+// Number of gradients the ramp texture starts with - it grows to fit the frame
+const INITIAL_RAMP_ROWS: u32 = 16;
+
+// Number of transforms the common uniform starts with - it grows to fit the frame
+const INITIAL_TRANSFORMS: usize = 16;
 
 pub struct PipelineManager {
     // Buffers
     pub shared_uniform_buffer: wgpu::Buffer,
     pub common_uniform_buffer: wgpu::Buffer,
+    transform_stride: usize,
+    transform_capacity: usize,
+
+    // Gradients baked one per row, shared by the 2D and triangles pipelines
+    gradient_ramp: Texture,
+    gradient_bind_group_layout: wgpu::BindGroupLayout,
+    gradient_bind_group: wgpu::BindGroup,
 
     // Pipeline
     pipeline_lines: LinesPipeline,
     pipeline_2d: TwoDPipeline,
+    pipeline_triangles: TrianglesPipeline,
     pipeline_text: TextPipeline,
+
+    // User materials for 2D shapes, built as they are created
+    material_bind_group_layout: wgpu::BindGroupLayout,
+    material_placeholder: Texture,
+    material_pipelines: HashMap<MaterialHandle, MaterialPipeline>,
+
+    // Multisampling - only when more than one sample is requested
+    msaa: Option<MsaaPipeline>,
+
+    // Stencil the shapes are masked with and depth for depth tested draws, multisampled along with the colour
+    mask_target: Texture,
+
+    // Instances of the draw lists in use, kept between frames
+    draw_lists: HashMap<u64, DrawListBuffers>,
+
+    config: wgpu::SurfaceConfiguration,
+    sample_count: u32,
 }
 
 impl PipelineManager {
     pub fn new(
         device: &wgpu::Device,
         config: &wgpu::SurfaceConfiguration,
-    ) -> Self {
+        sample_count: u32,
+    ) -> Result<Self, ShaderError> {
 
         // Shared Uniform
         let shared_uniform = SharedUniform {
@@ -33,171 +69,576 @@ impl PipelineManager {
             usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST
         });
 
-        // Common Uniform
-        let common_uniform = CommonUniform{
-            screen_size: [config.width as f32, config.height as f32],
-        };
-        let common_uniform_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor{
-            label: Some("Commmon Uniform Buffer"),
-            contents: bytemuck::bytes_of(&common_uniform), 
-            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST
-        });
+        // Common Uniform - one for each transform drawn with in the frame, spaced out for binding
+        // at an offset. It is written at the start of each frame.
+        let alignment = device.limits().min_uniform_buffer_offset_alignment as usize;
+        let transform_stride = std::mem::size_of::<CommonUniform>().div_ceil(alignment) * alignment;
+        let common_uniform_buffer = create_common_uniform_buffer(device, transform_stride, INITIAL_TRANSFORMS);
+
+        // Gradient ramp
+        let gradient_ramp = Texture::create_gradient_ramp(device, RAMP_WIDTH as u32, INITIAL_RAMP_ROWS);
+        let gradient_bind_group_layout = create_gradient_bind_group_layout(device);
+        let gradient_bind_group = create_gradient_bind_group(
+            device,
+            &gradient_bind_group_layout,
+            &gradient_ramp,
+        );
 
-        
         // Create pipeline
         let pipeline_lines = LinesPipeline::new(
             device, 
             config, 
-            &common_uniform_buffer
-        );
+            &common_uniform_buffer,
+            sample_count,
+        )?;
 
         let pipeline_2d = TwoDPipeline::new(
             device,
             config,
-            &common_uniform_buffer
-        );
+            &common_uniform_buffer,
+            &gradient_bind_group_layout,
+            sample_count,
+        )?;
+
+        let pipeline_triangles = TrianglesPipeline::new(
+            device,
+            config,
+            &common_uniform_buffer,
+            &gradient_bind_group_layout,
+            sample_count,
+        )?;
 
         let pipeline_text = TextPipeline::new(
             device,
             config,
         );
 
-        Self {
+        // Materials without all their textures have the gaps filled by a placeholder
+        let material_bind_group_layout = create_material_bind_group_layout(device);
+        let material_placeholder = Texture::new(device, 1, 1);
+
+        let msaa = if sample_count > 1 {
+            Some(MsaaPipeline::new(
+                device,
+                config,
+                sample_count,
+            )?)
+        } else {
+            None
+        };
+
+        let mask_target = Texture::create_mask_target(device, config, sample_count);
+
+        Ok(Self {
             // Buffers
             shared_uniform_buffer,
             common_uniform_buffer,
+            transform_stride,
+            transform_capacity: INITIAL_TRANSFORMS,
+
+            // Gradients
+            gradient_ramp,
+            gradient_bind_group_layout,
+            gradient_bind_group,
 
             // Pipelines
             pipeline_lines,
             pipeline_2d,
+            pipeline_triangles,
             pipeline_text,
-        }
+
+            // Materials
+            material_bind_group_layout,
+            material_placeholder,
+            material_pipelines: HashMap::new(),
+
+            msaa,
+            mask_target,
+            draw_lists: HashMap::new(),
+
+            config: config.clone(),
+            sample_count,
+        })
     }
 
     // Resize the pipelines
     pub fn resize(
         &mut self,
         device: &wgpu::Device,
-        queue: &wgpu::Queue,
         config: &wgpu::SurfaceConfiguration,
     ) {
         // Resize the pipelines
         self.pipeline_lines.resize(
-            device,
             config,
         );
         self.pipeline_2d.resize(
+            config,
+        );
+        self.pipeline_triangles.resize(
             device,
             config,
         );
         self.pipeline_text.resize(
             config,
         );
+        for material_pipeline in self.material_pipelines.values_mut() {
+            material_pipeline.resize(
+                config,
+            );
+        }
+        if let Some(msaa) = &mut self.msaa {
+            msaa.resize(
+                device,
+                config,
+            );
+        }
+        self.mask_target = Texture::create_mask_target(device, config, self.sample_count);
+        self.config = config.clone();
+    }
 
-        // Create an updated uniform buffer
-        let common_uniform = CommonUniform{
-            screen_size: [config.width as f32, config.height as f32],
-        };
+    // Write the common uniform for the frame - the first is drawn in place, the rest with the
+    // transforms of the draw lists in the order they are drawn
+    pub fn update_transforms(
+        &mut self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        transforms: &[Transform2D],
+    ) {
+        let n_transforms = transforms.len() + 1;
+        if n_transforms > self.transform_capacity {
+            self.transform_capacity = n_transforms.next_power_of_two();
+            self.common_uniform_buffer = create_common_uniform_buffer(device, self.transform_stride, self.transform_capacity);
+            self.pipeline_lines.bind_uniform(device, &self.common_uniform_buffer);
+            self.pipeline_2d.bind_uniform(device, &self.common_uniform_buffer);
+            self.pipeline_triangles.bind_uniform(device, &self.common_uniform_buffer);
+        }
+
+        let mut contents = vec![0u8; n_transforms * self.transform_stride];
+        let in_place = Transform2D::default();
+        for (transform, entry) in std::iter::once(&in_place).chain(transforms).zip(contents.chunks_mut(self.transform_stride)) {
+            let common_uniform = CommonUniform{
+                screen_size: [self.config.width as f32, self.config.height as f32],
+                transform: transform.to_columns(),
+            };
+            entry[..std::mem::size_of::<CommonUniform>()].copy_from_slice(bytemuck::bytes_of(&common_uniform));
+        }
 
-        // Write the uniform
         queue.write_buffer(
             &self.common_uniform_buffer, 
             0, 
-            bytemuck::bytes_of(&common_uniform));
+            &contents);
+    }
+
+    // Offset of the common uniform holding the transform, as counted by update_transforms
+    pub fn transform_offset(&self, transform: usize) -> u32 {
+        (transform * self.transform_stride) as u32
     }
     
-    // Prepare the buffers
-    pub fn prepare_buffers(
+    // Layout textures are bound with to be drawn by the 2D shapes
+    pub fn texture_bind_group_layout(&self) -> &wgpu::BindGroupLayout {
+        self.pipeline_2d.texture_bind_group_layout()
+    }
+
+    // Build pipelines for new materials and upload the params of changed ones
+    pub fn prepare_materials(
         &mut self,
         device: &wgpu::Device,
-        buffer_dimensions_required: Vector2,
+        queue: &wgpu::Queue,
+        material_manager: &mut MaterialManager,
+        texture_manager: &TextureManager,
     ) {
-        self.pipeline_2d.prepare_buffers(
-            device,
-            &self.common_uniform_buffer,
-            buffer_dimensions_required,
-        );
+        for handle in material_manager.take_needs_preparing() {
+            let material = match material_manager.get_material(&handle) {
+                Some(material) => { material },
+                None => { continue; },
+            };
+
+            if let Some(material_pipeline) = self.material_pipelines.get(&handle) {
+                material_pipeline.update_params(queue, material);
+                continue;
+            }
+
+            let mut textures = [&self.material_placeholder; MAX_MATERIAL_TEXTURES];
+            for (slot, texture_handle) in textures.iter_mut().zip(&material.textures) {
+                if let Some(texture) = texture_manager.get_sub_texture(texture_handle)
+                    .and_then(|sub_texture| texture_manager.get_texture(&sub_texture.texture)) {
+                    // Materials sample plain 2D textures, so can't use one in the texture array
+                    if texture.get_extent().depth_or_array_layers > 1 {
+                        log::warn!("Textures in the texture array can't be used by materials");
+                        continue;
+                    }
+                    *slot = texture;
+                }
+            }
+
+            let material_pipeline = MaterialPipeline::new(
+                device,
+                &self.config,
+                [self.pipeline_2d.bind_group_layout(), &self.gradient_bind_group_layout, self.pipeline_2d.texture_bind_group_layout(), &self.material_bind_group_layout],
+                material,
+                textures,
+                self.sample_count,
+            );
+            self.material_pipelines.insert(handle, material_pipeline);
+        }
     }
 
-    // Update the line instances currently on the GPU
+    // Rebuild the pipelines from the shaders on disk, any that fail keep what they had. Only the
+    // materials given have new source to build from.
+    #[cfg(feature = "hot-reload")]
+    pub fn reload_shaders(
+        &mut self,
+        device: &wgpu::Device,
+        material_manager: &MaterialManager,
+        materials: &[MaterialHandle],
+    ) {
+        let mut results = vec![
+            self.pipeline_lines.reload_shader(device, &self.config),
+            self.pipeline_2d.reload_shader(device, &self.config),
+            self.pipeline_triangles.reload_shader(device, &self.config),
+        ];
+        if let Some(msaa) = &mut self.msaa {
+            results.push(msaa.reload_shader(device, &self.config));
+        }
+
+        for error in results.into_iter().filter_map(Result::err) {
+            log::error!("Keeping the previous shader: {}", error);
+        }
+
+        for handle in materials {
+            if let (Some(material_pipeline), Some(material)) = (self.material_pipelines.get_mut(handle), material_manager.get_material(handle)) {
+                material_pipeline.reload_shader(device, &self.config, material);
+            }
+        }
+    }
+
+    // Upload all of the line instances for the frame, the parts one after another
     pub fn update_line_instances(
-        &self,
+        &mut self,
+        device: &wgpu::Device,
         queue: &wgpu::Queue,
-        instances: &[LineInstance],
+        parts: &[&[LineInstance]],
     ) {
         self.pipeline_lines.update_instance_buffer(
+            device,
             queue,
-            instances,
+            parts,
         )
     }
 
-    // Update the line instances currently on the GPU
+    // Upload all of the 2D instances for the frame, the parts one after another
     pub fn update_two_d_instances(
-        &self,
+        &mut self,
+        device: &wgpu::Device,
         queue: &wgpu::Queue,
-        instances: &[TwoDInstance],
+        parts: &[&[TwoDInstance]],
     ) {
         self.pipeline_2d.update_instance_buffer(
+            device,
             queue,
-            instances,
+            parts,
         )
     }
 
-    // Method for rendering lines - pass on the command to the Lines Pipeline 
-    pub fn render_lines(
+    // Upload the instances of a draw list, unless the GPU already has them from an earlier frame
+    pub fn update_draw_list(
+        &mut self,
+        device: &wgpu::Device,
+        id: u64,
+        revision: u64,
+        line_instances: &[LineInstance],
+        two_d_instances: &[TwoDInstance],
+    ) {
+        if matches!(self.draw_lists.get(&id), Some(buffers) if buffers.revision == revision) {
+            return;
+        }
+
+        let draw_list_buffers = DrawListBuffers {
+            revision,
+            lines: create_instance_buffer(device, "Draw List Line Instance Buffer", line_instances),
+            two_d: create_instance_buffer(device, "Draw List 2D Instance Buffer", two_d_instances),
+        };
+        self.draw_lists.insert(id, draw_list_buffers);
+    }
+
+    // Free the instances of draw lists that are no longer drawn
+    pub fn retain_draw_lists(
+        &mut self,
+        keep: impl Fn(u64) -> bool,
+    ) {
+        self.draw_lists.retain(|id, _| keep(*id));
+    }
+
+    // Upload all of the triangle vertices for the frame
+    pub fn update_triangle_vertices(
         &mut self,
         device: &wgpu::Device,
         queue: &wgpu::Queue,
-        frame_view: &wgpu::TextureView,
-        start_instance: u32,
-        end_instance: u32,
-        load_op: wgpu::LoadOp<wgpu::Color>,
+        vertices: &[TriangleVertex],
     ) {
-        self.pipeline_lines.render_instances(
+        self.pipeline_triangles.update_vertex_buffer(
             device,
             queue,
-            frame_view,
-            start_instance,
-            end_instance,
-            load_op,
-        );
+            vertices,
+        )
     }
 
-    // Method for rendering 2D objects - pass on the command to the 2D Pipeline
-    pub fn render_2d(
+    // Upload all of the gradients for the frame, growing the ramp texture if they don't fit
+    pub fn update_gradient_ramps(
         &mut self,
         device: &wgpu::Device,
         queue: &wgpu::Queue,
+        ramps: &[GradientRamp],
+    ) {
+        let (_, rows) = self.gradient_ramp.get_size();
+        if ramps.len() as u32 > rows {
+            self.gradient_ramp = Texture::create_gradient_ramp(device, RAMP_WIDTH as u32, (ramps.len() as u32).next_power_of_two());
+            self.gradient_bind_group = create_gradient_bind_group(
+                device,
+                &self.gradient_bind_group_layout,
+                &self.gradient_ramp,
+            );
+        }
+
+        queue.write_texture(
+            wgpu::ImageCopyTexture {
+                texture: self.gradient_ramp.get_texture_buffer(),
+                mip_level: 0,
+                origin: wgpu::Origin3d::ZERO,
+                aspect: wgpu::TextureAspect::All,
+            },
+            bytemuck::cast_slice(ramps),
+            wgpu::ImageDataLayout {
+                offset: 0,
+                bytes_per_row: std::num::NonZeroU32::new((RAMP_WIDTH * 4) as u32),
+                rows_per_image: None,
+            },
+            wgpu::Extent3d {
+                width: RAMP_WIDTH as u32,
+                height: ramps.len() as u32,
+                depth_or_array_layers: 1,
+            },
+        );
+    }
+
+    // Record a pass of shape draws, applying its clears first
+    pub fn render_shapes(
+        &mut self,
+        device: &wgpu::Device,
+        encoder: &mut wgpu::CommandEncoder,
         frame_view: &wgpu::TextureView,
-        start_instance: u32,
-        end_instance: u32,
-        texture: Option<&Texture>,
-        load_op: wgpu::LoadOp<wgpu::Color>,
+        texture_manager: &TextureManager,
+        shape_pass: &ShapePass,
     ) {
-        self.pipeline_2d.render_instances(
-            device,
-            queue,
-            frame_view,
-            start_instance,
-            end_instance,
-            texture,
-            load_op,
+        // Build any pipelines the draws need that haven't been used before
+        for draw in &shape_pass.draws {
+            match draw {
+                ShapeDraw::Lines{blend_mode, pass, ..} => {
+                    self.pipeline_lines.prepare_pipeline(device, *blend_mode, *pass);
+                },
+                ShapeDraw::TwoD{material, blend_mode, pass, ..} => {
+                    match material.and_then(|handle| self.material_pipelines.get_mut(&handle)) {
+                        Some(material_pipeline) => { material_pipeline.prepare_pipeline(device, *blend_mode, *pass); },
+                        None => { self.pipeline_2d.prepare_pipeline(device, *blend_mode, *pass); },
+                    }
+                },
+                ShapeDraw::Triangles{..} => {},
+            }
+        }
+
+        let target = render_target(&self.msaa, &self.mask_target, frame_view);
+        let mut rpass = create_render_pass(
+            encoder,
+            target,
+            target.shape_attachment(shape_pass.clear_mask, shape_pass.clear_depth),
+            shape_pass.load_op,
         );
+
+        for draw in &shape_pass.draws {
+            match draw {
+                ShapeDraw::Lines{instances, blend_mode, pass, draw_list, transform_offset} => {
+                    self.pipeline_lines.draw_instances(
+                        &mut rpass,
+                        draw_list.and_then(|id| self.draw_lists.get(&id)).and_then(|buffers| buffers.lines.as_ref()),
+                        instances.clone(),
+                        *blend_mode,
+                        *pass,
+                        *transform_offset,
+                    );
+                },
+
+                ShapeDraw::TwoD{instances, texture, material, blend_mode, pass, draw_list, transform_offset} => {
+                    // Textures not loaded yet are left blank
+                    let texture_bind_group = texture
+                        .and_then(|handle| texture_manager.get_texture(&handle))
+                        .and_then(|texture| texture.get_bind_group());
+
+                    // Unknown materials fall back to the built in fill
+                    let material_pipeline = material.and_then(|handle| self.material_pipelines.get(&handle));
+
+                    self.pipeline_2d.draw_instances(
+                        &mut rpass,
+                        TwoDDraw {
+                            gradient_bind_group: &self.gradient_bind_group,
+                            instance_buffer: draw_list.and_then(|id| self.draw_lists.get(&id)).and_then(|buffers| buffers.two_d.as_ref()),
+                            instances: instances.clone(),
+                            texture_bind_group,
+                            material: material_pipeline,
+                            blend_mode: *blend_mode,
+                            pass: *pass,
+                            transform_offset: *transform_offset,
+                        },
+                    );
+                },
+
+                ShapeDraw::Triangles{vertices, mask} => {
+                    self.pipeline_triangles.draw_vertices(
+                        &mut rpass,
+                        &self.gradient_bind_group,
+                        vertices.clone(),
+                        *mask,
+                        0,
+                    );
+                },
+            }
+        }
     }
 
     // Method for rendering text - pass on the command to the Text Pipeline
     pub fn render_sections(
         &mut self,
         device: &wgpu::Device,
-        queue: &wgpu::Queue,
+        encoder: &mut wgpu::CommandEncoder,
         frame_view: &wgpu::TextureView,
         sections: &mut [Option<Section>],
     ) {
-        self.pipeline_text.render_sections(
-            device,
-            queue,
-            frame_view,
-            sections,
-        );
+        match &self.msaa {
+            // Text can't be multisampled so is drawn to its own layer and laid over the top
+            Some(msaa) => {
+                msaa.clear_text_layer(
+                    encoder,
+                );
+                self.pipeline_text.render_sections(
+                    device,
+                    encoder,
+                    msaa.text_layer_view(),
+                    sections,
+                );
+                msaa.composite_text(
+                    encoder,
+                    frame_view,
+                );
+            },
+            None => {
+                self.pipeline_text.render_sections(
+                    device,
+                    encoder,
+                    frame_view,
+                    sections,
+                );
+            },
+        }
+    }
+
+    // Submit everything recorded for the frame in one go
+    pub fn submit(
+        &mut self,
+        queue: &wgpu::Queue,
+        encoder: wgpu::CommandEncoder,
+    ) {
+        self.pipeline_text.finish();
+        queue.submit(Some(encoder.finish()));
+        self.pipeline_text.recall();
+    }
+}
+
+// A draw recorded into a shape pass. Instances and vertices are ranges of those uploaded for the
+// frame, or of a draw list's own, and the transform is an offset into the common uniform.
+pub enum ShapeDraw {
+    Lines {
+        instances: Range<u32>,
+        blend_mode: BlendMode,
+        pass: DrawPass,
+        draw_list: Option<u64>,
+        transform_offset: u32,
+    },
+    TwoD {
+        instances: Range<u32>,
+        texture: Option<usize>,
+        material: Option<MaterialHandle>,
+        blend_mode: BlendMode,
+        pass: DrawPass,
+        draw_list: Option<u64>,
+        transform_offset: u32,
+    },
+    Triangles {
+        vertices: Range<u32>,
+        mask: MaskState,
+    },
+}
+
+// Consecutive draws sharing a render pass, along with what it clears first
+pub struct ShapePass {
+    pub load_op: wgpu::LoadOp<wgpu::Color>,
+    pub clear_mask: bool,
+    pub clear_depth: bool,
+    pub draws: Vec<ShapeDraw>,
+}
+
+impl ShapePass {
+    pub fn new() -> Self {
+        Self {
+            load_op: wgpu::LoadOp::Load,
+            clear_mask: false,
+            clear_depth: false,
+            draws: Vec::new(),
+        }
+    }
+
+    // Nothing to draw or clear
+    pub fn is_empty(&self) -> bool {
+        self.draws.is_empty() && !self.clear_mask && !self.clear_depth && matches!(self.load_op, wgpu::LoadOp::Load)
+    }
+}
+
+// A draw list's instances on the GPU, and the revision of the list they were uploaded from
+struct DrawListBuffers {
+    revision: u64,
+    lines: Option<wgpu::Buffer>,
+    two_d: Option<wgpu::Buffer>,
+}
+
+// Buffer holding exactly the instances given, none if there aren't any
+fn create_instance_buffer<I: bytemuck::Pod>(device: &wgpu::Device, label: &str, instances: &[I]) -> Option<wgpu::Buffer> {
+    if instances.is_empty() {
+        return None;
+    }
+
+    Some(device.create_buffer_init(&wgpu::util::BufferInitDescriptor{
+        label: Some(label),
+        contents: bytemuck::cast_slice(instances),
+        usage: wgpu::BufferUsages::VERTEX,
+    }))
+}
+
+fn create_common_uniform_buffer(device: &wgpu::Device, stride: usize, n_transforms: usize) -> wgpu::Buffer {
+    device.create_buffer( &wgpu::BufferDescriptor{
+        label: Some("Commmon Uniform Buffer"),
+        mapped_at_creation: false,
+        size: (stride * n_transforms) as wgpu::BufferAddress,
+        usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+    })
+}
+
+// Where the shape pipelines should draw for the given view, masked by the mask target
+fn render_target<'t>(msaa: &'t Option<MsaaPipeline>, mask_target: &'t Texture, frame_view: &'t wgpu::TextureView) -> RenderTarget<'t> {
+    let target: RenderTarget = match msaa {
+        Some(msaa) => { msaa.target(frame_view) },
+        None => { frame_view.into() },
+    };
+
+    RenderTarget {
+        mask: Some(mask_target.get_view()),
+        ..target
     }
 }
\ No newline at end of file
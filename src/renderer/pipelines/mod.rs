@@ -1,16 +1,30 @@
 mod manager;
 mod pipeline_2d;
 mod pipeline_lines;
+mod pipeline_material;
+mod pipeline_msaa;
+mod pipeline_post;
 mod pipeline_text;
+mod pipeline_triangles;
+mod preprocessor;
 mod uniforms;
 mod util;
 
 // for internal use
-use super::{LineInstance, TwoDInstance, Texture, Section}; 
-use pipeline_2d::TwoDPipeline;
+use super::{LineInstance, TwoDInstance, TriangleVertex, GradientRamp, RAMP_WIDTH, Texture, TextureManager, Material, MaterialManager, MAX_MATERIAL_TEXTURES, Section, PostEffect, MaskState, DrawPass, Transform2D}; 
+use pipeline_2d::{TwoDPipeline, TwoDDraw};
 use pipeline_lines::LinesPipeline;
+use pipeline_material::MaterialPipeline;
+use pipeline_msaa::MsaaPipeline;
 use pipeline_text::TextPipeline;
-use uniforms::{CommonUniform, SharedUniform};
+use pipeline_triangles::TrianglesPipeline;
+use preprocessor::{ShaderPreprocessor, built_in_shader};
+use uniforms::{CommonUniform, SharedUniform, PostUniform};
 
 // public for external use
-pub use manager::{PipelineManager,MAX_INSTANCES};
\ No newline at end of file
+pub use manager::{PipelineManager, ShapePass, ShapeDraw, INITIAL_INSTANCES};
+pub use pipeline_post::PostPipeline;
+pub use pipeline_material::material_shader;
+pub use preprocessor::ShaderError;
+#[cfg(feature = "hot-reload")]
+pub use preprocessor::SHADER_DIRECTORY;
\ No newline at end of file
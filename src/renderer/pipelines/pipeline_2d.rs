@@ -1,32 +1,39 @@
 use std::borrow::Cow;
+use std::ops::Range;
 
 use wgpu::SurfaceConfiguration;
 
-use crate::{Texture, Vector2};
+use crate::{BlendMode, Texture};
 
 use super::{
-    MAX_INSTANCES,
+    INITIAL_INSTANCES,
+    DrawPass,
+    MaterialPipeline,
     TwoDInstance,
+    ShaderError,
+    built_in_shader,
     util::*,
 };
 
 pub struct TwoDPipeline {
     // Buffers
     pub instance_buffer_2d: wgpu::Buffer,
+    instance_capacity: usize,
 
-    // Bound texture
-    pipeline_texture: Texture,
+    // Drawn with when the shapes have no texture
+    blank_bind_group: wgpu::BindGroup,
 
     // Bind Groups
     two_d_bind_group_layout: wgpu::BindGroupLayout,
     two_d_bind_group: wgpu::BindGroup,
+    texture_bind_group_layout: wgpu::BindGroupLayout,
 
     // Shader Modules
     module_2d: wgpu::ShaderModule,
 
     // Pipeline
     two_d_pipeline_layout: wgpu::PipelineLayout,
-    pipeline_2d: wgpu::RenderPipeline,
+    pipelines_2d: BlendedPipelines,
 }
 
 impl TwoDPipeline {
@@ -34,184 +41,202 @@ impl TwoDPipeline {
         device: &wgpu::Device,
         config: &wgpu::SurfaceConfiguration,
         common_uniform_buffer: &wgpu::Buffer,
-    ) -> Self {
-
-        // Create instance buffer
-        let instance_buffer_2d_size = (MAX_INSTANCES * std::mem::size_of::<TwoDInstance>()) as wgpu::BufferAddress;
-        let instance_buffer_2d = device.create_buffer( &wgpu::BufferDescriptor{
-            label: Some("Line Instance Buffer"),
-            mapped_at_creation: false,
-            size: instance_buffer_2d_size,
-            usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
-        });
+        gradient_bind_group_layout: &wgpu::BindGroupLayout,
+        sample_count: u32,
+    ) -> Result<Self, ShaderError> {
 
-        // Create Initial Pipeline Texture
-        let pipeline_texture = Texture::new(device, 256, 256);
+        // Create instance buffer, grown to fit the frame as needed
+        let instance_buffer_2d = create_instance_buffer(device, INITIAL_INSTANCES);
 
         // Create bind group layouts
-        let two_d_bind_group_layout = create_main_bind_group_layout(device);
+        let two_d_bind_group_layout = create_instanced_bind_group_layout(device);
+        let texture_bind_group_layout = create_texture_bind_group_layout(device);
 
         // Create the actual bindgroups
-        let two_d_bind_group = create_main_bind_group(
+        let two_d_bind_group = create_instanced_bind_group(
             device, 
             &two_d_bind_group_layout,
             common_uniform_buffer,
-            &pipeline_texture,
         );
 
+        // Create the texture for untextured shapes, kept alive by its bind group
+        let blank_bind_group = Texture::new(device, 1, 1).create_bind_group(device, &texture_bind_group_layout);
+
         // Create Pipeline layout
         let two_d_pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
             label: Some("2D Pipeline Layout"),
             push_constant_ranges: &[],
-            bind_group_layouts: &[&two_d_bind_group_layout],
+            bind_group_layouts: &[&two_d_bind_group_layout, gradient_bind_group_layout, &texture_bind_group_layout],
         });
 
         // Import shaders
         let module_2d = device.create_shader_module(&wgpu::ShaderModuleDescriptor {
             label: Some("2D Pipeline Shader"),
-            source: wgpu::ShaderSource::Wgsl(Cow::Borrowed(include_str!("shaders/two_d_pipeline.wgsl"))),
+            source: wgpu::ShaderSource::Wgsl(Cow::Owned(built_in_shader("two_d_pipeline.wgsl", &INSTANCED_ENTRY_POINTS)?)),
         });
         //let fs_module_2d = device.create_shader_module(&wgpu::include_spirv!("shaders/two_d_pipeline_Fragment.spirv"));
 
-        // Create pipeline
-        let pipeline_2d = create_instanced_pipeline(
-            device, 
-            config,
-            &two_d_pipeline_layout,
-            TwoDInstance::desc(),
-            &module_2d, 
-            false
-        );
+        // Pipelines for each blend mode and pass are built as they are needed
+        let pipelines_2d = BlendedPipelines::new(config.format, sample_count);
 
-        Self {
+        Ok(Self {
             // Buffers
             instance_buffer_2d,
+            instance_capacity: INITIAL_INSTANCES,
 
             // Texture
-            pipeline_texture,
+            blank_bind_group,
 
             // Bind Groups
             two_d_bind_group_layout,
             two_d_bind_group,
+            texture_bind_group_layout,
 
             // Shader Modules
             module_2d,
 
             // Pipeline
             two_d_pipeline_layout,
-            pipeline_2d,
-        }
+            pipelines_2d,
+        })
     }
 
     // Function to resize the pipeline
     pub fn resize(
-        &mut self, 
-        device: &wgpu::Device,
+        &mut self,
         config: &SurfaceConfiguration,
     ) {
-        // Recreate the pipeline
-        self.pipeline_2d = create_instanced_pipeline(
+        self.pipelines_2d.set_format(config.format);
+    }
+
+    // Build the pipeline for the blend mode and pass before drawing with it
+    pub fn prepare_pipeline(
+        &mut self,
+        device: &wgpu::Device,
+        blend_mode: BlendMode,
+        pass: DrawPass,
+    ) {
+        self.pipelines_2d.prepare(
             device,
-            config,
             &self.two_d_pipeline_layout,
             TwoDInstance::desc(),
             &self.module_2d,
-            false
+            blend_mode,
+            pass,
         );
     }
 
-    // Prepare the buffers
-    pub fn prepare_buffers(
+    // Rebuild the shader from disk, keeping the current one if it fails to compile
+    #[cfg(feature = "hot-reload")]
+    pub fn reload_shader(
         &mut self,
         device: &wgpu::Device,
-        common_uniform_buffer: &wgpu::Buffer,
-        buffer_dimensions_required: Vector2,
-    ) {
-        let (width, height) = self.pipeline_texture.get_size();
-        if buffer_dimensions_required.x > width as f32 || buffer_dimensions_required.y > height as f32 {
-            self.pipeline_texture = Texture::new(device, buffer_dimensions_required.x as u32, buffer_dimensions_required.y as u32);
-            self.two_d_bind_group = create_main_bind_group(device, &self.two_d_bind_group_layout, common_uniform_buffer, &self.pipeline_texture);
-        }
+        config: &SurfaceConfiguration,
+    ) -> Result<(), super::ShaderError> {
+        let source = super::ShaderPreprocessor::new().compile_built_in("two_d_pipeline.wgsl", &INSTANCED_ENTRY_POINTS)?;
+        self.module_2d = device.create_shader_module(&wgpu::ShaderModuleDescriptor {
+            label: Some("2D Pipeline Shader"),
+            source: wgpu::ShaderSource::Wgsl(Cow::Owned(source)),
+        });
+        // Rebuild the pipelines as they are next needed
+        self.pipelines_2d.reset(config.format);
+
+        Ok(())
     }
 
-    // Update the instances currently on the GPU
-    pub fn update_instance_buffer(
-        &self,
-        queue: &wgpu::Queue,
-        instances: &[TwoDInstance],
-    ) {
-        queue.write_buffer(
-            &self.instance_buffer_2d, 
-            0, 
-            bytemuck::cast_slice(instances),
-        );
+    // Layout of the bind group shared with user materials
+    pub fn bind_group_layout(&self) -> &wgpu::BindGroupLayout {
+        &self.two_d_bind_group_layout
+    }
+
+    // Layout each texture's bind group is made with
+    pub fn texture_bind_group_layout(&self) -> &wgpu::BindGroupLayout {
+        &self.texture_bind_group_layout
     }
 
-    pub fn render_instances(
+    // Upload the instances for the frame, the parts one after another, growing the buffer if they don't fit
+    pub fn update_instance_buffer(
         &mut self,
         device: &wgpu::Device,
         queue: &wgpu::Queue,
-        frame_view: &wgpu::TextureView,
-        start_instance: u32,
-        end_instance: u32,
-        texture: Option<&Texture>,
-        load_op: wgpu::LoadOp<wgpu::Color>,
+        parts: &[&[TwoDInstance]],
     ) {
-
-        // Create command encoder
-        let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor{
-            label: Some("Instanced Line Command Encoder"),
-        });
-
-        // Copy texture to bound resources
-        if let Some(tex) = texture {
-            encoder.copy_texture_to_texture(
-                wgpu::ImageCopyTexture {
-                    texture: tex.get_texture_buffer(),
-                    mip_level: 0,
-                    origin: wgpu::Origin3d {
-                        x: 0,
-                        y: 0,
-                        z: 0,
-                    },
-                    aspect: wgpu::TextureAspect::All,
-                },
-                wgpu::ImageCopyTexture {
-                    texture: self.pipeline_texture.get_texture_buffer(),
-                    mip_level: 0,
-                    origin: wgpu::Origin3d {
-                        x: 0,
-                        y: 0,
-                        z: 0,
-                    },
-                    aspect: wgpu::TextureAspect::All,
-                }, 
-                tex.get_extent());
+        let n_instances = parts.iter().map(|part| part.len()).sum::<usize>();
+        if n_instances > self.instance_capacity {
+            self.instance_capacity = n_instances.next_power_of_two();
+            self.instance_buffer_2d = create_instance_buffer(device, self.instance_capacity);
         }
 
-        // Create a render pass
-        {
-            let mut rpass = create_render_pass(
-                &mut encoder, 
-                frame_view, 
-                None,
-                load_op,
+        let mut offset = 0;
+        for part in parts.iter().filter(|part| !part.is_empty()) {
+            queue.write_buffer(
+                &self.instance_buffer_2d, 
+                (offset * std::mem::size_of::<TwoDInstance>()) as wgpu::BufferAddress, 
+                bytemuck::cast_slice(part),
             );
+            offset += part.len();
+        }
+    }
 
-            // Set the normal pipeline
-            rpass.set_pipeline(&self.pipeline_2d);
-
-            // Set each of the bind groups
-            rpass.set_bind_group(0, &self.two_d_bind_group, &[]);
+    // Bind the common uniform again after it has been recreated
+    pub fn bind_uniform(
+        &mut self,
+        device: &wgpu::Device,
+        common_uniform_buffer: &wgpu::Buffer,
+    ) {
+        self.two_d_bind_group = create_instanced_bind_group(device, &self.two_d_bind_group_layout, common_uniform_buffer);
+    }
 
-            // Set the instances
-            rpass.set_vertex_buffer(0, self.instance_buffer_2d.slice(..));
+    // Record drawing the instances into the pass
+    pub fn draw_instances<'p>(
+        &'p self,
+        rpass: &mut wgpu::RenderPass<'p>,
+        draw: TwoDDraw<'p>,
+    ) {
+        let TwoDDraw{gradient_bind_group, instance_buffer, instances, texture_bind_group, material, blend_mode, pass, transform_offset} = draw;
 
-            // Render
-            rpass.draw(0..4, start_instance..end_instance);
+        // Set the pipeline for the blend mode and pass
+        match material {
+            Some(material) => { rpass.set_pipeline(material.pipeline(blend_mode, pass)); },
+            None => { rpass.set_pipeline(self.pipelines_2d.get(blend_mode, pass)); },
+        }
+        rpass.set_stencil_reference(1);
+
+        // Set each of the bind groups
+        rpass.set_bind_group(0, &self.two_d_bind_group, &[transform_offset]);
+        rpass.set_bind_group(1, gradient_bind_group, &[]);
+        rpass.set_bind_group(2, texture_bind_group.unwrap_or(&self.blank_bind_group), &[]);
+        if let Some(material) = material {
+            rpass.set_bind_group(3, material.bind_group(), &[]);
         }
 
-        // Complete
-        queue.submit(Some(encoder.finish()));
+        // Set the instances
+        rpass.set_vertex_buffer(0, instance_buffer.unwrap_or(&self.instance_buffer_2d).slice(..));
+
+        // Render
+        rpass.draw(0..4, instances);
     }
-}
\ No newline at end of file
+}
+
+// A batch of 2D instances to draw. They are taken from the buffer given, or the pipeline's own if
+// there isn't one, and moved by the transform at the offset. Shapes without a texture bind group
+// are drawn with a blank one.
+pub struct TwoDDraw<'p> {
+    pub gradient_bind_group: &'p wgpu::BindGroup,
+    pub instance_buffer: Option<&'p wgpu::Buffer>,
+    pub instances: Range<u32>,
+    pub texture_bind_group: Option<&'p wgpu::BindGroup>,
+    pub material: Option<&'p MaterialPipeline>,
+    pub blend_mode: BlendMode,
+    pub pass: DrawPass,
+    pub transform_offset: u32,
+}
+
+fn create_instance_buffer(device: &wgpu::Device, n_instances: usize) -> wgpu::Buffer {
+    device.create_buffer( &wgpu::BufferDescriptor{
+        label: Some("2D Instance Buffer"),
+        mapped_at_creation: false,
+        size: (n_instances * std::mem::size_of::<TwoDInstance>()) as wgpu::BufferAddress,
+        usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
+    })
+}
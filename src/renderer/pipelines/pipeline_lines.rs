@@ -1,17 +1,24 @@
 use std::borrow::Cow;
+use std::ops::Range;
+
+use crate::BlendMode;
 
 use super::{
-    MAX_INSTANCES,
+    INITIAL_INSTANCES,
     LineInstance,
+    DrawPass,
+    ShaderError,
+    built_in_shader,
     util::*,
 };
 
 pub struct LinesPipeline {
     // Buffers
     pub instance_buffer_lines: wgpu::Buffer,
+    instance_capacity: usize,
 
     // Bind Groups
-    _instanced_bindgroup_layout: wgpu::BindGroupLayout,
+    instanced_bindgroup_layout: wgpu::BindGroupLayout,
     line_bind_group: wgpu::BindGroup,
 
     // Shader Modules
@@ -19,7 +26,7 @@ pub struct LinesPipeline {
 
     // Pipeline
     instanced_pipeline_layout: wgpu::PipelineLayout,
-    pipeline_line: wgpu::RenderPipeline,
+    pipelines_line: BlendedPipelines,
 }
 
 impl LinesPipeline {
@@ -27,16 +34,11 @@ impl LinesPipeline {
         device: &wgpu::Device,
         config: &wgpu::SurfaceConfiguration,
         common_uniform_buffer: &wgpu::Buffer,
-    ) -> Self {
-
-        // Create instance buffer
-        let instance_buffer_line_size = (MAX_INSTANCES * std::mem::size_of::<LineInstance>()) as wgpu::BufferAddress;
-        let instance_buffer_lines = device.create_buffer( &wgpu::BufferDescriptor{
-            label: Some("Line Instance Buffer"),
-            mapped_at_creation: false,
-            size: instance_buffer_line_size,
-            usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
-        });
+        sample_count: u32,
+    ) -> Result<Self, ShaderError> {
+
+        // Create instance buffer, grown to fit the frame as needed
+        let instance_buffer_lines = create_instance_buffer(device, INITIAL_INSTANCES);
 
         // Create bind group layouts
         let instanced_bindgroup_layout = create_instanced_bind_group_layout(device);
@@ -58,26 +60,20 @@ impl LinesPipeline {
         // Import shaders
         let module_line = device.create_shader_module(&wgpu::ShaderModuleDescriptor {
             label: Some("Lines Pipeline Shader"),
-            source: wgpu::ShaderSource::Wgsl(Cow::Borrowed(include_str!("shaders/lines_pipeline.wgsl"))),
+            source: wgpu::ShaderSource::Wgsl(Cow::Owned(built_in_shader("lines_pipeline.wgsl", &INSTANCED_ENTRY_POINTS)?)),
         });
         //let fs_module_line = device.create_shader_module(&wgpu::include_spirv!("shaders/lines_Fragment.spirv"));
 
-        // Create pipeline
-        let pipeline_line = create_instanced_pipeline(
-            device,
-            config,
-            &instanced_pipeline_layout,
-            LineInstance::desc(),
-            &module_line, 
-            false
-        );
+        // Pipelines for each blend mode and pass are built as they are needed
+        let pipelines_line = BlendedPipelines::new(config.format, sample_count);
 
-        Self {
+        Ok(Self {
             // Buffers
             instance_buffer_lines,
+            instance_capacity: INITIAL_INSTANCES,
 
             // Bind Groups
-            _instanced_bindgroup_layout: instanced_bindgroup_layout,
+            instanced_bindgroup_layout,
             line_bind_group,
 
             // Shader Modules
@@ -85,78 +81,121 @@ impl LinesPipeline {
 
             // Pipeline
             instanced_pipeline_layout,
-            pipeline_line,
-        }
+            pipelines_line,
+        })
     }
 
     // Function to resize the pipeline
     pub fn resize(
-        &mut self, 
+        &mut self,
+        config: &wgpu::SurfaceConfiguration,
+    ) {
+        self.pipelines_line.set_format(config.format);
+    }
+
+    // Build the pipeline for the blend mode and pass before drawing with it
+    pub fn prepare_pipeline(
+        &mut self,
         device: &wgpu::Device,
-        config: &wgpu::SurfaceConfiguration
+        blend_mode: BlendMode,
+        pass: DrawPass,
     ) {
-        // Recreate the pipeline
-        self.pipeline_line = create_instanced_pipeline(
+        self.pipelines_line.prepare(
             device,
-            config,
             &self.instanced_pipeline_layout,
             LineInstance::desc(),
             &self.module_line,
-            false
+            blend_mode,
+            pass,
         );
     }
 
-    // Update the instances currently on the GPU
-    pub fn update_instance_buffer(
-        &self,
-        queue: &wgpu::Queue,
-        instances: &[LineInstance],
-    ) {
-        queue.write_buffer(
-            &self.instance_buffer_lines, 
-            0, 
-            bytemuck::cast_slice(instances),
-        );
+    // Rebuild the shader from disk, keeping the current one if it fails to compile
+    #[cfg(feature = "hot-reload")]
+    pub fn reload_shader(
+        &mut self,
+        device: &wgpu::Device,
+        config: &wgpu::SurfaceConfiguration,
+    ) -> Result<(), super::ShaderError> {
+        let source = super::ShaderPreprocessor::new().compile_built_in("lines_pipeline.wgsl", &INSTANCED_ENTRY_POINTS)?;
+        self.module_line = device.create_shader_module(&wgpu::ShaderModuleDescriptor {
+            label: Some("Lines Pipeline Shader"),
+            source: wgpu::ShaderSource::Wgsl(Cow::Owned(source)),
+        });
+        // Rebuild the pipelines as they are next needed
+        self.pipelines_line.reset(config.format);
+
+        Ok(())
     }
 
-    pub fn render_instances(
+    // Upload the instances for the frame, the parts one after another, growing the buffer if they don't fit
+    pub fn update_instance_buffer(
         &mut self,
         device: &wgpu::Device,
         queue: &wgpu::Queue,
-        frame_view: &wgpu::TextureView,
-        start_instance: u32,
-        end_instance: u32,
-        load_op: wgpu::LoadOp<wgpu::Color>,
+        parts: &[&[LineInstance]],
     ) {
+        let n_instances = parts.iter().map(|part| part.len()).sum::<usize>();
+        if n_instances > self.instance_capacity {
+            self.instance_capacity = n_instances.next_power_of_two();
+            self.instance_buffer_lines = create_instance_buffer(device, self.instance_capacity);
+        }
 
-        // Create command encoder
-        let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor{
-            label: Some("Instanced Line Command Encoder"),
-        });
-
-        // Create a render pass
-        {
-            let mut rpass = create_render_pass(
-                &mut encoder, 
-                frame_view, 
-                None,
-                load_op,
+        let mut offset = 0;
+        for part in parts.iter().filter(|part| !part.is_empty()) {
+            queue.write_buffer(
+                &self.instance_buffer_lines, 
+                (offset * std::mem::size_of::<LineInstance>()) as wgpu::BufferAddress, 
+                bytemuck::cast_slice(part),
             );
+            offset += part.len();
+        }
+    }
 
-            // Set the normal pipeline
-            rpass.set_pipeline(&self.pipeline_line);
+    // Bind the common uniform again after it has been recreated
+    pub fn bind_uniform(
+        &mut self,
+        device: &wgpu::Device,
+        common_uniform_buffer: &wgpu::Buffer,
+    ) {
+        self.line_bind_group = create_instanced_bind_group(
+            device,
+            &self.instanced_bindgroup_layout,
+            common_uniform_buffer,
+        );
+    }
 
-            // Set each of the bind groups
-            rpass.set_bind_group(0, &self.line_bind_group, &[]);
+    // Record drawing the instances into the pass. They are taken from the buffer given, or the
+    // pipeline's own if there isn't one, and moved by the transform at the offset.
+    pub fn draw_instances<'p>(
+        &'p self,
+        rpass: &mut wgpu::RenderPass<'p>,
+        instance_buffer: Option<&'p wgpu::Buffer>,
+        instances: Range<u32>,
+        blend_mode: BlendMode,
+        pass: DrawPass,
+        transform_offset: u32,
+    ) {
+        // Set the pipeline for the blend mode and pass
+        rpass.set_pipeline(self.pipelines_line.get(blend_mode, pass));
+        rpass.set_stencil_reference(1);
 
-            // Set the instances
-            rpass.set_vertex_buffer(0, self.instance_buffer_lines.slice(..));
+        // Set each of the bind groups
+        rpass.set_bind_group(0, &self.line_bind_group, &[transform_offset]);
 
-            // Render
-            rpass.draw(0..4, start_instance..end_instance);
-        }
+        // Set the instances
+        rpass.set_vertex_buffer(0, instance_buffer.unwrap_or(&self.instance_buffer_lines).slice(..));
 
-        // Complete
-        queue.submit(Some(encoder.finish()));
+        // Render
+        rpass.draw(0..4, instances);
     }
-}
\ No newline at end of file
+}
+
+fn create_instance_buffer(device: &wgpu::Device, n_instances: usize) -> wgpu::Buffer {
+    device.create_buffer( &wgpu::BufferDescriptor{
+        label: Some("Line Instance Buffer"),
+        mapped_at_creation: false,
+        size: (n_instances * std::mem::size_of::<LineInstance>()) as wgpu::BufferAddress,
+        usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
+    })
+}
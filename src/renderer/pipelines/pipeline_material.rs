@@ -0,0 +1,160 @@
+use std::borrow::Cow;
+
+use wgpu::util::DeviceExt;
+
+use crate::BlendMode;
+
+use super::{
+    DrawPass,
+    Material,
+    ShaderError,
+    ShaderPreprocessor,
+    Texture,
+    TwoDInstance,
+    util::*,
+};
+
+// The 2D shader with the material's function painting the shape in place of the built in fill
+pub fn material_shader(shader: &str) -> Result<String, ShaderError> {
+    let mut preprocessor = ShaderPreprocessor::new();
+    preprocessor.define("MATERIAL", "");
+    preprocessor.add_snippet("material", shader);
+    preprocessor.compile_built_in("two_d_pipeline.wgsl", &INSTANCED_ENTRY_POINTS)
+}
+
+// A user material drawn with the 2D instances in place of the built in fill
+pub struct MaterialPipeline {
+    // Buffers
+    material_uniform_buffer: wgpu::Buffer,
+
+    // Bind Groups
+    material_bind_group: wgpu::BindGroup,
+
+    // Shader Modules
+    module_material: wgpu::ShaderModule,
+
+    // Pipeline
+    material_pipeline_layout: wgpu::PipelineLayout,
+    pipelines_material: BlendedPipelines,
+}
+
+impl MaterialPipeline {
+    // Bind group layouts are the 2D, gradient, texture and material layouts in that order
+    pub fn new(
+        device: &wgpu::Device,
+        config: &wgpu::SurfaceConfiguration,
+        bind_group_layouts: [&wgpu::BindGroupLayout; 4],
+        material: &Material,
+        textures: [&Texture; 2],
+        sample_count: u32,
+    ) -> Self {
+
+        // Create uniform buffer
+        let material_uniform_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor{
+            label: Some("Material Uniform Buffer"),
+            contents: bytemuck::cast_slice(&material.params),
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+        });
+
+        // Create the actual bindgroup
+        let material_bind_group = create_material_bind_group(
+            device,
+            bind_group_layouts[3],
+            &material_uniform_buffer,
+            textures,
+        );
+
+        // Create Pipeline layout
+        let material_pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("Material Pipeline Layout"),
+            push_constant_ranges: &[],
+            bind_group_layouts: &bind_group_layouts,
+        });
+
+        // Import shaders - the source was checked when the material was created
+        let module_material = device.create_shader_module(&wgpu::ShaderModuleDescriptor {
+            label: Some("Material Shader"),
+            source: wgpu::ShaderSource::Wgsl(Cow::Borrowed(&material.source)),
+        });
+
+        // Pipelines for each blend mode and pass are built as they are needed
+        let pipelines_material = BlendedPipelines::new(config.format, sample_count);
+
+        Self {
+            // Buffers
+            material_uniform_buffer,
+
+            // Bind Groups
+            material_bind_group,
+
+            // Shader Modules
+            module_material,
+
+            // Pipeline
+            material_pipeline_layout,
+            pipelines_material,
+        }
+    }
+
+    // Function to resize the pipeline
+    pub fn resize(
+        &mut self,
+        config: &wgpu::SurfaceConfiguration,
+    ) {
+        self.pipelines_material.set_format(config.format);
+    }
+
+    // Build the pipeline for the blend mode and pass before drawing with it
+    pub fn prepare_pipeline(
+        &mut self,
+        device: &wgpu::Device,
+        blend_mode: BlendMode,
+        pass: DrawPass,
+    ) {
+        self.pipelines_material.prepare(
+            device,
+            &self.material_pipeline_layout,
+            TwoDInstance::desc(),
+            &self.module_material,
+            blend_mode,
+            pass,
+        );
+    }
+
+    // Rebuild from the material's source after it was recompiled
+    #[cfg(feature = "hot-reload")]
+    pub fn reload_shader(
+        &mut self,
+        device: &wgpu::Device,
+        config: &wgpu::SurfaceConfiguration,
+        material: &Material,
+    ) {
+        self.module_material = device.create_shader_module(&wgpu::ShaderModuleDescriptor {
+            label: Some("Material Shader"),
+            source: wgpu::ShaderSource::Wgsl(Cow::Borrowed(&material.source)),
+        });
+        // Rebuild the pipelines as they are next needed
+        self.pipelines_material.reset(config.format);
+    }
+
+    // Copy the material's params to the GPU
+    pub fn update_params(
+        &self,
+        queue: &wgpu::Queue,
+        material: &Material,
+    ) {
+        queue.write_buffer(
+            &self.material_uniform_buffer,
+            0,
+            bytemuck::cast_slice(&material.params),
+        );
+    }
+
+    pub fn pipeline(&self, blend_mode: BlendMode, pass: DrawPass) -> &wgpu::RenderPipeline {
+        self.pipelines_material.get(blend_mode, pass)
+    }
+
+    pub fn bind_group(&self) -> &wgpu::BindGroup {
+        &self.material_bind_group
+    }
+}
@@ -0,0 +1,188 @@
+use std::borrow::Cow;
+
+use super::{
+    Texture,
+    ShaderError,
+    built_in_shader,
+    util::*,
+};
+
+// Owns the multisampled target that everything is drawn to before being resolved into the frame.
+// The glyph brush can't draw multisampled, so text is drawn to a layer and composited over the top.
+pub struct MsaaPipeline {
+    sample_count: u32,
+
+    // Targets
+    msaa_target: Texture,
+    text_layer: Texture,
+
+    // Bind Groups
+    composite_bind_group_layout: wgpu::BindGroupLayout,
+    composite_bind_group: wgpu::BindGroup,
+
+    // Shader Modules
+    module_composite: wgpu::ShaderModule,
+
+    // Pipeline
+    composite_pipeline_layout: wgpu::PipelineLayout,
+    pipeline_composite: wgpu::RenderPipeline,
+}
+
+impl MsaaPipeline {
+    pub fn new(
+        device: &wgpu::Device,
+        config: &wgpu::SurfaceConfiguration,
+        sample_count: u32,
+    ) -> Result<Self, ShaderError> {
+
+        // Create the targets
+        let msaa_target = Texture::create_multisampled_target(device, config, sample_count);
+        let text_layer = Texture::create_render_target(device, config);
+
+        // Create bind group layouts
+        let composite_bind_group_layout = create_composite_bind_group_layout(device);
+
+        // Create the actual bindgroups
+        let composite_bind_group = create_composite_bind_group(
+            device,
+            &composite_bind_group_layout,
+            &text_layer,
+        );
+
+        // Create Pipeline layout
+        let composite_pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("Composite Pipeline Layout"),
+            push_constant_ranges: &[],
+            bind_group_layouts: &[&composite_bind_group_layout],
+        });
+
+        // Import shaders
+        let module_composite = device.create_shader_module(&wgpu::ShaderModuleDescriptor {
+            label: Some("Composite Pipeline Shader"),
+            source: wgpu::ShaderSource::Wgsl(Cow::Owned(built_in_shader("composite.wgsl", &["vs_main", "fs_main"])?)),
+        });
+
+        // Create pipeline
+        let pipeline_composite = create_composite_pipeline(
+            device,
+            config.format,
+            &composite_pipeline_layout,
+            &module_composite,
+            sample_count,
+        );
+
+        Ok(Self {
+            sample_count,
+
+            // Targets
+            msaa_target,
+            text_layer,
+
+            // Bind Groups
+            composite_bind_group_layout,
+            composite_bind_group,
+
+            // Shader Modules
+            module_composite,
+
+            // Pipeline
+            composite_pipeline_layout,
+            pipeline_composite,
+        })
+    }
+
+    // Function to resize the pipeline
+    pub fn resize(
+        &mut self,
+        device: &wgpu::Device,
+        config: &wgpu::SurfaceConfiguration,
+    ) {
+        // Recreate the targets at the new size
+        self.msaa_target = Texture::create_multisampled_target(device, config, self.sample_count);
+        self.text_layer = Texture::create_render_target(device, config);
+
+        self.composite_bind_group = create_composite_bind_group(
+            device,
+            &self.composite_bind_group_layout,
+            &self.text_layer,
+        );
+
+        // Recreate the pipeline
+        self.pipeline_composite = create_composite_pipeline(
+            device,
+            config.format,
+            &self.composite_pipeline_layout,
+            &self.module_composite,
+            self.sample_count,
+        );
+    }
+
+    // Rebuild the shader from disk, keeping the current one if it fails to compile
+    #[cfg(feature = "hot-reload")]
+    pub fn reload_shader(
+        &mut self,
+        device: &wgpu::Device,
+        config: &wgpu::SurfaceConfiguration,
+    ) -> Result<(), super::ShaderError> {
+        let source = super::ShaderPreprocessor::new().compile_built_in("composite.wgsl", &["vs_main", "fs_main"])?;
+        self.module_composite = device.create_shader_module(&wgpu::ShaderModuleDescriptor {
+            label: Some("Composite Pipeline Shader"),
+            source: wgpu::ShaderSource::Wgsl(Cow::Owned(source)),
+        });
+        self.pipeline_composite = create_composite_pipeline(
+            device,
+            config.format,
+            &self.composite_pipeline_layout,
+            &self.module_composite,
+            self.sample_count,
+        );
+
+        Ok(())
+    }
+
+    // Draw to the multisampled target and resolve into the frame
+    pub fn target<'t>(&'t self, frame_view: &'t wgpu::TextureView) -> RenderTarget<'t> {
+        RenderTarget {
+            view: self.msaa_target.get_view(),
+            resolve_target: Some(frame_view),
+            mask: None,
+        }
+    }
+
+    // The layer text is drawn to before being composited
+    pub fn text_layer_view(&self) -> &wgpu::TextureView {
+        self.text_layer.get_view()
+    }
+
+    // Clear the text layer ready for the next batch of text
+    pub fn clear_text_layer(
+        &self,
+        encoder: &mut wgpu::CommandEncoder,
+    ) {
+        // An empty render pass is enough to apply the clear
+        create_render_pass(
+            encoder,
+            self.text_layer_view().into(),
+            None,
+            wgpu::LoadOp::Clear(wgpu::Color::TRANSPARENT),
+        );
+    }
+
+    // Lay the text over the multisampled target and resolve into the frame
+    pub fn composite_text(
+        &self,
+        encoder: &mut wgpu::CommandEncoder,
+        frame_view: &wgpu::TextureView,
+    ) {
+        let mut rpass = create_render_pass(
+            encoder,
+            self.target(frame_view),
+            None,
+            wgpu::LoadOp::Load,
+        );
+
+        rpass.set_pipeline(&self.pipeline_composite);
+        rpass.set_bind_group(0, &self.composite_bind_group, &[]);
+        rpass.draw(0..3, 0..1);
+    }
+}
@@ -0,0 +1,492 @@
+use std::borrow::Cow;
+use std::collections::HashMap;
+
+use super::{
+    PostEffect,
+    PostUniform,
+    ShaderError,
+    ShaderPreprocessor,
+    Texture,
+    TextureManager,
+    built_in_shader,
+    util::*,
+};
+
+// Offscreen targets: the scene plus two to ping-pong between
+const SCENE_TARGET: usize = 0;
+const N_TARGETS: usize = 3;
+
+// Passes the uniform buffer has room for at first, it grows when a frame needs more
+const INITIAL_PASSES: usize = 8;
+
+// The built in full screen passes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum PostPass {
+    Copy,
+    Blur,
+    Threshold,
+    BloomCombine,
+    Vignette,
+    ColourGrade,
+    ChromaticAberration,
+    Crt,
+}
+
+impl PostPass {
+    const ALL: [PostPass; 8] = [
+        PostPass::Copy,
+        PostPass::Blur,
+        PostPass::Threshold,
+        PostPass::BloomCombine,
+        PostPass::Vignette,
+        PostPass::ColourGrade,
+        PostPass::ChromaticAberration,
+        PostPass::Crt,
+    ];
+
+    fn entry_point(&self) -> &'static str {
+        match self {
+            PostPass::Copy =>                   { "fs_copy" },
+            PostPass::Blur =>                   { "fs_blur" },
+            PostPass::Threshold =>              { "fs_threshold" },
+            PostPass::BloomCombine =>           { "fs_bloom_combine" },
+            PostPass::Vignette =>               { "fs_vignette" },
+            PostPass::ColourGrade =>            { "fs_colour_grade" },
+            PostPass::ChromaticAberration =>    { "fs_chromatic_aberration" },
+            PostPass::Crt =>                    { "fs_crt" },
+        }
+    }
+}
+
+// Which pipeline a pass runs
+#[derive(Debug, Clone, Copy)]
+enum PostShader {
+    BuiltIn(PostPass),
+    Custom(&'static str),
+}
+
+// The second texture a pass samples
+#[derive(Clone, Copy)]
+enum PostAux<'t> {
+    // Nothing extra, so the source is bound again
+    None,
+    Target(usize),
+    // A lookup texture and the handle it is stored under
+    Texture(usize, &'t Texture),
+}
+
+// One full screen pass of the effects chain, drawn into a target or the frame when there isn't one
+struct PostRun<'t> {
+    shader: PostShader,
+    source: usize,
+    aux: PostAux<'t>,
+    destination: Option<usize>,
+    uniform: PostUniform,
+}
+
+pub struct PostPipeline {
+    // Offscreen targets - only created once an effect is requested
+    targets: Vec<Texture>,
+    target_config: wgpu::SurfaceConfiguration,
+
+    // Buffers - every pass in the frame has a uniform in the one buffer, bound at its offset
+    uniform_buffer: wgpu::Buffer,
+    uniform_stride: usize,
+    uniform_capacity: usize,
+
+    // Bind Groups - one for each pair of source and aux targets, made along with the targets
+    post_bind_group_layout: wgpu::BindGroupLayout,
+    target_bind_groups: HashMap<(usize, usize), wgpu::BindGroup>,
+    // By source target and lookup texture, kept while they are used each frame. Textures aren't
+    // replaced once loaded, reloading them writes into the same one.
+    lut_bind_groups: HashMap<(usize, usize), wgpu::BindGroup>,
+
+    // Pipeline
+    post_pipeline_layout: wgpu::PipelineLayout,
+    pipelines: HashMap<PostPass, wgpu::RenderPipeline>,
+    // By source, kept while they are used each frame. Shaders that failed are kept as None so they
+    // are only reported once while in use.
+    custom_pipelines: HashMap<&'static str, Option<wgpu::RenderPipeline>>,
+}
+
+impl PostPipeline {
+    pub fn new(
+        device: &wgpu::Device,
+        config: &wgpu::SurfaceConfiguration,
+    ) -> Result<Self, ShaderError> {
+
+        // Post Uniform - spaced out for binding at an offset
+        let alignment = device.limits().min_uniform_buffer_offset_alignment as usize;
+        let uniform_stride = std::mem::size_of::<PostUniform>().div_ceil(alignment) * alignment;
+        let uniform_buffer = create_post_uniform_buffer(device, uniform_stride, INITIAL_PASSES);
+
+        // Create bind group layouts
+        let post_bind_group_layout = create_post_bind_group_layout(device);
+
+        // Create Pipeline layout
+        let post_pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("Post Pipeline Layout"),
+            push_constant_ranges: &[],
+            bind_group_layouts: &[&post_bind_group_layout],
+        });
+
+        // Import shaders
+        let entry_points: Vec<&str> = PostPass::ALL.iter().map(|pass| pass.entry_point()).collect();
+        let module_post = device.create_shader_module(&wgpu::ShaderModuleDescriptor {
+            label: Some("Post Pipeline Shader"),
+            source: wgpu::ShaderSource::Wgsl(Cow::Owned(built_in_shader("post_pipeline.wgsl", &entry_points)?)),
+        });
+
+        // Create a pipeline for each of the built in passes
+        let pipelines = create_pass_pipelines(
+            device,
+            config.format,
+            &post_pipeline_layout,
+            &module_post,
+        );
+
+        Ok(Self {
+            // Targets
+            targets: Vec::new(),
+            target_config: config.clone(),
+
+            // Buffers
+            uniform_buffer,
+            uniform_stride,
+            uniform_capacity: INITIAL_PASSES,
+
+            // Bind Groups
+            post_bind_group_layout,
+            target_bind_groups: HashMap::new(),
+            lut_bind_groups: HashMap::new(),
+
+            // Pipeline
+            post_pipeline_layout,
+            pipelines,
+            custom_pipelines: HashMap::new(),
+        })
+    }
+
+    // Function to resize the pipeline - targets are recreated on next use
+    pub fn resize(
+        &mut self,
+        config: &wgpu::SurfaceConfiguration,
+    ) {
+        self.target_config = config.clone();
+        self.targets.clear();
+        self.target_bind_groups.clear();
+        self.lut_bind_groups.clear();
+    }
+
+    // Rebuild the built in passes from disk, keeping the current ones if they fail to compile
+    #[cfg(feature = "hot-reload")]
+    pub fn reload_shaders(
+        &mut self,
+        device: &wgpu::Device,
+    ) -> Result<(), ShaderError> {
+        let entry_points: Vec<&str> = PostPass::ALL.iter().map(|pass| pass.entry_point()).collect();
+        let source = ShaderPreprocessor::new().compile_built_in("post_pipeline.wgsl", &entry_points)?;
+        let module_post = device.create_shader_module(&wgpu::ShaderModuleDescriptor {
+            label: Some("Post Pipeline Shader"),
+            source: wgpu::ShaderSource::Wgsl(Cow::Owned(source)),
+        });
+
+        self.pipelines = create_pass_pipelines(
+            device,
+            self.target_config.format,
+            &self.post_pipeline_layout,
+            &module_post,
+        );
+
+        // Custom passes share the prelude so are rebuilt when next used
+        self.custom_pipelines.clear();
+
+        Ok(())
+    }
+
+    // Make sure the offscreen targets exist and the custom passes in use are compiled
+    pub fn prepare(
+        &mut self,
+        device: &wgpu::Device,
+        effects: &[PostEffect],
+    ) {
+        if self.targets.is_empty() {
+            self.targets = (0..N_TARGETS)
+                .map(|_| Texture::create_render_target(device, &self.target_config))
+                .collect();
+            self.bind_targets(device);
+        }
+
+        // Custom passes not used this frame are dropped
+        self.custom_pipelines.retain(|shader, _| {
+            effects.iter().any(|effect| matches!(effect, PostEffect::Custom{shader: used, ..} if used == shader))
+        });
+
+        for effect in effects {
+            if let PostEffect::Custom{shader, ..} = effect {
+                if !self.custom_pipelines.contains_key(shader) {
+                    let pipeline = match custom_shader(shader) {
+                        Ok(source) => {
+                            let module = device.create_shader_module(&wgpu::ShaderModuleDescriptor {
+                                label: Some("Custom Post Shader"),
+                                source: wgpu::ShaderSource::Wgsl(Cow::Owned(source)),
+                            });
+
+                            Some(create_fullscreen_pipeline(
+                                device,
+                                self.target_config.format,
+                                &self.post_pipeline_layout,
+                                &module,
+                                "fs_main",
+                            ))
+                        },
+                        Err(error) => {
+                            log::error!("Skipping custom post effect: {}", error);
+                            None
+                        },
+                    };
+
+                    self.custom_pipelines.insert(shader, pipeline);
+                }
+            }
+        }
+    }
+
+    // The view the scene should be rendered to when effects are active
+    pub fn scene_view(&self) -> &wgpu::TextureView {
+        self.targets[SCENE_TARGET].get_view()
+    }
+
+    // Run the effects in order over the scene and write the result to the frame
+    pub fn apply(
+        &mut self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        encoder: &mut wgpu::CommandEncoder,
+        frame_view: &wgpu::TextureView,
+        effects: &[PostEffect],
+        texture_manager: &TextureManager,
+    ) {
+        let runs = self.plan(effects, texture_manager);
+
+        // Write every uniform for the frame in one go, making room first if needed
+        if runs.len() > self.uniform_capacity {
+            self.uniform_capacity = runs.len().next_power_of_two();
+            self.uniform_buffer = create_post_uniform_buffer(device, self.uniform_stride, self.uniform_capacity);
+            self.bind_targets(device);
+        }
+
+        let mut contents = vec![0u8; runs.len() * self.uniform_stride];
+        for (run, entry) in runs.iter().zip(contents.chunks_mut(self.uniform_stride)) {
+            entry[..std::mem::size_of::<PostUniform>()].copy_from_slice(bytemuck::bytes_of(&run.uniform));
+        }
+        queue.write_buffer(&self.uniform_buffer, 0, &contents);
+
+        // Lookup textures aren't targets, so are bound the first time they are used with a source
+        self.lut_bind_groups.retain(|(source, lut), _| {
+            runs.iter().any(|run| matches!(run.aux, PostAux::Texture(handle, _) if (run.source, handle) == (*source, *lut)))
+        });
+
+        for run in &runs {
+            if let PostAux::Texture(handle, texture) = run.aux {
+                let (layout, uniform_buffer, source) = (&self.post_bind_group_layout, &self.uniform_buffer, &self.targets[run.source]);
+                self.lut_bind_groups.entry((run.source, handle)).or_insert_with(|| {
+                    create_post_bind_group(device, layout, uniform_buffer, source, texture)
+                });
+            }
+        }
+
+        for (i, run) in runs.iter().enumerate() {
+            let pipeline = match run.shader {
+                PostShader::BuiltIn(pass) => { &self.pipelines[&pass] },
+                PostShader::Custom(shader) => {
+                    match self.custom_pipelines.get(shader) {
+                        Some(Some(pipeline)) => { pipeline },
+                        _ => { continue; },
+                    }
+                },
+            };
+
+            let bind_group = match run.aux {
+                PostAux::None => { &self.target_bind_groups[&(run.source, run.source)] },
+                PostAux::Target(aux) => { &self.target_bind_groups[&(run.source, aux)] },
+                PostAux::Texture(handle, _) => { &self.lut_bind_groups[&(run.source, handle)] },
+            };
+
+            let destination = match run.destination {
+                Some(target) => { self.targets[target].get_view() },
+                None => { frame_view },
+            };
+
+            // Create a render pass
+            let mut rpass = create_render_pass(
+                encoder,
+                destination.into(),
+                None,
+                wgpu::LoadOp::Load,
+            );
+
+            rpass.set_pipeline(pipeline);
+            rpass.set_bind_group(0, bind_group, &[(i * self.uniform_stride) as u32]);
+            rpass.draw(0..3, 0..1);
+        }
+    }
+
+    // Turn the effects into the passes that draw them, ending with a copy into the frame
+    fn plan<'t>(&self, effects: &[PostEffect], texture_manager: &'t TextureManager) -> Vec<PostRun<'t>> {
+        let mut runs = Vec::new();
+        let mut run = |shader: PostShader, source: usize, aux: PostAux<'t>, destination: Option<usize>, uniform: PostUniform| {
+            runs.push(PostRun {
+                shader,
+                source,
+                aux,
+                destination,
+                uniform,
+            });
+        };
+        let mut source = SCENE_TARGET;
+
+        for effect in effects {
+            // The two targets not currently holding the result
+            let (a, b) = free_targets(source);
+
+            match effect {
+                PostEffect::GaussianBlur{radius} => {
+                    run(PostShader::BuiltIn(PostPass::Blur), source, PostAux::None, Some(a), self.uniform([1.0, 0.0, *radius, 0.0]));
+                    run(PostShader::BuiltIn(PostPass::Blur), a, PostAux::None, Some(b), self.uniform([0.0, 1.0, *radius, 0.0]));
+                    source = b;
+                },
+
+                PostEffect::Bloom{threshold, intensity, radius} => {
+                    run(PostShader::BuiltIn(PostPass::Threshold), source, PostAux::None, Some(a), self.uniform([*threshold, 0.0, 0.0, 0.0]));
+                    run(PostShader::BuiltIn(PostPass::Blur), a, PostAux::None, Some(b), self.uniform([1.0, 0.0, *radius, 0.0]));
+                    run(PostShader::BuiltIn(PostPass::Blur), b, PostAux::None, Some(a), self.uniform([0.0, 1.0, *radius, 0.0]));
+                    run(PostShader::BuiltIn(PostPass::BloomCombine), source, PostAux::Target(a), Some(b), self.uniform([*intensity, 0.0, 0.0, 0.0]));
+                    source = b;
+                },
+
+                PostEffect::Vignette{radius, softness, colour} => {
+                    let mut post_uniform = self.uniform([*radius, *softness, 0.0, 0.0]);
+                    post_uniform.colour = [colour.r as f32, colour.g as f32, colour.b as f32, colour.a as f32];
+                    run(PostShader::BuiltIn(PostPass::Vignette), source, PostAux::None, Some(a), post_uniform);
+                    source = a;
+                },
+
+                PostEffect::ColourGrade{lut, intensity} => {
+                    // Skip the grade if the lookup texture isn't available
+                    let lut_texture = texture_manager.get_sub_texture(lut)
+                        .and_then(|sub_texture| Some((sub_texture.texture, texture_manager.get_texture(&sub_texture.texture)?)));
+
+                    match lut_texture {
+                        // Grades sample a plain 2D texture, so can't use one in the texture array
+                        Some((_, lut_texture)) if lut_texture.get_extent().depth_or_array_layers > 1 => {
+                            log::warn!("Textures in the texture array can't be used to colour grade");
+                        },
+                        Some((handle, lut_texture)) => {
+                            run(PostShader::BuiltIn(PostPass::ColourGrade), source, PostAux::Texture(handle, lut_texture), Some(a), self.uniform([*intensity, 0.0, 0.0, 0.0]));
+                            source = a;
+                        },
+                        None => {},
+                    }
+                },
+
+                PostEffect::ChromaticAberration{offset} => {
+                    run(PostShader::BuiltIn(PostPass::ChromaticAberration), source, PostAux::None, Some(a), self.uniform([*offset, 0.0, 0.0, 0.0]));
+                    source = a;
+                },
+
+                PostEffect::Crt{scanline_intensity, curvature} => {
+                    run(PostShader::BuiltIn(PostPass::Crt), source, PostAux::None, Some(a), self.uniform([*scanline_intensity, *curvature, 0.0, 0.0]));
+                    source = a;
+                },
+
+                PostEffect::Custom{shader, params} => {
+                    if let Some(Some(_)) = self.custom_pipelines.get(shader) {
+                        run(PostShader::Custom(shader), source, PostAux::None, Some(a), self.uniform(*params));
+                        source = a;
+                    }
+                },
+            }
+        }
+
+        // Finally copy the result into the frame
+        run(PostShader::BuiltIn(PostPass::Copy), source, PostAux::None, None, self.uniform([0.0; 4]));
+
+        runs
+    }
+
+    fn uniform(&self, params: [f32; 4]) -> PostUniform {
+        PostUniform {
+            params,
+            colour: [0.0; 4],
+            screen_size: [self.target_config.width as f32, self.target_config.height as f32],
+            _padding: [0.0; 2],
+        }
+    }
+
+    // Bind every pair of targets as source and aux, along with the uniform buffer. Lookup textures
+    // are bound with the uniform buffer too, so they are bound again when next used.
+    fn bind_targets(&mut self, device: &wgpu::Device) {
+        self.target_bind_groups.clear();
+        self.lut_bind_groups.clear();
+        if self.targets.is_empty() {
+            return;
+        }
+
+        for source in 0..N_TARGETS {
+            for aux in 0..N_TARGETS {
+                let bind_group = create_post_bind_group(
+                    device,
+                    &self.post_bind_group_layout,
+                    &self.uniform_buffer,
+                    &self.targets[source],
+                    &self.targets[aux],
+                );
+                self.target_bind_groups.insert((source, aux), bind_group);
+            }
+        }
+    }
+}
+
+// Pick the two targets that aren't the current source
+fn free_targets(source: usize) -> (usize, usize) {
+    let mut free = (0..N_TARGETS).filter(|target| *target != source);
+    (free.next().unwrap(), free.next().unwrap())
+}
+
+// A pipeline for each of the built in passes, all from the one module
+fn create_pass_pipelines(
+    device: &wgpu::Device,
+    format: wgpu::TextureFormat,
+    layout: &wgpu::PipelineLayout,
+    module: &wgpu::ShaderModule,
+) -> HashMap<PostPass, wgpu::RenderPipeline> {
+    PostPass::ALL.iter()
+        .map(|pass| {
+            let pipeline = create_fullscreen_pipeline(
+                device,
+                format,
+                layout,
+                module,
+                pass.entry_point(),
+            );
+            (*pass, pipeline)
+        })
+        .collect()
+}
+
+// Custom shaders see the same prelude as the built in passes
+fn custom_shader(shader: &str) -> Result<String, ShaderError> {
+    let mut preprocessor = ShaderPreprocessor::new();
+    preprocessor.add_snippet("custom_post", shader);
+    preprocessor.compile("custom_post", "#include \"post_common.wgsl\"\n#include \"custom_post\"", &["fs_main"])
+}
+
+fn create_post_uniform_buffer(device: &wgpu::Device, stride: usize, n_passes: usize) -> wgpu::Buffer {
+    device.create_buffer(&wgpu::BufferDescriptor{
+        label: Some("Post Uniform Buffer"),
+        mapped_at_creation: false,
+        size: (stride * n_passes) as wgpu::BufferAddress,
+        usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+    })
+}
@@ -48,15 +48,10 @@ impl TextPipeline {
     pub fn render_sections(
         &mut self,
         device: &wgpu::Device,
-        queue: &wgpu::Queue,
+        encoder: &mut wgpu::CommandEncoder,
         frame_view: &wgpu::TextureView,
         sections: &mut [Option<Section>],
     ) {
-        // Create command encoder
-        let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor{
-            label: Some("Text Command Encoder"),
-        });
-
         // Loop through the sections and queue if valid
         for section_option in sections.iter_mut() {
             if let Some(section) = section_option.take() {
@@ -69,18 +64,21 @@ impl TextPipeline {
             .draw_queued(
                 &device,
                 &mut self.staging_belt,
-                &mut encoder,
+                encoder,
                 &frame_view,
                 self.canvas_size.0,
                 self.canvas_size.1,
             )
             .expect("Draw queued");
-        
+    }
+
+    // The staging buffers need finishing before the frame is submitted
+    pub fn finish(&mut self) {
         self.staging_belt.finish();
-        // Complete
-        queue.submit(Some(encoder.finish()));
+    }
 
-        // Recall unused staging buffers
+    // Recall unused staging buffers once the frame has been submitted
+    pub fn recall(&mut self) {
         use futures::task::SpawnExt;
 
         self.local_spawner
@@ -89,4 +87,4 @@ impl TextPipeline {
 
         self.local_pool.run_until_stalled();
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,217 @@
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::ops::Range;
+
+use super::{
+    MaskState,
+    TriangleVertex,
+    ShaderError,
+    built_in_shader,
+    util::*,
+};
+
+// Number of vertices the buffer starts with - it grows to fit the frame
+const INITIAL_VERTICES: usize = 6 * 1024;
+
+pub struct TrianglesPipeline {
+    // Buffers
+    vertex_buffer: wgpu::Buffer,
+    vertex_capacity: usize,
+
+    // Bind Groups
+    triangles_bind_group_layout: wgpu::BindGroupLayout,
+    triangles_bind_group: wgpu::BindGroup,
+
+    // Shader Modules
+    module_triangles: wgpu::ShaderModule,
+
+    // Pipeline
+    triangles_pipeline_layout: wgpu::PipelineLayout,
+    pipelines_triangles: HashMap<MaskState, wgpu::RenderPipeline>,
+    sample_count: u32,
+}
+
+impl TrianglesPipeline {
+    pub fn new(
+        device: &wgpu::Device,
+        config: &wgpu::SurfaceConfiguration,
+        common_uniform_buffer: &wgpu::Buffer,
+        gradient_bind_group_layout: &wgpu::BindGroupLayout,
+        sample_count: u32,
+    ) -> Result<Self, ShaderError> {
+
+        // Create vertex buffer
+        let vertex_buffer = create_vertex_buffer(device, INITIAL_VERTICES);
+
+        // Create bind group layouts
+        let triangles_bind_group_layout = create_instanced_bind_group_layout(device);
+
+        // Create the actual bindgroups
+        let triangles_bind_group = create_instanced_bind_group(
+            device,
+            &triangles_bind_group_layout,
+            common_uniform_buffer,
+        );
+
+        // Create Pipeline layout
+        let triangles_pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("Triangles Pipeline Layout"),
+            push_constant_ranges: &[],
+            bind_group_layouts: &[&triangles_bind_group_layout, gradient_bind_group_layout],
+        });
+
+        // Import shaders
+        let module_triangles = device.create_shader_module(&wgpu::ShaderModuleDescriptor {
+            label: Some("Triangles Pipeline Shader"),
+            source: wgpu::ShaderSource::Wgsl(Cow::Owned(built_in_shader("triangles_pipeline.wgsl", &["vs_main", "fs_main"])?)),
+        });
+
+        // Create a pipeline for each mask state
+        let pipelines_triangles = create_masked_pipelines(
+            device,
+            config,
+            &triangles_pipeline_layout,
+            &module_triangles,
+            sample_count,
+        );
+
+        Ok(Self {
+            // Buffers
+            vertex_buffer,
+            vertex_capacity: INITIAL_VERTICES,
+
+            // Bind Groups
+            triangles_bind_group_layout,
+            triangles_bind_group,
+
+            // Shader Modules
+            module_triangles,
+
+            // Pipeline
+            triangles_pipeline_layout,
+            pipelines_triangles,
+            sample_count,
+        })
+    }
+
+    // Function to resize the pipeline
+    pub fn resize(
+        &mut self,
+        device: &wgpu::Device,
+        config: &wgpu::SurfaceConfiguration
+    ) {
+        // Recreate the pipelines
+        self.pipelines_triangles = create_masked_pipelines(
+            device,
+            config,
+            &self.triangles_pipeline_layout,
+            &self.module_triangles,
+            self.sample_count,
+        );
+    }
+
+    // Rebuild the shader from disk, keeping the current one if it fails to compile
+    #[cfg(feature = "hot-reload")]
+    pub fn reload_shader(
+        &mut self,
+        device: &wgpu::Device,
+        config: &wgpu::SurfaceConfiguration,
+    ) -> Result<(), super::ShaderError> {
+        let source = super::ShaderPreprocessor::new().compile_built_in("triangles_pipeline.wgsl", &["vs_main", "fs_main"])?;
+        self.module_triangles = device.create_shader_module(&wgpu::ShaderModuleDescriptor {
+            label: Some("Triangles Pipeline Shader"),
+            source: wgpu::ShaderSource::Wgsl(Cow::Owned(source)),
+        });
+        self.resize(device, config);
+
+        Ok(())
+    }
+
+    // Upload every vertex for the frame, growing the buffer if they don't fit
+    pub fn update_vertex_buffer(
+        &mut self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        vertices: &[TriangleVertex],
+    ) {
+        if vertices.len() > self.vertex_capacity {
+            self.vertex_capacity = vertices.len().next_power_of_two();
+            self.vertex_buffer = create_vertex_buffer(device, self.vertex_capacity);
+        }
+
+        queue.write_buffer(
+            &self.vertex_buffer,
+            0,
+            bytemuck::cast_slice(vertices),
+        );
+    }
+
+    // Bind the common uniform again after it has been recreated
+    pub fn bind_uniform(
+        &mut self,
+        device: &wgpu::Device,
+        common_uniform_buffer: &wgpu::Buffer,
+    ) {
+        self.triangles_bind_group = create_instanced_bind_group(
+            device,
+            &self.triangles_bind_group_layout,
+            common_uniform_buffer,
+        );
+    }
+
+    // Record drawing the vertices into the pass
+    pub fn draw_vertices<'p>(
+        &'p self,
+        rpass: &mut wgpu::RenderPass<'p>,
+        gradient_bind_group: &'p wgpu::BindGroup,
+        vertices: Range<u32>,
+        mask: MaskState,
+        transform_offset: u32,
+    ) {
+        // Set the pipeline for the mask
+        rpass.set_pipeline(&self.pipelines_triangles[&mask]);
+        rpass.set_stencil_reference(1);
+
+        // Set each of the bind groups
+        rpass.set_bind_group(0, &self.triangles_bind_group, &[transform_offset]);
+        rpass.set_bind_group(1, gradient_bind_group, &[]);
+
+        // Set the vertices
+        rpass.set_vertex_buffer(0, self.vertex_buffer.slice(..));
+
+        // Render
+        rpass.draw(vertices, 0..1);
+    }
+}
+
+fn create_masked_pipelines(
+    device: &wgpu::Device,
+    config: &wgpu::SurfaceConfiguration,
+    pipeline_layout: &wgpu::PipelineLayout,
+    shader: &wgpu::ShaderModule,
+    sample_count: u32,
+) -> HashMap<MaskState, wgpu::RenderPipeline> {
+    MaskState::ALL.iter()
+        .map(|mask| {
+            let pipeline = create_vertex_pipeline(
+                device,
+                config,
+                pipeline_layout,
+                TriangleVertex::desc(),
+                shader,
+                *mask,
+                sample_count,
+            );
+            (*mask, pipeline)
+        })
+        .collect()
+}
+
+fn create_vertex_buffer(device: &wgpu::Device, n_vertices: usize) -> wgpu::Buffer {
+    device.create_buffer( &wgpu::BufferDescriptor{
+        label: Some("Triangle Vertex Buffer"),
+        mapped_at_creation: false,
+        size: (n_vertices * std::mem::size_of::<TriangleVertex>()) as wgpu::BufferAddress,
+        usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
+    })
+}
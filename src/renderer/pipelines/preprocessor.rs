@@ -0,0 +1,483 @@
+use std::borrow::Cow;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+// Every built in shader file by its path under shaders/, any of which can be pulled in with #include
+const SHADER_FILES: [(&str, &str); 13] = [
+    ("include/locals.wgsl",         include_str!("shaders/include/locals.wgsl")),
+    ("include/rotate.wgsl",         include_str!("shaders/include/rotate.wgsl")),
+    ("include/ramp.wgsl",           include_str!("shaders/include/ramp.wgsl")),
+    ("include/fullscreen.wgsl",     include_str!("shaders/include/fullscreen.wgsl")),
+    ("include/depth.wgsl",          include_str!("shaders/include/depth.wgsl")),
+    ("two_d_common.wgsl",           include_str!("shaders/two_d_common.wgsl")),
+    ("two_d_pipeline.wgsl",         include_str!("shaders/two_d_pipeline.wgsl")),
+    ("material_common.wgsl",        include_str!("shaders/material_common.wgsl")),
+    ("lines_pipeline.wgsl",         include_str!("shaders/lines_pipeline.wgsl")),
+    ("triangles_pipeline.wgsl",     include_str!("shaders/triangles_pipeline.wgsl")),
+    ("composite.wgsl",              include_str!("shaders/composite.wgsl")),
+    ("post_common.wgsl",            include_str!("shaders/post_common.wgsl")),
+    ("post_pipeline.wgsl",          include_str!("shaders/post_pipeline.wgsl")),
+];
+
+// Where the files are read from when hot reloading, so edits show without a rebuild
+#[cfg(feature = "hot-reload")]
+pub const SHADER_DIRECTORY: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/src/renderer/pipelines/shaders");
+
+// A problem with a shader, located in the file or snippet it came from
+#[derive(Debug, Clone)]
+pub struct ShaderError {
+    pub file: String,
+    // 1 based, 0 when the problem isn't on any one line
+    pub line: usize,
+    pub column: usize,
+    pub message: String,
+}
+
+impl fmt::Display for ShaderError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.line == 0 {
+            write!(f, "{}: {}", self.file, self.message)
+        } else {
+            write!(f, "{}:{}:{}: {}", self.file, self.line, self.column, self.message)
+        }
+    }
+}
+
+impl std::error::Error for ShaderError {}
+
+// Expands the directives in WGSL before it reaches naga:
+//
+//     #include "name"          pastes in a snippet or built in file, once per shader
+//     #define NAME [value]     values replace NAME wherever it appears as a whole word
+//     #undef NAME
+//     #ifdef NAME / #ifndef NAME / #else / #endif
+//
+pub struct ShaderPreprocessor {
+    snippets: HashMap<String, String>,
+    defines: HashMap<String, String>,
+}
+
+impl ShaderPreprocessor {
+    pub fn new() -> Self {
+        Self {
+            snippets: HashMap::new(),
+            defines: HashMap::new(),
+        }
+    }
+
+    pub fn add_snippet(&mut self, name: &str, source: &str) {
+        self.snippets.insert(name.to_owned(), source.to_owned());
+    }
+
+    pub fn define(&mut self, name: &str, value: &str) {
+        self.defines.insert(name.to_owned(), value.to_owned());
+    }
+
+    // Compile one of the files in SHADER_FILES
+    pub fn compile_built_in(&self, file: &str, entry_points: &[&str]) -> Result<String, ShaderError> {
+        let source = built_in_source(file)
+            .ok_or_else(|| ShaderError {
+                file: file.to_owned(),
+                line: 0,
+                column: 0,
+                message: "No built in shader with this name".to_owned(),
+            })?;
+        self.compile(file, &source, entry_points)
+    }
+
+    // Expand the source then check it with naga, including that the entry points exist
+    pub fn compile(&self, file: &str, source: &str, entry_points: &[&str]) -> Result<String, ShaderError> {
+        let mut output = Output {
+            code: String::new(),
+            lines: Vec::new(),
+            files: Vec::new(),
+            included: HashSet::new(),
+            defines: self.defines.clone(),
+        };
+        output.included.insert(file.to_owned());
+        self.expand(file, source, &mut output)?;
+
+        let module = naga::front::wgsl::parse_str(&output.code)
+            .map_err(|error| {
+                let (line, column) = error.location(&output.code);
+                output.error(line, column, error.to_string())
+            })?;
+
+        let mut validator = naga::valid::Validator::new(
+            naga::valid::ValidationFlags::all(),
+            naga::valid::Capabilities::all(),
+        );
+        validator.validate(&module)
+            .map_err(|error| {
+                // The outer error only names the function, the detail is further down the chain
+                let mut message = error.to_string();
+                let mut source = std::error::Error::source(&error);
+                while let Some(inner) = source {
+                    message = format!("{}: {}", message, inner);
+                    source = inner.source();
+                }
+
+                // Spans go from the whole function down to the expression at fault
+                match error.spans().last().and_then(|(span, _)| span.to_range()) {
+                    Some(range) => {
+                        let text = output.code.get(range.clone()).unwrap_or("");
+                        let (line, column) = output.location(range.start + text.len() - text.trim_start().len());
+                        output.error(line, column, message)
+                    },
+                    None => { output.error(0, 0, message) },
+                }
+            })?;
+
+        for entry_point in entry_points {
+            if !module.entry_points.iter().any(|entry| entry.name == *entry_point) {
+                return Err(ShaderError {
+                    file: file.to_owned(),
+                    line: 0,
+                    column: 0,
+                    message: format!("Missing entry point {}", entry_point),
+                });
+            }
+        }
+
+        Ok(output.code)
+    }
+
+    fn expand(&self, file: &str, source: &str, output: &mut Output) -> Result<(), ShaderError> {
+        let file_index = output.files.len();
+        output.files.push(file.to_owned());
+
+        // Each open #ifdef with whether it is currently taking lines
+        let mut conditions: Vec<Condition> = Vec::new();
+
+        for (index, line) in source.lines().enumerate() {
+            let number = index + 1;
+            let active = conditions.iter().all(|condition| condition.active);
+
+            let error = |message: String| ShaderError {
+                file: file.to_owned(),
+                line: number,
+                column: 1,
+                message,
+            };
+
+            let directive = match line.trim().strip_prefix('#') {
+                Some(directive) => { directive },
+                None => {
+                    if active {
+                        let line = substitute(line, &output.defines);
+                        output.push_line(&line, file_index, number);
+                    }
+                    continue;
+                },
+            };
+
+            let mut parts = directive.trim().splitn(3, char::is_whitespace);
+            let keyword = parts.next().unwrap_or("");
+            let name = parts.next().unwrap_or("");
+            let rest = parts.next().unwrap_or("").trim();
+
+            match keyword {
+                "ifdef" | "ifndef" => {
+                    if name.is_empty() {
+                        return Err(error(format!("#{} needs a name", keyword)));
+                    }
+                    conditions.push(Condition {
+                        active: output.defines.contains_key(name) == (keyword == "ifdef"),
+                        has_else: false,
+                        line: number,
+                    });
+                },
+                "else" => {
+                    match conditions.last_mut() {
+                        Some(condition) if !condition.has_else => {
+                            condition.active = !condition.active;
+                            condition.has_else = true;
+                        },
+                        _ => { return Err(error("#else without #ifdef".to_owned())); },
+                    }
+                },
+                "endif" => {
+                    if conditions.pop().is_none() {
+                        return Err(error("#endif without #ifdef".to_owned()));
+                    }
+                },
+
+                // Everything else is skipped in a branch not taken
+                _ if !active => {},
+
+                "define" => {
+                    if name.is_empty() {
+                        return Err(error("#define needs a name".to_owned()));
+                    }
+                    output.defines.insert(name.to_owned(), rest.to_owned());
+                },
+                "undef" => {
+                    output.defines.remove(name);
+                },
+                "include" => {
+                    let include = name.trim_matches('"');
+                    let snippet = self.snippets.get(include)
+                        .map(|snippet| Cow::Borrowed(snippet.as_str()))
+                        .or_else(|| built_in_source(include))
+                        .ok_or_else(|| error(format!("Unknown include \"{}\"", include)))?;
+
+                    if output.included.insert(include.to_owned()) {
+                        self.expand(include, &snippet, output)?;
+                    }
+                },
+                _ => {
+                    return Err(error(format!("Unknown directive #{}", keyword)));
+                },
+            }
+        }
+
+        match conditions.last() {
+            Some(condition) => {
+                Err(ShaderError {
+                    file: file.to_owned(),
+                    line: condition.line,
+                    column: 1,
+                    message: "#ifdef without #endif".to_owned(),
+                })
+            },
+            None => { Ok(()) },
+        }
+    }
+}
+
+// Compile one of the built in shaders without any snippets or defines
+pub fn built_in_shader(file: &str, entry_points: &[&str]) -> Result<String, ShaderError> {
+    ShaderPreprocessor::new().compile_built_in(file, entry_points)
+}
+
+// The copy on disk is preferred when hot reloading, falling back to the one built in
+fn built_in_source(file: &str) -> Option<Cow<'static, str>> {
+    let (_, source) = SHADER_FILES.iter().find(|(name, _)| *name == file)?;
+
+    #[cfg(feature = "hot-reload")]
+    if let Ok(source) = std::fs::read_to_string(std::path::Path::new(SHADER_DIRECTORY).join(file)) {
+        return Some(Cow::Owned(source));
+    }
+
+    Some(Cow::Borrowed(source))
+}
+
+struct Condition {
+    active: bool,
+    has_else: bool,
+    line: usize,
+}
+
+// The expanded code with where each of its lines came from
+struct Output {
+    code: String,
+    lines: Vec<(usize, usize)>,
+    files: Vec<String>,
+    included: HashSet<String>,
+    defines: HashMap<String, String>,
+}
+
+impl Output {
+    fn push_line(&mut self, line: &str, file_index: usize, number: usize) {
+        self.code.push_str(line);
+        self.code.push('\n');
+        self.lines.push((file_index, number));
+    }
+
+    // 1 based line and column in the expanded code of a byte offset
+    fn location(&self, offset: usize) -> (usize, usize) {
+        let before = &self.code[..offset.min(self.code.len())];
+        let line = before.matches('\n').count() + 1;
+        let column = before.len() - before.rfind('\n').map_or(0, |newline| newline + 1) + 1;
+        (line, column)
+    }
+
+    // Map a line in the expanded code back to the file it came from
+    fn error(&self, line: usize, column: usize, message: String) -> ShaderError {
+        match line.checked_sub(1).and_then(|index| self.lines.get(index)) {
+            Some((file_index, number)) => {
+                ShaderError {
+                    file: self.files[*file_index].clone(),
+                    line: *number,
+                    column,
+                    message,
+                }
+            },
+            None => {
+                ShaderError {
+                    file: self.files[0].clone(),
+                    line: 0,
+                    column: 0,
+                    message,
+                }
+            },
+        }
+    }
+}
+
+// Swap any defined names that have values for those values
+fn substitute(line: &str, defines: &HashMap<String, String>) -> String {
+    if defines.values().all(|value| value.is_empty()) {
+        return line.to_owned();
+    }
+
+    let mut result = String::with_capacity(line.len());
+    let mut word = String::new();
+
+    let flush = |word: &mut String, result: &mut String| {
+        match defines.get(word.as_str()) {
+            Some(value) if !value.is_empty() => { result.push_str(value); },
+            _ => { result.push_str(word); },
+        }
+        word.clear();
+    };
+
+    for c in line.chars() {
+        if c.is_alphanumeric() || c == '_' {
+            word.push(c);
+        } else {
+            flush(&mut word, &mut result);
+            result.push(c);
+        }
+    }
+    flush(&mut word, &mut result);
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Expand the source without compiling it
+    fn expand(preprocessor: &ShaderPreprocessor, source: &str) -> Result<Output, ShaderError> {
+        let mut output = Output {
+            code: String::new(),
+            lines: Vec::new(),
+            files: Vec::new(),
+            included: HashSet::new(),
+            defines: preprocessor.defines.clone(),
+        };
+        output.included.insert("main.wgsl".to_owned());
+        preprocessor.expand("main.wgsl", source, &mut output)?;
+
+        Ok(output)
+    }
+
+    fn code_lines(output: &Output) -> Vec<&str> {
+        output.code.lines().collect()
+    }
+
+    #[test]
+    fn include_once() {
+        let mut preprocessor = ShaderPreprocessor::new();
+        preprocessor.add_snippet("inner", "inner line");
+        preprocessor.add_snippet("outer", "#include \"inner\"\nouter line");
+
+        let output = expand(&preprocessor, "#include \"outer\"\n#include \"inner\"\n#include \"outer\"\nmain line").unwrap();
+        assert_eq!(code_lines(&output), vec!["inner line", "outer line", "main line"]);
+
+        let error = expand(&preprocessor, "line\n#include \"missing\"").err().unwrap();
+        assert_eq!((error.file.as_str(), error.line), ("main.wgsl", 2));
+    }
+
+    #[test]
+    fn nested_conditions() {
+        let source = "\
+#ifdef A
+a
+#ifdef B
+a b
+#else
+a not b
+#endif
+#else
+not a
+#ifndef B
+not a not b
+#endif
+#endif
+end";
+
+        let mut preprocessor = ShaderPreprocessor::new();
+        assert_eq!(code_lines(&expand(&preprocessor, source).unwrap()), vec!["not a", "not a not b", "end"]);
+
+        preprocessor.define("A", "");
+        assert_eq!(code_lines(&expand(&preprocessor, source).unwrap()), vec!["a", "a not b", "end"]);
+
+        preprocessor.define("B", "");
+        assert_eq!(code_lines(&expand(&preprocessor, source).unwrap()), vec!["a", "a b", "end"]);
+    }
+
+    #[test]
+    fn unbalanced_conditions() {
+        let preprocessor = ShaderPreprocessor::new();
+
+        let error = expand(&preprocessor, "line\n#ifdef A\n").err().unwrap();
+        assert_eq!(error.line, 2);
+        assert!(expand(&preprocessor, "#endif").is_err());
+        assert!(expand(&preprocessor, "#else").is_err());
+        assert!(expand(&preprocessor, "#ifdef A\n#else\n#else\n#endif").is_err());
+        assert!(expand(&preprocessor, "#unknown").is_err());
+
+        // Directives in a branch not taken are skipped, unknown or not
+        assert!(expand(&preprocessor, "#ifdef A\n#unknown\n#endif").is_ok());
+    }
+
+    #[test]
+    fn define_substitution() {
+        let mut preprocessor = ShaderPreprocessor::new();
+        preprocessor.define("SIZE", "4");
+
+        // Only whole words are replaced
+        let source = "#define SCALE 2.0\nlet a = SIZE * SCALE + SIZES + MY_SIZE;\n#undef SCALE\nlet b = SCALE;";
+        let output = expand(&preprocessor, source).unwrap();
+        assert_eq!(code_lines(&output), vec!["let a = 4 * 2.0 + SIZES + MY_SIZE;", "let b = SCALE;"]);
+
+        // Defines without values only switch conditions
+        let output = expand(&preprocessor, "#define FLAG\n#ifdef FLAG\nFLAG\n#endif").unwrap();
+        assert_eq!(code_lines(&output), vec!["FLAG"]);
+    }
+
+    #[test]
+    fn error_lines_map_back_to_their_files() {
+        let mut preprocessor = ShaderPreprocessor::new();
+        preprocessor.add_snippet("snippet", "// snippet\nsnippet line");
+
+        let output = expand(&preprocessor, "#define X\n// main\n#include \"snippet\"\nmain line").unwrap();
+        assert_eq!(code_lines(&output), vec!["// main", "// snippet", "snippet line", "main line"]);
+
+        let error = output.error(3, 5, "problem".to_owned());
+        assert_eq!((error.file.as_str(), error.line, error.column), ("snippet", 2, 5));
+
+        let error = output.error(4, 1, "problem".to_owned());
+        assert_eq!((error.file.as_str(), error.line, error.column), ("main.wgsl", 4, 1));
+
+        // Anything outside the code is put on the file being compiled with no line
+        let error = output.error(0, 0, "problem".to_owned());
+        assert_eq!((error.file.as_str(), error.line), ("main.wgsl", 0));
+
+        assert_eq!(output.location(0), (1, 1));
+        assert_eq!(output.location("// main\n// sn".len()), (2, 6));
+    }
+
+    #[test]
+    fn compile_errors_are_located() {
+        let mut preprocessor = ShaderPreprocessor::new();
+        preprocessor.add_snippet("broken", "// fine\nfn broken() -> f32 { return 1.0 +; }");
+
+        let error = preprocessor.compile("main.wgsl", "// main\n#include \"broken\"", &[]).err().unwrap();
+        assert_eq!((error.file.as_str(), error.line), ("broken", 2));
+
+        let error = preprocessor.compile("main.wgsl", "fn main_fn() {}", &["vs_main"]).err().unwrap();
+        assert_eq!(error.line, 0);
+    }
+
+    #[test]
+    fn built_in_shaders() {
+        assert!(built_in_shader("composite.wgsl", &["vs_main", "fs_main"]).is_ok());
+
+        let error = built_in_shader("missing.wgsl", &[]).err().unwrap();
+        assert_eq!(error.file, "missing.wgsl");
+    }
+}
@@ -0,0 +1,13 @@
+#include "include/fullscreen.wgsl"
+
+[[group(0), binding(0)]]
+var t_Layer: texture_2d<f32>;
+
+[[group(0), binding(1)]]
+var s_Layer: sampler;
+
+// The layer is already premultiplied so is passed straight through to the blend
+[[stage(fragment)]]
+fn fs_main(in: VertexOutput) -> [[location(0)]] vec4<f32> {
+    return textureSample(t_Layer, s_Layer, in.uv);
+}
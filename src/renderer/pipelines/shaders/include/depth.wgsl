@@ -0,0 +1,21 @@
+// Fragment entry points for depth tested draws, splitting what PAINT gives by whether it is opaque.
+// The opaque parts are drawn first writing depth, then the rest are blended back to front over them.
+let OPAQUE_ALPHA: f32 = 0.999;
+
+[[stage(fragment)]]
+fn fs_opaque(in: VertexOutput) -> [[location(0)]] vec4<f32> {
+    let colour = PAINT(in);
+    if (colour.a < OPAQUE_ALPHA) {
+        discard;
+    }
+    return colour;
+}
+
+[[stage(fragment)]]
+fn fs_transparent(in: VertexOutput) -> [[location(0)]] vec4<f32> {
+    let colour = PAINT(in);
+    if (colour.a >= OPAQUE_ALPHA) {
+        discard;
+    }
+    return colour;
+}
@@ -0,0 +1,20 @@
+struct VertexOutput {
+    [[builtin(position)]]   pos: vec4<f32>;
+    [[location(0)]]         uv: vec2<f32>;
+};
+
+// Single triangle covering the whole target
+[[stage(vertex)]]
+fn vs_main (
+    [[builtin(vertex_index)]]   index: u32,
+) -> VertexOutput {
+    var out: VertexOutput;
+
+    let x = select(-1.0, 3.0, index == u32(1));
+    let y = select(-1.0, 3.0, index == u32(2));
+
+    out.pos = vec4<f32>(x, y, 0.0, 1.0);
+    out.uv = vec2<f32>((x + 1.0) / 2.0, (1.0 - y) / 2.0);
+
+    return out;
+}
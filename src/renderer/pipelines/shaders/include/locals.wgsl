@@ -0,0 +1,13 @@
+// Screen size for the pipelines that draw in pixels, and the transform of the draw list being drawn
+struct Locals {
+    screen_size: vec2<f32>;
+    transform: mat3x2<f32>;
+};
+[[group(0), binding(0)]]
+var<uniform> locals: Locals;
+
+// Position in pixels from the top left to Vulcan canvas space 1 to -1
+fn pixel_to_clip(pixel: vec2<f32>) -> vec4<f32> {
+    let position = locals.transform * vec3<f32>(pixel, 1.0);
+    return vec4<f32>((2.0 * position.x / locals.screen_size.x) - 1.0, 1.0 - (2.0 * position.y / locals.screen_size.y), 0.0, 1.0);
+}
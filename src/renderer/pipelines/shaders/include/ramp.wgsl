@@ -0,0 +1,17 @@
+// Gradient ramps, one premultiplied gradient per row
+[[group(1), binding(0)]]
+var t_Ramp: texture_2d<f32>;
+
+// Look up the gradient colour a fraction of the way along a row of the ramp
+fn ramp_colour(row: i32, t: f32) -> vec4<f32> {
+    let width = textureDimensions(t_Ramp).x;
+    let x = clamp(t, 0.0, 1.0) * f32(width - 1);
+    let left = i32(floor(x));
+    let right = min(left + 1, width - 1);
+
+    return mix(
+        textureLoad(t_Ramp, vec2<i32>(left, row), 0),
+        textureLoad(t_Ramp, vec2<i32>(right, row), 0),
+        fract(x)
+    );
+}
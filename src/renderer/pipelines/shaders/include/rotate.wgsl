@@ -0,0 +1,22 @@
+fn rotate_point(cx: f32, cy: f32, angle: f32, p: vec2<f32>) -> vec2<f32>
+{
+
+  var new_p: vec2<f32> = p;
+
+  let s: f32 = sin(angle);
+  let c: f32 = cos(angle);
+
+  // translate point back to origin:
+  new_p.x = p.x - cx;
+  new_p.y = p.y - cy;
+
+  // rotate point
+  let xnew: f32 = new_p.x * c - new_p.y * s;
+  let ynew: f32 = new_p.x * s + new_p.y * c;
+
+  // translate point back:
+  new_p.x = xnew + cx;
+  new_p.y = ynew + cy;
+
+  return new_p;
+}
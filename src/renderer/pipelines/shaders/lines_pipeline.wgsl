@@ -1,13 +1,18 @@
 struct VertexOutput {
     [[builtin(position)]]   pos: vec4<f32>;
     [[location(0)]] col: vec4<f32>;
+    [[location(1)]] along: f32;
+    [[location(2)]] edge: f32;
+    [[location(3), interpolate(flat)]] dash: vec4<f32>;
+    [[location(4), interpolate(flat)]] dash_phase: f32;
+    [[location(5), interpolate(flat)]] half_width: f32;
+    [[location(6), interpolate(flat)]] line_length: f32;
 };
 
-struct Locals {
-    screen_size: vec2<f32>;
-};
-[[group(0), binding(0)]]
-var<uniform> locals: Locals;
+#include "include/locals.wgsl"
+
+// Distance in pixels the quad is pushed out past the line so the edges can be faded
+let AA_FRINGE: f32 = 1.0;
 
 [[stage(vertex)]]
 fn vs_main (
@@ -16,68 +21,114 @@ fn vs_main (
     [[location(5)]] position_2: vec2<f32>,
     [[location(6)]] line_colour: vec4<f32>,
     [[location(7)]] line_width: f32,
+    [[location(8)]] end_colour: vec4<f32>,
+    [[location(9)]] dash: vec4<f32>,
+    [[location(10)]] dash_phase: f32,
+    [[location(11)]] depth: f32,
 ) -> VertexOutput {
 
     var out: VertexOutput;
 
-    // Pass straight through
-    out.col = line_colour;
-
-    // Create position default (just in case)
-    out.pos = vec4<f32>(0.0, 0.0, 0.0, 1.0);
-
-    // calc postions in Vulcan canvas space 1 to -1
-    let pos1 = vec2<f32>((2.0 * position_1.x / locals.screen_size.x) - 1.0, 1.0 - (2.0 * position_1.y / locals.screen_size.y));
-
-    let pos2 = vec2<f32>((2.0 * position_2.x / locals.screen_size.x) - 1.0, 1.0 - (2.0 * position_2.y / locals.screen_size.y));
+    // Lines thinner than a pixel are drawn a pixel wide and faded instead
+    let half_width = max(line_width, 1.0) / 2.0;
+    let fade = clamp(line_width, 0.0, 1.0);
+    let extent = half_width + AA_FRINGE;
 
-    // Calc single pixel vec
-    let pixel = vec2<f32>(2.0)/locals.screen_size;
+    // Line unit vector and perpendicular unit vector - in pixels
+    let line_length = distance(position_1, position_2);
+    var line_vec = vec2<f32>(1.0, 0.0);
+    if (line_length > 0.0) {
+        line_vec = (position_2 - position_1) / line_length;
+    }
+    let perp = vec2<f32>(-line_vec.y, line_vec.x);
 
-    // Line unit vector and perpendicular unit vector
-    let line_vec = normalize(pos2 - pos1);
-    
-    // Work out the positions & uvs of the all corners
+    // Work out the positions of the all corners
     //
     //    TL          TR
     //    0-----------0
     //    BL          BR
     //
+    var position = position_1;
     if (index == u32(0)) { // BL
-        // CW Perpendicular * Pixel Size * Line Width / 2.0
-        let offset = vec2<f32>(-line_vec.y, line_vec.x) * pixel * line_width / 2.0;
-        // Point 1
-        out.pos.x = pos1.x + offset.x;
-        out.pos.y = pos1.y + offset.y;
+        position = position_1 + perp * extent - line_vec * AA_FRINGE;
+        out.edge = extent;
+        out.along = -AA_FRINGE;
+        out.col = line_colour;
 
     } else if (index == u32(1)) { // TL
-        // CCW Perpendicular * Pixel Size * Line Width / 2.0
-        let offset = vec2<f32>(line_vec.y, -line_vec.x) * pixel * line_width / 2.0;
-        // Point 1
-        out.pos.x = pos1.x + offset.x;
-        out.pos.y = pos1.y + offset.y;
+        position = position_1 - perp * extent - line_vec * AA_FRINGE;
+        out.edge = -extent;
+        out.along = -AA_FRINGE;
+        out.col = line_colour;
 
     } else if (index == u32(2)) { // BR
-        // CW Perpendicular * Pixel Size * Line Width / 2.0
-        let offset = vec2<f32>(-line_vec.y, line_vec.x) * pixel * line_width / 2.0;
-        // Point 2
-        out.pos.x = pos2.x + offset.x;
-        out.pos.y = pos2.y + offset.y;
-
-    } else if (index == u32(3)) { // TR
-        // CCW Perpendicular * Pixel Size * Line Width / 2.0
-        let offset = vec2<f32>(line_vec.y, -line_vec.x) * pixel * line_width / 2.0;
-        // Point 2
-        out.pos.x = pos2.x + offset.x;
-        out.pos.y = pos2.y + offset.y;
-    } 
+        position = position_2 + perp * extent + line_vec * AA_FRINGE;
+        out.edge = extent;
+        out.along = line_length + AA_FRINGE;
+        out.col = end_colour;
+
+    } else { // TR
+        position = position_2 - perp * extent + line_vec * AA_FRINGE;
+        out.edge = -extent;
+        out.along = line_length + AA_FRINGE;
+        out.col = end_colour;
+    }
+
+    out.col.a = out.col.a * fade;
+
+    // calc postion in Vulcan canvas space 1 to -1
+    out.pos = pixel_to_clip(position);
+    out.pos.z = depth;
+
+    // Pass straight through
+    out.dash = dash;
+    out.dash_phase = dash_phase;
+    out.half_width = half_width;
+    out.line_length = line_length;
 
     return out;
 }
 
+// Coverage of a pixel centred at t by the span from start to end
+fn span_coverage(t: f32, start: f32, end: f32) -> f32 {
+    return clamp(min(t - start, end - t) + 0.5, 0.0, 1.0);
+}
+
+fn line_colour(in: VertexOutput) -> vec4<f32> {
+    var col = in.col;
+
+    // Fade out across the sides and the ends
+    let side_coverage = clamp(in.half_width + 0.5 - abs(in.edge), 0.0, 1.0);
+    let end_coverage = span_coverage(in.along, 0.0, in.line_length);
+    var coverage = side_coverage * end_coverage;
+
+    // Pattern runs dash, gap, dash, gap - an empty pattern is a solid line
+    let period = in.dash.x + in.dash.y + in.dash.z + in.dash.w;
+    if (period > 0.0) {
+        let shifted = in.along - in.dash_phase;
+        let t = shifted - period * floor(shifted / period);
+
+        // The first dash is checked again one period on so its start is faded as the pattern wraps
+        var dash_coverage = max(span_coverage(t, 0.0, in.dash.x), span_coverage(t, period, period + in.dash.x));
+
+        // An empty second dash would otherwise leave a half covered pixel
+        let second_start = in.dash.x + in.dash.y;
+        if (in.dash.z > 0.0) {
+            dash_coverage = max(dash_coverage, span_coverage(t, second_start, second_start + in.dash.z));
+        }
 
+        coverage = coverage * dash_coverage;
+    }
+
+    // Colours are premultiplied so fade every channel
+    return col * coverage;
+}
 
 [[stage(fragment)]]
 fn fs_main(in: VertexOutput) -> [[location(0)]] vec4<f32> {
-    return in.col;
-}
\ No newline at end of file
+    return line_colour(in);
+}
+
+#define PAINT line_colour
+#include "include/depth.wgsl"
+
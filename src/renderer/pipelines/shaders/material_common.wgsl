@@ -0,0 +1,38 @@
+// Bindings for custom 2D materials, included before the material's own shader. The material
+// provides
+//
+//     fn material(input: MaterialInput) -> vec4<f32>
+//
+// returning the premultiplied colour of the inside of the shape. The style, shadow, glow and
+// opacity from the description are applied afterwards, as with the built in fill.
+
+struct MaterialUniform {
+    params: array<vec4<f32>, 4>;
+};
+[[group(3), binding(0)]]
+var<uniform> material_uniform: MaterialUniform;
+
+// Extra textures given when the material was created
+[[group(3), binding(1)]]
+var t_Material0: texture_2d<f32>;
+
+[[group(3), binding(2)]]
+var t_Material1: texture_2d<f32>;
+
+[[group(3), binding(3)]]
+var s_Material: sampler;
+
+struct MaterialInput {
+    // Across the shape from 0 at the top left to 1 at the bottom right
+    uv: vec2<f32>;
+    // In pixels from the centre with y pointing up
+    position: vec2<f32>;
+    size: vec2<f32>;
+    // In pixels to the edge of the shape, negative inside
+    distance: f32;
+    colour: vec4<f32>;
+    // Sample of the shape's own texture, if it has one
+    texture: vec4<f32>;
+    // Params given with each draw
+    params: vec4<f32>;
+};
@@ -0,0 +1,19 @@
+#include "include/fullscreen.wgsl"
+
+struct PostLocals {
+    params: vec4<f32>;
+    colour: vec4<f32>;
+    screen_size: vec2<f32>;
+};
+[[group(0), binding(0)]]
+var<uniform> post: PostLocals;
+
+[[group(0), binding(1)]]
+var t_Source: texture_2d<f32>;
+
+[[group(0), binding(2)]]
+var s_Source: sampler;
+
+[[group(0), binding(3)]]
+var t_Aux: texture_2d<f32>;
+
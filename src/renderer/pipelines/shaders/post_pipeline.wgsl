@@ -0,0 +1,119 @@
+#include "post_common.wgsl"
+
+
+fn luminance(c: vec3<f32>) -> f32 {
+    return dot(c, vec3<f32>(0.2126, 0.7152, 0.0722));
+}
+
+[[stage(fragment)]]
+fn fs_copy(in: VertexOutput) -> [[location(0)]] vec4<f32> {
+    return textureSample(t_Source, s_Source, in.uv);
+}
+
+// params.xy = direction, params.z = radius in pixels
+[[stage(fragment)]]
+fn fs_blur(in: VertexOutput) -> [[location(0)]] vec4<f32> {
+    let texel = post.params.xy / post.screen_size;
+    let radius = clamp(post.params.z, 0.0, 64.0);
+    let sigma = max(radius / 2.0, 0.5);
+    let taps = i32(ceil(radius));
+
+    var total = vec4<f32>(0.0);
+    var weight_sum: f32 = 0.0;
+
+    for (var i: i32 = -taps; i <= taps; i = i + 1) {
+        let x = f32(i);
+        let weight = exp(-(x * x) / (2.0 * sigma * sigma));
+        total = total + textureSample(t_Source, s_Source, in.uv + texel * x) * weight;
+        weight_sum = weight_sum + weight;
+    }
+
+    return total / weight_sum;
+}
+
+// params.x = threshold
+[[stage(fragment)]]
+fn fs_threshold(in: VertexOutput) -> [[location(0)]] vec4<f32> {
+    let col = textureSample(t_Source, s_Source, in.uv);
+    let lum = luminance(col.rgb);
+    let keep = max(lum - post.params.x, 0.0) / max(lum, 0.0001);
+
+    return vec4<f32>(col.rgb * keep, col.a);
+}
+
+// Source is the original frame, aux is the blurred highlights. params.x = intensity
+[[stage(fragment)]]
+fn fs_bloom_combine(in: VertexOutput) -> [[location(0)]] vec4<f32> {
+    let col = textureSample(t_Source, s_Source, in.uv);
+    let bloom = textureSample(t_Aux, s_Source, in.uv);
+
+    return vec4<f32>(col.rgb + bloom.rgb * post.params.x, col.a);
+}
+
+// params.x = radius, params.y = softness, colour = vignette colour
+[[stage(fragment)]]
+fn fs_vignette(in: VertexOutput) -> [[location(0)]] vec4<f32> {
+    let col = textureSample(t_Source, s_Source, in.uv);
+
+    let aspect = post.screen_size.x / post.screen_size.y;
+    let centred = (in.uv - vec2<f32>(0.5)) * vec2<f32>(aspect, 1.0) * 2.0;
+    let amount = smoothStep(post.params.x, post.params.x + max(post.params.y, 0.0001), length(centred));
+
+    return vec4<f32>(mix(col.rgb, post.colour.rgb, amount * post.colour.a), col.a);
+}
+
+// Aux is the lookup texture. params.x = intensity
+[[stage(fragment)]]
+fn fs_colour_grade(in: VertexOutput) -> [[location(0)]] vec4<f32> {
+    let col = textureSample(t_Source, s_Source, in.uv);
+
+    // The lookup is indexed in gamma space
+    let graded_in = pow(clamp(col.rgb, vec3<f32>(0.0), vec3<f32>(1.0)), vec3<f32>(1.0 / 2.2));
+
+    let size = f32(textureDimensions(t_Aux).y);
+    let cell = graded_in * (size - 1.0);
+    let slice_lo = floor(cell.b);
+    let slice_hi = min(slice_lo + 1.0, size - 1.0);
+
+    let uv_lo = vec2<f32>((slice_lo * size + cell.r + 0.5) / (size * size), (cell.g + 0.5) / size);
+    let uv_hi = vec2<f32>((slice_hi * size + cell.r + 0.5) / (size * size), (cell.g + 0.5) / size);
+
+    let graded = mix(
+        textureSample(t_Aux, s_Source, uv_lo).rgb,
+        textureSample(t_Aux, s_Source, uv_hi).rgb,
+        cell.b - slice_lo
+    );
+
+    return vec4<f32>(mix(col.rgb, graded, post.params.x), col.a);
+}
+
+// params.x = offset in pixels at the edge of the frame
+[[stage(fragment)]]
+fn fs_chromatic_aberration(in: VertexOutput) -> [[location(0)]] vec4<f32> {
+    let offset = (in.uv - vec2<f32>(0.5)) * 2.0 * post.params.x / post.screen_size;
+
+    let r = textureSample(t_Source, s_Source, in.uv + offset).r;
+    let ga = textureSample(t_Source, s_Source, in.uv).ga;
+    let b = textureSample(t_Source, s_Source, in.uv - offset).b;
+
+    return vec4<f32>(r, ga.x, b, ga.y);
+}
+
+// params.x = scanline intensity, params.y = curvature
+[[stage(fragment)]]
+fn fs_crt(in: VertexOutput) -> [[location(0)]] vec4<f32> {
+    // Barrel distortion
+    var centred = in.uv * 2.0 - vec2<f32>(1.0);
+    centred = centred + centred * (centred.yx * centred.yx) * post.params.y;
+    let uv = centred * 0.5 + vec2<f32>(0.5);
+
+    let col = textureSample(t_Source, s_Source, uv);
+
+    // Anything pulled in from outside the frame is black
+    let inside = all(uv >= vec2<f32>(0.0)) && all(uv <= vec2<f32>(1.0));
+
+    // One dark band every other pixel row
+    let scanline = 1.0 - post.params.x * (0.5 + 0.5 * cos(uv.y * post.screen_size.y * 3.14159265));
+
+    return select(vec4<f32>(0.0, 0.0, 0.0, col.a), vec4<f32>(col.rgb * scanline, col.a), inside);
+}
@@ -0,0 +1,60 @@
+struct VertexOutput {
+    [[builtin(position)]]   pos: vec4<f32>;
+    [[location(0)]]         col: vec4<f32>;
+    [[location(1)]]         edge: vec2<f32>;
+    [[location(2)]]         half_width: f32;
+    [[location(3)]]         gradient_position: vec2<f32>;
+    [[location(4), interpolate(flat)]] gradient: vec2<f32>;
+};
+
+#include "include/locals.wgsl"
+
+#include "include/ramp.wgsl"
+
+[[stage(vertex)]]
+fn vs_main (
+    [[location(4)]] position: vec2<f32>,
+    [[location(5)]] colour: vec4<f32>,
+    [[location(6)]] edge: vec2<f32>,
+    [[location(7)]] half_width: f32,
+    [[location(8)]] paint: vec4<f32>,
+) -> VertexOutput {
+
+    var out: VertexOutput;
+
+    // Pass straight through
+    out.col = colour;
+    out.edge = edge;
+    out.half_width = half_width;
+    out.gradient_position = paint.xy;
+    out.gradient = paint.zw;
+
+    // calc postions in Vulcan canvas space 1 to -1
+    out.pos = pixel_to_clip(position);
+
+    return out;
+}
+
+[[stage(fragment)]]
+fn fs_main(in: VertexOutput) -> [[location(0)]] vec4<f32> {
+    // Coverage across the stroke and past the end of the line - both half covered at the true edge
+    let across = clamp(in.half_width + 0.5 - abs(in.edge.x), 0.0, 1.0);
+    let along = clamp(0.5 - in.edge.y, 0.0, 1.0);
+
+    let coverage = across * along;
+
+    if (coverage <= 0.0) {
+        discard;
+    }
+
+    // Gradients are tinted by the colour - 1 is linear, 2 is radial. Both are premultiplied.
+    var col = in.col;
+    let row = i32(in.gradient.x + 0.5);
+    if (in.gradient.y > 1.5) {
+        col = col * ramp_colour(row, length(in.gradient_position));
+    } else if (in.gradient.y > 0.5) {
+        col = col * ramp_colour(row, in.gradient_position.x);
+    }
+
+    return col * coverage;
+}
@@ -0,0 +1,387 @@
+struct VertexOutput {
+    [[builtin(position)]]   pos: vec4<f32>;
+    [[location(0)]]         uv: vec2<f32>;
+    [[location(1)]]         half_size: vec2<f32>;
+    [[location(2)]]         shape: u32;
+    [[location(3)]]         corner_radius: f32;
+    [[location(4)]]         col: vec4<f32>;
+    [[location(5)]]         tex_coord: vec2<f32>;
+    [[location(6)]]         opacity: f32;
+    [[location(7)]]         border_col: vec4<f32>;
+    [[location(8), interpolate(flat)]] line_width: f32;
+    // Style, tint mode and texture layer
+    [[location(9), interpolate(flat)]] modes: vec3<u32>;
+    [[location(10), interpolate(flat)]] params: vec4<f32>;
+    [[location(11), interpolate(flat)]] fill_geometry: vec4<f32>;
+    [[location(12), interpolate(flat)]] fill: vec2<u32>;
+    [[location(13), interpolate(flat)]] shadow_col: vec4<f32>;
+    [[location(14), interpolate(flat)]] glow_col: vec4<f32>;
+    [[location(15), interpolate(flat)]] effects: vec4<f32>;
+};
+
+#include "include/locals.wgsl"
+
+let PI: f32 = 3.14159265;
+
+#include "include/rotate.wgsl"
+
+[[stage(vertex)]]
+fn vs_main (
+    [[builtin(vertex_index)]]   index: u32,
+    [[location(0)]]             position: vec2<f32>,
+    [[location(1)]]             size: vec2<f32>,
+    [[location(2)]]             colour: vec4<f32>,
+    [[location(3)]]             texture_coords: vec4<f32>,
+    [[location(4)]]             settings: vec4<f32>,
+    [[location(5)]]             shape: u32,
+    [[location(6)]]             border_colour: vec4<f32>,
+    [[location(7)]]             style: u32,
+    [[location(8)]]             shape_params: vec4<f32>,
+    [[location(9)]]             fill_geometry: vec4<f32>,
+    [[location(10)]]            fill: vec2<u32>,
+    [[location(11)]]            shadow_colour: vec4<f32>,
+    [[location(12)]]            glow_colour: vec4<f32>,
+    [[location(13)]]            effects: vec4<f32>,
+    [[location(14)]]            tint_layer: vec2<u32>,
+    [[location(15)]]            depth: f32,
+) -> VertexOutput {
+    var out: VertexOutput;
+
+    // Settings are opacity, line width, corner radius and rotation
+    let rotation = settings.w;
+
+    // Pass straight through
+    out.shape = shape;
+    out.half_size = size / 2.0;
+    out.corner_radius = settings.z;
+    out.col = colour;
+    out.opacity = settings.x;
+    out.border_col = border_colour;
+    out.line_width = settings.y;
+    // Tint layer is the tint mode and texture layer
+    out.modes = vec3<u32>(style, tint_layer.x, tint_layer.y);
+    out.params = shape_params;
+    out.fill_geometry = fill_geometry;
+    out.fill = fill;
+    out.shadow_col = shadow_colour;
+    out.glow_col = glow_colour;
+    out.effects = effects;
+
+    // Effects are the shadow offset, shadow blur and glow radius. The offset is turned into the
+    // shape's own space with y up, so the shadow keeps falling the same way as the shape rotates.
+    let offset = rotate_point(0.0, 0.0, -rotation, effects.xy);
+    out.effects.x = offset.x;
+    out.effects.y = -offset.y;
+
+    // Grow the quad so the shadow and glow aren't cut off
+    let shadow_reach = abs(out.effects.xy) + vec2<f32>(effects.z);
+    let margin = max(max(shadow_reach.x, shadow_reach.y), effects.w);
+
+    // Corners in the order TL, BL, TR, BR with y pointing up
+    var corner = vec2<f32>(-1.0, 1.0);
+    if (index == u32(1)) {
+        corner = vec2<f32>(-1.0, -1.0);
+    } else if (index == u32(2)) {
+        corner = vec2<f32>(1.0, 1.0);
+    } else if (index == u32(3)) {
+        corner = vec2<f32>(1.0, -1.0);
+    }
+
+    let local = corner * (out.half_size + vec2<f32>(margin));
+    out.uv = local / out.half_size;
+
+    // Texture coordinates carry on past the edges of the shape
+    let along = vec2<f32>(local.x / size.x + 0.5, 0.5 - local.y / size.y);
+    out.tex_coord = mix(texture_coords.xy, texture_coords.zw, along);
+
+    // Rotate about the middle and calc postion in Vulcan canvas space 1 to -1
+    let midpoint = position + out.half_size;
+    let point = rotate_point(midpoint.x, midpoint.y, rotation, midpoint + vec2<f32>(local.x, -local.y));
+
+    out.pos = pixel_to_clip(point);
+    out.pos.z = depth;
+
+    return out;
+}
+
+
+// The texture of the batch, each has its own bind group. Textures are all bound as arrays, those
+// that aren't the texture array having the one layer.
+[[group(2), binding(0)]]
+var t_Color: texture_2d_array<f32>;
+
+[[group(2), binding(1)]]
+var s_Color: sampler;
+
+#include "include/ramp.wgsl"
+
+// Fill types
+let FILL_SOLID: u32 = 0u;
+let FILL_LINEAR: u32 = 1u;
+let FILL_RADIAL: u32 = 2u;
+
+// Colour of the fill at a position in pixels from the centre of the quad
+fn fill_colour(in: VertexOutput, p: vec2<f32>, tint: vec4<f32>) -> vec4<f32> {
+    if (in.fill.x == FILL_SOLID) {
+        return tint;
+    }
+
+    // Positions are fractions of the size from the top left, with y pointing down
+    let size = in.half_size * 2.0;
+    let position = vec2<f32>(p.x, -p.y) + in.half_size;
+    let g = in.fill_geometry;
+
+    var t: f32 = 0.0;
+    if (in.fill.x == FILL_LINEAR) {
+        let start = g.xy * size;
+        let direction = g.zw * size - start;
+        t = dot(position - start, direction) / max(dot(direction, direction), 0.0001);
+    } else if (in.fill.x == FILL_RADIAL) {
+        let radius = g.z * min(size.x, size.y);
+        t = length(position - g.xy * size) / max(radius, 0.0001);
+    } else {
+        // Conic, once round clockwise from the start angle
+        let offset = position - g.xy * size;
+        let turns = (atan2(offset.y, offset.x) - g.z) / (2.0 * PI);
+        t = turns - floor(turns);
+    }
+
+    return ramp_colour(i32(in.fill.y), t);
+}
+
+
+// Distance functions, all in pixels from the centre of the quad with y pointing up
+
+fn sdBox(p: vec2<f32>, b: vec2<f32>) -> f32 {
+    let q: vec2<f32> = abs(p) - b;
+    return length(max(q, vec2<f32>(0.0))) + min(max(q.x, q.y), 0.0);
+}
+// Corner radii in the order top right, bottom right, top left, bottom left
+fn sdRoundBox(p: vec2<f32>, b: vec2<f32>, radii: vec4<f32>) -> f32 {
+    var r: vec2<f32> = radii.zw;
+    if (p.x > 0.0) {
+        r = radii.xy;
+    }
+    var corner: f32 = r.y;
+    if (p.y > 0.0) {
+        corner = r.x;
+    }
+    let q: vec2<f32> = abs(p) - b + vec2<f32>(corner);
+    return length(max(q, vec2<f32>(0.0))) + min(max(q.x, q.y), 0.0) - corner;
+}
+fn sdCircle(p: vec2<f32>, r: f32) -> f32 {
+	return length(p) - r;
+}
+// Close to the true distance near the edge, which is all the edges and borders need
+fn sdEllipse(p: vec2<f32>, r: vec2<f32>) -> f32 {
+    let k0: f32 = length(p / r);
+    let k1: f32 = length(p / (r * r));
+    return k0 * (k0 - 1.0) / max(k1, 0.0001);
+}
+// Star with a point at the top, which is a regular polygon when the inner radius is the apothem
+fn sdStar(p: vec2<f32>, r: f32, inner: f32, n: f32) -> f32 {
+    let an: f32 = PI / n;
+
+    // Fold into a single wedge between a point and the inner corner next to it
+    let angle: f32 = atan2(p.x, p.y);
+    let bn: f32 = angle - 2.0 * an * floor(angle / (2.0 * an)) - an;
+    let q: vec2<f32> = length(p) * vec2<f32>(cos(bn), abs(sin(bn)));
+
+    // Distance to the edge from the inner corner out to the point
+    let corner: vec2<f32> = vec2<f32>(inner, 0.0);
+    let e: vec2<f32> = r * vec2<f32>(cos(an), sin(an)) - corner;
+    let w: vec2<f32> = q - corner;
+    let h: f32 = clamp(dot(w, e) / dot(e, e), 0.0, 1.0);
+    let side: f32 = e.x * w.y - e.y * w.x;
+    return length(w - e * h) * -sign(side);
+}
+fn sdPolygon(p: vec2<f32>, r: f32, n: f32) -> f32 {
+    return sdStar(p, r, r * cos(PI / n), n);
+}
+// Slice of a ring between two angles, clockwise on screen from the right
+fn sdPie(p: vec2<f32>, r: f32, inner: f32, start_angle: f32, end_angle: f32) -> f32 {
+    let half_sweep: f32 = clamp((end_angle - start_angle) / 2.0, 0.0, PI);
+
+    // Turn so the middle of the slice points down the y axis
+    let screen: vec2<f32> = vec2<f32>(p.x, -p.y);
+    let turn: f32 = PI / 2.0 - (start_angle + end_angle) / 2.0;
+    let s: f32 = sin(turn);
+    let c: f32 = cos(turn);
+    var q: vec2<f32> = vec2<f32>(screen.x * c - screen.y * s, screen.x * s + screen.y * c);
+    q.x = abs(q.x);
+
+    let sc: vec2<f32> = vec2<f32>(sin(half_sweep), cos(half_sweep));
+    let l: f32 = length(q) - r;
+    let m: f32 = length(q - sc * clamp(dot(q, sc), 0.0, r));
+    let pie: f32 = max(l, m * sign(sc.y * q.x - sc.x * q.y));
+    return max(pie, inner - length(q));
+}
+fn sdRhombus(p: vec2<f32>, b: vec2<f32>) -> f32 {
+    let q: vec2<f32> = abs(p);
+    let v: vec2<f32> = b - 2.0 * q;
+    let h: f32 = clamp((v.x * b.x - v.y * b.y) / dot(b, b), -1.0, 1.0);
+    let d: f32 = length(q - 0.5 * b * vec2<f32>(1.0 - h, 1.0 + h));
+    return d * sign(q.x * b.y + q.y * b.x - b.x * b.y);
+}
+fn sdTriangle(p: vec2<f32>, p0: vec2<f32>, p1: vec2<f32>, p2: vec2<f32>) -> f32 {
+    let e0: vec2<f32> = p1 - p0;
+    let e1: vec2<f32> = p2 - p1;
+    let e2: vec2<f32> = p0 - p2;
+    let v0: vec2<f32> = p - p0;
+    let v1: vec2<f32> = p - p1;
+    let v2: vec2<f32> = p - p2;
+    let pq0: vec2<f32> = v0 - e0 * clamp(dot(v0, e0) / dot(e0, e0), 0.0, 1.0);
+    let pq1: vec2<f32> = v1 - e1 * clamp(dot(v1, e1) / dot(e1, e1), 0.0, 1.0);
+    let pq2: vec2<f32> = v2 - e2 * clamp(dot(v2, e2) / dot(e2, e2), 0.0, 1.0);
+    let s: f32 = sign(e0.x * e2.y - e0.y * e2.x);
+    let d: vec2<f32> = min(min(
+        vec2<f32>(dot(pq0, pq0), s * (v0.x * e0.y - v0.y * e0.x)),
+        vec2<f32>(dot(pq1, pq1), s * (v1.x * e1.y - v1.y * e1.x))),
+        vec2<f32>(dot(pq2, pq2), s * (v2.x * e2.y - v2.y * e2.x)));
+    return -sqrt(d.x) * sign(d.y);
+}
+// Arrow pointing right, the shaft width is a fraction of the height and the head length of the width
+fn sdArrow(p: vec2<f32>, b: vec2<f32>, shaft_width: f32, head_length: f32) -> f32 {
+    let head_start: f32 = b.x - 2.0 * b.x * head_length;
+    let shaft_half_length: f32 = (head_start + b.x) / 2.0;
+    let shaft: f32 = sdBox(p - vec2<f32>(head_start - shaft_half_length, 0.0), vec2<f32>(shaft_half_length, b.y * shaft_width));
+    let head: f32 = sdTriangle(p, vec2<f32>(head_start, b.y), vec2<f32>(b.x, 0.0), vec2<f32>(head_start, -b.y));
+    return min(shaft, head);
+}
+
+// Coverage of a pixel by the shape, with the distance in pixels
+fn asFilled(d: f32) -> f32 {
+	return clamp(0.5 - d, 0.0, 1.0);
+}
+// Coverage by a border running just inside the edge of the shape
+fn asLine(d: f32, thickness: f32) -> f32 {
+	return asFilled(d) * clamp(d + thickness + 0.5, 0.0, 1.0);
+}
+
+// Styles
+let STYLE_FILLED: u32 = 0u;
+let STYLE_OUTLINED: u32 = 1u;
+
+// Distance in pixels from a point to the edge of the shape, negative inside
+fn shape_distance(in: VertexOutput, p: vec2<f32>) -> f32 {
+    // Set a default value for the distance function
+    var d: f32 = 1.0;
+
+    let b = in.half_size;
+
+    // Shapes that keep their proportions fit inside the smaller side
+    let radius = min(b.x, b.y);
+
+    // Calc for different prim types
+    if (in.shape == u32(1)) { // Rectangle
+        d = sdRoundBox(p, b, vec4<f32>(in.corner_radius * radius));
+    } else if (in.shape == u32(2)) { // Circle
+        d = sdCircle(p, radius);
+    } else if (in.shape == u32(3)) { // Triangle
+        d = sdPolygon(p, radius, 3.0);
+    } else if (in.shape == u32(4)) { // Hexagon, flat on the top
+        d = sdPolygon(p.yx, radius, 6.0);
+    } else if (in.shape == u32(5)) { // Ellipse
+        d = sdEllipse(p, b);
+    } else if (in.shape == u32(6)) { // Capsule
+        d = sdRoundBox(p, b, vec4<f32>(radius));
+    } else if (in.shape == u32(7)) { // Ring
+        let thickness = in.params.x * radius;
+        d = abs(length(p) - radius + thickness / 2.0) - thickness / 2.0;
+    } else if (in.shape == u32(8)) { // Pie
+        d = sdPie(p, radius, in.params.z * radius, in.params.x, in.params.y);
+    } else if (in.shape == u32(9)) { // Star
+        d = sdStar(p, radius, in.params.y * radius, in.params.x);
+    } else if (in.shape == u32(10)) { // Polygon
+        d = sdPolygon(p, radius, in.params.x);
+    } else if (in.shape == u32(11)) { // Rhombus
+        d = sdRhombus(p, b);
+    } else if (in.shape == u32(12)) { // Arrow
+        d = sdArrow(p, b, in.params.x, in.params.y);
+    } else if (in.shape == u32(13)) { // Cross
+        let thickness = in.params.x * radius;
+        d = min(sdBox(p, vec2<f32>(b.x, thickness)), sdBox(p, vec2<f32>(thickness, b.y)));
+    } else if (in.shape == u32(14)) { // Rounded rectangle, corners given clockwise from the top left
+        d = sdRoundBox(p, b, in.params.yzxw * radius);
+    }
+
+    return d;
+}
+
+// Tint modes
+let TINT_MULTIPLY: u32 = 0u;
+let TINT_REPLACE: u32 = 1u;
+let TINT_ADD: u32 = 2u;
+let TINT_OVERLAY: u32 = 3u;
+let TINT_TEXTURE_ONLY: u32 = 4u;
+
+fn overlay(base: f32, blend: f32) -> f32 {
+    if (base < 0.5) {
+        return 2.0 * base * blend;
+    }
+    return 1.0 - 2.0 * (1.0 - base) * (1.0 - blend);
+}
+
+// Tint the premultiplied texture by the premultiplied colour, as strongly as the colour's alpha
+fn tinted(tex: vec4<f32>, col: vec4<f32>, mode: u32) -> vec4<f32> {
+    let strength = col.a;
+
+    if (mode == TINT_MULTIPLY) {
+        return vec4<f32>(tex.rgb * (vec3<f32>(1.0 - strength) + col.rgb), tex.a);
+    } else if (mode == TINT_REPLACE) {
+        return vec4<f32>(tex.rgb * (1.0 - strength) + col.rgb * tex.a, tex.a);
+    } else if (mode == TINT_ADD) {
+        return vec4<f32>(tex.rgb + col.rgb * tex.a, tex.a);
+    } else if (mode == TINT_OVERLAY) {
+        // Overlay works on straight colours
+        if (tex.a <= 0.0 || strength <= 0.0) {
+            return tex;
+        }
+        let base = tex.rgb / tex.a;
+        let blend = col.rgb / strength;
+        let overlaid = vec3<f32>(overlay(base.r, blend.r), overlay(base.g, blend.g), overlay(base.b, blend.b));
+        return vec4<f32>(mix(base, overlaid, strength) * tex.a, tex.a);
+    } else if (mode == TINT_TEXTURE_ONLY) {
+        return tex;
+    }
+
+    // Colour only
+    return col;
+}
+
+// Lay one premultiplied colour over another
+fn over(top: vec4<f32>, bottom: vec4<f32>) -> vec4<f32> {
+    return top + bottom * (1.0 - top.a);
+}
+
+// Lay the style, shadow and glow around the paint of the shape, p is in pixels from the centre
+// and d the distance to the edge. Both the paint and the result are premultiplied.
+fn shade(in: VertexOutput, p: vec2<f32>, d: f32, paint: vec4<f32>) -> vec4<f32> {
+    let fill_coverage = asFilled(d);
+    let border = clamp(d + in.line_width + 0.5, 0.0, 1.0);
+
+    var tint = paint;
+    if (in.modes.x == STYLE_FILLED) {
+        tint = tint * fill_coverage;
+    } else if (in.modes.x == STYLE_OUTLINED) {
+        tint = in.border_col * asLine(d, in.line_width);
+    } else {
+        // Filled with the border laid over the top
+        tint = mix(tint, in.border_col, border) * fill_coverage;
+    }
+
+    // Soft shadow of the shape, moved by the offset
+    let blur = max(in.effects.z, 0.5);
+    let shadow_distance = shape_distance(in, p - in.effects.xy);
+    var shadow = in.shadow_col * (1.0 - smoothStep(-blur, blur, shadow_distance));
+
+    // Glow fading out from the edge
+    var glow = in.glow_col * pow(clamp(1.0 - d / max(in.effects.w, 0.0001), 0.0, 1.0), 2.0);
+
+    // Both only show outside the shape
+    let outside = 1.0 - fill_coverage;
+    shadow = shadow * outside;
+    glow = glow * outside;
+
+    return over(tint, over(glow, shadow)) * in.opacity;
+}
@@ -11,4 +11,14 @@ pub struct SharedUniform {
 #[derive(Clone, Copy, Pod, Zeroable)]
 pub struct CommonUniform {
     pub screen_size: [f32;2],
-}
\ No newline at end of file
+    pub transform: [f32;6],
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Pod, Zeroable)]
+pub struct PostUniform {
+    pub params: [f32;4],
+    pub colour: [f32;4],
+    pub screen_size: [f32;2],
+    pub _padding: [f32;2],
+}
@@ -1,18 +1,65 @@
-use wgpu::{ColorTargetState, BlendState, BlendComponent, ColorWrites};
+use std::collections::HashMap;
 
-use super::Texture;
+use wgpu::{ColorTargetState, BlendState, BlendComponent, BlendFactor, ColorWrites};
+
+use crate::BlendMode;
+
+use super::{MaskState, DrawPass, CommonUniform, PostUniform, Texture};
+
+// Format of the mask, as made by Texture::create_mask_target. Its depth is used by depth tested draws.
+const MASK_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Depth24PlusStencil8;
+
+// Entry points of the instanced shaders, with one fragment entry point for each kind of pass
+pub const INSTANCED_ENTRY_POINTS: [&str; 4] = ["vs_main", "fs_main", "fs_opaque", "fs_transparent"];
+
+// Colour attachment to render to - multisampled targets also name the view they resolve into.
+// The shape pipelines all test against the mask, so need it attached alongside.
+#[derive(Clone, Copy)]
+pub struct RenderTarget<'t> {
+    pub view: &'t wgpu::TextureView,
+    pub resolve_target: Option<&'t wgpu::TextureView>,
+    pub mask: Option<&'t wgpu::TextureView>,
+}
+
+impl <'t> From<&'t wgpu::TextureView> for RenderTarget<'t> {
+    fn from(view: &'t wgpu::TextureView) -> Self {
+        Self {
+            view,
+            resolve_target: None,
+            mask: None,
+        }
+    }
+}
+
+impl <'t> RenderTarget<'t> {
+    // Attach the mask, with its depth for depth tested draws, keeping what is in each unless asked
+    // to clear it
+    pub fn shape_attachment(&self, clear_mask: bool, clear_depth: bool) -> Option<wgpu::RenderPassDepthStencilAttachment<'t>> {
+        self.mask.map(|view| wgpu::RenderPassDepthStencilAttachment {
+            view,
+            depth_ops: Some(wgpu::Operations {
+                load: if clear_depth { wgpu::LoadOp::Clear(1.0) } else { wgpu::LoadOp::Load },
+                store: true,
+            }),
+            stencil_ops: Some(wgpu::Operations {
+                load: if clear_mask { wgpu::LoadOp::Clear(0) } else { wgpu::LoadOp::Load },
+                store: true,
+            }),
+        })
+    }
+}
 
 pub fn create_render_pass<'frame>(
     encoder: &'frame mut wgpu::CommandEncoder, 
-    frame_view: &'frame wgpu::TextureView,
+    target: RenderTarget<'frame>,
     depth_attachement: std::option::Option<wgpu::RenderPassDepthStencilAttachment<'frame>>,
     load_op: wgpu::LoadOp<wgpu::Color>,
 ) -> wgpu::RenderPass<'frame> {
     encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
         label: None,
         color_attachments: &[wgpu::RenderPassColorAttachment {
-            view: &frame_view,
-            resolve_target: None,
+            view: target.view,
+            resolve_target: target.resolve_target,
             ops: wgpu::Operations {
                 load: load_op,
                 store: true,
@@ -22,6 +69,7 @@ pub fn create_render_pass<'frame>(
     })
 }
 
+// The common uniform is bound at an offset picking the transform to draw with
 pub fn create_instanced_bind_group_layout(device: &wgpu::Device) -> wgpu::BindGroupLayout {
     device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
         label: None,
@@ -32,7 +80,7 @@ pub fn create_instanced_bind_group_layout(device: &wgpu::Device) -> wgpu::BindGr
                 ty: wgpu::BindingType::Buffer {
                     ty: wgpu::BufferBindingType::Uniform,
                     min_binding_size: None,
-                    has_dynamic_offset: false,
+                    has_dynamic_offset: true,
                 },
                 count: None,
             },
@@ -55,7 +103,7 @@ pub fn create_instanced_bind_group(
                     wgpu::BufferBinding{
                         buffer: uniform_buffer,
                         offset: 0,
-                        size: None,
+                        size: wgpu::BufferSize::new(std::mem::size_of::<CommonUniform>() as u64),
                     }
                 ),
             },
@@ -63,35 +111,87 @@ pub fn create_instanced_bind_group(
     })
 }
 
+// Instanced pipelines for each blend mode and pass, each built the first time it is drawn with
+// rather than all of them up front
+pub struct BlendedPipelines {
+    pipelines: HashMap<(BlendMode, DrawPass), wgpu::RenderPipeline>,
+    format: wgpu::TextureFormat,
+    sample_count: u32,
+}
+
+impl BlendedPipelines {
+    pub fn new(format: wgpu::TextureFormat, sample_count: u32) -> Self {
+        Self {
+            pipelines: HashMap::new(),
+            format,
+            sample_count,
+        }
+    }
+
+    // Drop every pipeline after the shader changes, to be built again when next used
+    pub fn reset(&mut self, format: wgpu::TextureFormat) {
+        self.pipelines.clear();
+        self.format = format;
+    }
+
+    // Keep the pipelines across a resize unless the surface format changed with it
+    pub fn set_format(&mut self, format: wgpu::TextureFormat) {
+        if format != self.format {
+            self.reset(format);
+        }
+    }
+
+    // Build the pipeline for the blend mode and pass if it hasn't been already
+    pub fn prepare(
+        &mut self,
+        device: &wgpu::Device,
+        pipeline_layout: &wgpu::PipelineLayout,
+        vertex_buffer_layout: wgpu::VertexBufferLayout,
+        shader: &wgpu::ShaderModule,
+        blend_mode: BlendMode,
+        pass: DrawPass,
+    ) {
+        let (format, sample_count) = (self.format, self.sample_count);
+        self.pipelines.entry((blend_mode, pass)).or_insert_with(|| {
+            create_instanced_pipeline(
+                device,
+                blended_target(format, blend_mode),
+                pipeline_layout,
+                vertex_buffer_layout,
+                shader,
+                pass,
+                sample_count,
+            )
+        });
+    }
+
+    // A pipeline already built by prepare
+    pub fn get(&self, blend_mode: BlendMode, pass: DrawPass) -> &wgpu::RenderPipeline {
+        &self.pipelines[&(blend_mode, pass)]
+    }
+}
+
 pub fn create_instanced_pipeline(
     device: &wgpu::Device,
-    config: &wgpu::SurfaceConfiguration,
+    target: ColorTargetState,
     pipeline_layout: &wgpu::PipelineLayout,
     vertex_buffer_layout: wgpu::VertexBufferLayout,
     shader: &wgpu::ShaderModule,
-    depth_checked: bool,
+    pass: DrawPass,
+    sample_count: u32,
 ) -> wgpu::RenderPipeline {
 
-    // Define the depth descriptor
-    let depth_descriptor = if depth_checked {
-        Some(wgpu::DepthStencilState {
-            format: wgpu::TextureFormat::Depth32Float,
-            depth_write_enabled: true,
-            depth_compare: wgpu::CompareFunction::Less,
-            stencil: wgpu::StencilState{
-                front: wgpu::StencilFaceState::IGNORE,
-                back: wgpu::StencilFaceState::IGNORE,
-                read_mask: 0,
-                write_mask: 0,
-            },
-            bias: wgpu::DepthBiasState { 
-                constant: 1, 
-                slope_scale: 1.0, 
-                clamp: 0.0, 
-            },            
-        })
-    } else {
-        None
+    // Define the depth descriptor and the fragment entry point for the pass
+    let (depth_descriptor, fragment_entry_point, target) = match pass {
+        DrawPass::Masked(mask) => {
+            (mask_depth_stencil(mask), "fs_main", masked_target(target, mask))
+        },
+        DrawPass::Opaque => {
+            (depth_tested(true), "fs_opaque", target)
+        },
+        DrawPass::Transparent => {
+            (depth_tested(false), "fs_transparent", target)
+        },
     };
 
 
@@ -105,37 +205,88 @@ pub fn create_instanced_pipeline(
         },
         fragment: Some(wgpu::FragmentState {
             module: shader,
-            entry_point: "fs_main",
-            targets: &[ColorTargetState{
-                format: config.format,
-                blend: Some(BlendState{
-                    color: BlendComponent{
-                        src_factor: wgpu::BlendFactor::SrcAlpha,
-                        dst_factor: wgpu::BlendFactor::OneMinusSrcAlpha,
-                        operation: wgpu::BlendOperation::Add,
+            entry_point: fragment_entry_point,
+            targets: &[target],
+        }),
+        primitive: wgpu::PrimitiveState {
+            topology: wgpu::PrimitiveTopology::TriangleStrip,
+            strip_index_format: None,
+            front_face: wgpu::FrontFace::Ccw,
+            // Draw lists can be mirrored by their transform, which flips the winding
+            cull_mode: None,
+            unclipped_depth: false,
+            polygon_mode: wgpu::PolygonMode::Fill,
+            conservative: false,
+        },
+        multisample: wgpu::MultisampleState { 
+            count: sample_count, 
+            mask: !0, 
+            alpha_to_coverage_enabled: false 
+        },
+        multiview: None,
+        depth_stencil: Some(depth_descriptor),
+    })
+}
+
+// A texture drawn with by the 2D shapes, each texture has a bind group of its own. Textures are
+// bound as arrays, so the texture array is drawn with in the same way.
+pub fn create_texture_bind_group_layout(device: &wgpu::Device) -> wgpu::BindGroupLayout {
+    device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+        label: Some("Texture Bind Group Layout"),
+        entries: &[
+            wgpu::BindGroupLayoutEntry {
+                binding: 0,
+                visibility: wgpu::ShaderStages::FRAGMENT,
+                ty: wgpu::BindingType::Texture{
+                    sample_type: wgpu::TextureSampleType::Float {
+                        filterable: true,
                     },
-                    
-                    // {  R ,   G,   B,   A  }
-                    // {1.0 , 1.0, 1.0, 0.5  } src
-                    // {1.0 , 1.0, 1.0, 0.4  } dst
-
-                    // {1.0 , 1.0, 1.0, 0.9  } src
-                    
-
-                    alpha: BlendComponent{
-                        src_factor: wgpu::BlendFactor::SrcAlpha,
-                        dst_factor: wgpu::BlendFactor::DstAlpha,
-                        operation: wgpu::BlendOperation::Max,
-                    }
-                }),
+                    view_dimension: wgpu::TextureViewDimension::D2Array,
+                    multisampled: false,
+                },
+                count: None,
+            },
+            wgpu::BindGroupLayoutEntry {
+                binding: 1,
+                visibility: wgpu::ShaderStages::FRAGMENT,
+                ty: wgpu::BindingType::Sampler(
+                    wgpu::SamplerBindingType::Filtering
+                ),
+                count: None,
+            },
+        ],
+    })
+}
+
+pub fn create_fullscreen_pipeline(
+    device: &wgpu::Device,
+    format: wgpu::TextureFormat,
+    pipeline_layout: &wgpu::PipelineLayout,
+    shader: &wgpu::ShaderModule,
+    fragment_entry_point: &str,
+) -> wgpu::RenderPipeline {
+    device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+        label: Some(fragment_entry_point),
+        layout: Some(pipeline_layout),
+        vertex: wgpu::VertexState {
+            module: shader,
+            entry_point: "vs_main",
+            buffers: &[],
+        },
+        fragment: Some(wgpu::FragmentState {
+            module: shader,
+            entry_point: fragment_entry_point,
+            targets: &[ColorTargetState{
+                format,
+                blend: None,
                 write_mask: ColorWrites::ALL,
             }],
         }),
         primitive: wgpu::PrimitiveState {
-            topology: wgpu::PrimitiveTopology::TriangleStrip,
+            topology: wgpu::PrimitiveTopology::TriangleList,
             strip_index_format: None,
             front_face: wgpu::FrontFace::Ccw,
-            cull_mode: Some(wgpu::Face::Back),
+            cull_mode: None,
             unclipped_depth: false,
             polygon_mode: wgpu::PolygonMode::Fill,
             conservative: false,
@@ -146,21 +297,21 @@ pub fn create_instanced_pipeline(
             alpha_to_coverage_enabled: false 
         },
         multiview: None,
-        depth_stencil: depth_descriptor,
+        depth_stencil: None,
     })
 }
 
-pub fn create_main_bind_group_layout(device: &wgpu::Device) -> wgpu::BindGroupLayout {
+pub fn create_post_bind_group_layout(device: &wgpu::Device) -> wgpu::BindGroupLayout {
     device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
-        label: None,
+        label: Some("Post Bind Group Layout"),
         entries: &[
             wgpu::BindGroupLayoutEntry {
                 binding: 0,
-                visibility: wgpu::ShaderStages::VERTEX,
+                visibility: wgpu::ShaderStages::FRAGMENT,
                 ty: wgpu::BindingType::Buffer {
                     ty: wgpu::BufferBindingType::Uniform,
                     min_binding_size: None,
-                    has_dynamic_offset: false,
+                    has_dynamic_offset: true,
                 },
                 count: None,
             },
@@ -184,19 +335,392 @@ pub fn create_main_bind_group_layout(device: &wgpu::Device) -> wgpu::BindGroupLa
                 ),
                 count: None,
             },
+            wgpu::BindGroupLayoutEntry {
+                binding: 3,
+                visibility: wgpu::ShaderStages::FRAGMENT,
+                ty: wgpu::BindingType::Texture{
+                    sample_type: wgpu::TextureSampleType::Float {
+                        filterable: true,
+                    },
+                    view_dimension: wgpu::TextureViewDimension::D2,
+                    multisampled: false,
+                },
+                count: None,
+            },
         ],
     })
 }
 
-pub fn create_main_bind_group(
+pub fn create_post_bind_group(
     device: &wgpu::Device,
-    main_bind_group_layout: &wgpu::BindGroupLayout,
+    post_bind_group_layout: &wgpu::BindGroupLayout,
     uniform_buffer: &wgpu::Buffer,
-    pipeline_texture: &Texture
+    source: &Texture,
+    aux: &Texture,
 ) -> wgpu::BindGroup {
     device.create_bind_group(&wgpu::BindGroupDescriptor {
+        label: Some("Post Bind Group"),
+        layout: post_bind_group_layout,
+        entries: &[
+            wgpu::BindGroupEntry {
+                binding: 0,
+                resource: wgpu::BindingResource::Buffer(
+                    wgpu::BufferBinding{
+                        buffer: uniform_buffer,
+                        offset: 0,
+                        size: wgpu::BufferSize::new(std::mem::size_of::<PostUniform>() as u64),
+                    }
+                ),
+            },
+            wgpu::BindGroupEntry {
+                binding: 1,
+                resource: wgpu::BindingResource::TextureView(source.get_view()),
+            },
+            wgpu::BindGroupEntry {
+                binding: 2,
+                resource: wgpu::BindingResource::Sampler(source.get_sampler()),
+            },
+            wgpu::BindGroupEntry {
+                binding: 3,
+                resource: wgpu::BindingResource::TextureView(aux.get_view()),
+            },
+        ],
+    })
+}
+
+
+// The colour target for the shape pipelines, blending as the mode asks. The shaders output
+// premultiplied colours, matching how the glyph brush blends text into the same targets.
+pub fn blended_target(format: wgpu::TextureFormat, blend_mode: BlendMode) -> ColorTargetState {
+    // Colour and alpha parts of the blend, as source factor, destination factor
+    let over = (BlendFactor::One, BlendFactor::OneMinusSrcAlpha);
+    let (colour, alpha) = match blend_mode {
+        // Premultiplied colours are only treated differently before they reach the GPU
+        BlendMode::Alpha | BlendMode::Premultiplied => {
+            (over, over)
+        },
+        BlendMode::Additive => {
+            (
+                (BlendFactor::One, BlendFactor::One),
+                (BlendFactor::One, BlendFactor::One),
+            )
+        },
+        BlendMode::Multiply => {
+            ((BlendFactor::Dst, BlendFactor::OneMinusSrcAlpha), over)
+        },
+        BlendMode::Screen => {
+            ((BlendFactor::One, BlendFactor::OneMinusSrc), over)
+        },
+        BlendMode::Replace => {
+            return ColorTargetState{
+                format,
+                blend: None,
+                write_mask: ColorWrites::ALL,
+            };
+        },
+    };
+
+    ColorTargetState{
+        format,
+        blend: Some(BlendState{
+            color: BlendComponent{
+                src_factor: colour.0,
+                dst_factor: colour.1,
+                operation: wgpu::BlendOperation::Add,
+            },
+            alpha: BlendComponent{
+                src_factor: alpha.0,
+                dst_factor: alpha.1,
+                operation: wgpu::BlendOperation::Add,
+            }
+        }),
+        write_mask: ColorWrites::ALL,
+    }
+}
+
+// Drawing into the mask leaves the colour alone
+fn masked_target(target: ColorTargetState, mask: MaskState) -> ColorTargetState {
+    match mask {
+        MaskState::Write => {
+            ColorTargetState{
+                write_mask: ColorWrites::empty(),
+                ..target
+            }
+        },
+        _ => { target },
+    }
+}
+
+// The stencil test for each mask state. Shapes mark the mask with 1 wherever they aren't
+// discarded, so the reference must be set to 1 in every pass.
+fn mask_depth_stencil(mask: MaskState) -> wgpu::DepthStencilState {
+    let (compare, pass_op) = match mask {
+        MaskState::Off => { (wgpu::CompareFunction::Always, wgpu::StencilOperation::Keep) },
+        MaskState::Write => { (wgpu::CompareFunction::Always, wgpu::StencilOperation::Replace) },
+        MaskState::Inside => { (wgpu::CompareFunction::Equal, wgpu::StencilOperation::Keep) },
+        MaskState::Outside => { (wgpu::CompareFunction::NotEqual, wgpu::StencilOperation::Keep) },
+    };
+
+    let face = wgpu::StencilFaceState {
+        compare,
+        fail_op: wgpu::StencilOperation::Keep,
+        depth_fail_op: wgpu::StencilOperation::Keep,
+        pass_op,
+    };
+
+    wgpu::DepthStencilState {
+        format: MASK_FORMAT,
+        depth_write_enabled: false,
+        depth_compare: wgpu::CompareFunction::Always,
+        stencil: wgpu::StencilState{
+            front: face,
+            back: face,
+            read_mask: 0xff,
+            write_mask: 0xff,
+        },
+        bias: wgpu::DepthBiasState::default(),
+    }
+}
+
+// Depth tested draws ignore the mask. Only the opaque pass writes depth, and the transparent pass
+// draws over anything at the same depth as it is sorted to come after.
+fn depth_tested(write: bool) -> wgpu::DepthStencilState {
+    wgpu::DepthStencilState {
+        format: MASK_FORMAT,
+        depth_write_enabled: write,
+        depth_compare: if write { wgpu::CompareFunction::Less } else { wgpu::CompareFunction::LessEqual },
+        stencil: wgpu::StencilState{
+            front: wgpu::StencilFaceState::IGNORE,
+            back: wgpu::StencilFaceState::IGNORE,
+            read_mask: 0,
+            write_mask: 0,
+        },
+        bias: wgpu::DepthBiasState::default(),
+    }
+}
+
+pub fn create_vertex_pipeline(
+    device: &wgpu::Device,
+    config: &wgpu::SurfaceConfiguration,
+    pipeline_layout: &wgpu::PipelineLayout,
+    vertex_buffer_layout: wgpu::VertexBufferLayout,
+    shader: &wgpu::ShaderModule,
+    mask: MaskState,
+    sample_count: u32,
+) -> wgpu::RenderPipeline {
+    device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
         label: None,
-        layout: main_bind_group_layout,
+        layout: Some(pipeline_layout),
+        vertex: wgpu::VertexState {
+            module: shader,
+            entry_point: "vs_main",
+            buffers: &[vertex_buffer_layout],
+        },
+        fragment: Some(wgpu::FragmentState {
+            module: shader,
+            entry_point: "fs_main",
+            targets: &[masked_target(blended_target(config.format, BlendMode::Alpha), mask)],
+        }),
+        primitive: wgpu::PrimitiveState {
+            topology: wgpu::PrimitiveTopology::TriangleList,
+            strip_index_format: None,
+            front_face: wgpu::FrontFace::Ccw,
+            cull_mode: None,
+            unclipped_depth: false,
+            polygon_mode: wgpu::PolygonMode::Fill,
+            conservative: false,
+        },
+        multisample: wgpu::MultisampleState { 
+            count: sample_count, 
+            mask: !0, 
+            alpha_to_coverage_enabled: false 
+        },
+        multiview: None,
+        depth_stencil: Some(mask_depth_stencil(mask)),
+    })
+}
+
+pub fn create_composite_bind_group_layout(device: &wgpu::Device) -> wgpu::BindGroupLayout {
+    device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+        label: Some("Composite Bind Group Layout"),
+        entries: &[
+            wgpu::BindGroupLayoutEntry {
+                binding: 0,
+                visibility: wgpu::ShaderStages::FRAGMENT,
+                ty: wgpu::BindingType::Texture{
+                    sample_type: wgpu::TextureSampleType::Float {
+                        filterable: true,
+                    },
+                    view_dimension: wgpu::TextureViewDimension::D2,
+                    multisampled: false,
+                },
+                count: None,
+            },
+            wgpu::BindGroupLayoutEntry {
+                binding: 1,
+                visibility: wgpu::ShaderStages::FRAGMENT,
+                ty: wgpu::BindingType::Sampler(
+                    wgpu::SamplerBindingType::Filtering
+                ),
+                count: None,
+            },
+        ],
+    })
+}
+
+pub fn create_composite_bind_group(
+    device: &wgpu::Device,
+    composite_bind_group_layout: &wgpu::BindGroupLayout,
+    source: &Texture,
+) -> wgpu::BindGroup {
+    device.create_bind_group(&wgpu::BindGroupDescriptor {
+        label: Some("Composite Bind Group"),
+        layout: composite_bind_group_layout,
+        entries: &[
+            wgpu::BindGroupEntry {
+                binding: 0,
+                resource: wgpu::BindingResource::TextureView(source.get_view()),
+            },
+            wgpu::BindGroupEntry {
+                binding: 1,
+                resource: wgpu::BindingResource::Sampler(source.get_sampler()),
+            },
+        ],
+    })
+}
+
+// Full screen pipeline that lays a premultiplied layer over the target
+pub fn create_composite_pipeline(
+    device: &wgpu::Device,
+    format: wgpu::TextureFormat,
+    pipeline_layout: &wgpu::PipelineLayout,
+    shader: &wgpu::ShaderModule,
+    sample_count: u32,
+) -> wgpu::RenderPipeline {
+    device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+        label: Some("Composite Pipeline"),
+        layout: Some(pipeline_layout),
+        vertex: wgpu::VertexState {
+            module: shader,
+            entry_point: "vs_main",
+            buffers: &[],
+        },
+        fragment: Some(wgpu::FragmentState {
+            module: shader,
+            entry_point: "fs_main",
+            targets: &[ColorTargetState{
+                format,
+                blend: Some(BlendState::PREMULTIPLIED_ALPHA_BLENDING),
+                write_mask: ColorWrites::ALL,
+            }],
+        }),
+        primitive: wgpu::PrimitiveState {
+            topology: wgpu::PrimitiveTopology::TriangleList,
+            strip_index_format: None,
+            front_face: wgpu::FrontFace::Ccw,
+            cull_mode: None,
+            unclipped_depth: false,
+            polygon_mode: wgpu::PolygonMode::Fill,
+            conservative: false,
+        },
+        multisample: wgpu::MultisampleState { 
+            count: sample_count, 
+            mask: !0, 
+            alpha_to_coverage_enabled: false 
+        },
+        multiview: None,
+        depth_stencil: None,
+    })
+}
+
+pub fn create_gradient_bind_group_layout(device: &wgpu::Device) -> wgpu::BindGroupLayout {
+    device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+        label: Some("Gradient Bind Group Layout"),
+        entries: &[
+            wgpu::BindGroupLayoutEntry {
+                binding: 0,
+                visibility: wgpu::ShaderStages::FRAGMENT,
+                ty: wgpu::BindingType::Texture{
+                    sample_type: wgpu::TextureSampleType::Float {
+                        filterable: true,
+                    },
+                    view_dimension: wgpu::TextureViewDimension::D2,
+                    multisampled: false,
+                },
+                count: None,
+            },
+        ],
+    })
+}
+
+pub fn create_gradient_bind_group(
+    device: &wgpu::Device,
+    gradient_bind_group_layout: &wgpu::BindGroupLayout,
+    gradient_ramp: &Texture,
+) -> wgpu::BindGroup {
+    device.create_bind_group(&wgpu::BindGroupDescriptor {
+        label: Some("Gradient Bind Group"),
+        layout: gradient_bind_group_layout,
+        entries: &[
+            wgpu::BindGroupEntry {
+                binding: 0,
+                resource: wgpu::BindingResource::TextureView(gradient_ramp.get_view()),
+            },
+        ],
+    })
+}
+
+pub fn create_material_bind_group_layout(device: &wgpu::Device) -> wgpu::BindGroupLayout {
+    let texture_entry = |binding| wgpu::BindGroupLayoutEntry {
+        binding,
+        visibility: wgpu::ShaderStages::FRAGMENT,
+        ty: wgpu::BindingType::Texture{
+            sample_type: wgpu::TextureSampleType::Float {
+                filterable: true,
+            },
+            view_dimension: wgpu::TextureViewDimension::D2,
+            multisampled: false,
+        },
+        count: None,
+    };
+
+    device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+        label: Some("Material Bind Group Layout"),
+        entries: &[
+            wgpu::BindGroupLayoutEntry {
+                binding: 0,
+                visibility: wgpu::ShaderStages::FRAGMENT,
+                ty: wgpu::BindingType::Buffer {
+                    ty: wgpu::BufferBindingType::Uniform,
+                    min_binding_size: None,
+                    has_dynamic_offset: false,
+                },
+                count: None,
+            },
+            texture_entry(1),
+            texture_entry(2),
+            wgpu::BindGroupLayoutEntry {
+                binding: 3,
+                visibility: wgpu::ShaderStages::FRAGMENT,
+                ty: wgpu::BindingType::Sampler(
+                    wgpu::SamplerBindingType::Filtering
+                ),
+                count: None,
+            },
+        ],
+    })
+}
+
+// The sampler is taken from the first texture
+pub fn create_material_bind_group(
+    device: &wgpu::Device,
+    material_bind_group_layout: &wgpu::BindGroupLayout,
+    uniform_buffer: &wgpu::Buffer,
+    textures: [&Texture; 2],
+) -> wgpu::BindGroup {
+    device.create_bind_group(&wgpu::BindGroupDescriptor {
+        label: Some("Material Bind Group"),
+        layout: material_bind_group_layout,
         entries: &[
             wgpu::BindGroupEntry {
                 binding: 0,
@@ -210,12 +734,16 @@ pub fn create_main_bind_group(
             },
             wgpu::BindGroupEntry {
                 binding: 1,
-                resource: wgpu::BindingResource::TextureView(pipeline_texture.get_view()),
+                resource: wgpu::BindingResource::TextureView(textures[0].get_view()),
             },
             wgpu::BindGroupEntry {
                 binding: 2,
-                resource: wgpu::BindingResource::Sampler(pipeline_texture.get_sampler()),
+                resource: wgpu::BindingResource::TextureView(textures[1].get_view()),
+            },
+            wgpu::BindGroupEntry {
+                binding: 3,
+                resource: wgpu::BindingResource::Sampler(textures[0].get_sampler()),
             },
         ],
     })
-}
\ No newline at end of file
+}
@@ -1,67 +1,735 @@
-use crate::{Colour, Point2, Vector2, TextureHandle, Section};
+use crate::{Colour, Point2, Vector2, TextureHandle, MaterialHandle, Section};
+
+use super::{Path, DrawList};
 
 // The render command presented to the library user
 pub enum RenderCommand<'s> {
     Clear(Colour),
     DrawLine(LineDescription),
-    Draw2D(TwoDDescription),
-    DrawText(Section<'s>)
+    DrawPolyline(PolylineDescription<'s>),
+    DrawCurve(CurveDescription),
+    DrawArc(ArcDescription),
+    FillPath(FillPathDescription<'s>),
+    StrokePath(StrokePathDescription<'s>),
+    Draw2D(TwoDDescription<'s>),
+    DrawText(Section<'s>),
+    PostProcess(PostEffect),
+    // Shapes after this mark the mask instead of drawing, until the mask is ended. Only the visible
+    // parts of a shape leave a mark, so give mask shapes an opaque colour.
+    BeginMask,
+    // Draws after this are clipped to the inside or outside of the mask
+    EndMask(MaskMode),
+    // Empties the mask and stops clipping
+    ClearMask,
+    // Replays a recorded draw list, moved by the transform if there is one
+    DrawList(&'s DrawList, Option<Transform2D>),
 }
 
 // Description of the line to be drawn
+#[derive(Clone)]
 pub struct LineDescription {
     pub start: Point2,
     pub end: Point2,
     pub width: f32,
     pub colour: Colour,
+    // Colour at the end of the line for a gradient - the start colour is used throughout if none
+    pub end_colour: Option<Colour>,
+    pub dash: DashPattern,
+    pub blend_mode: BlendMode,
+    // Depth tested lines are sorted by depth instead of drawn in order, see TwoDDescription
+    pub depth: Option<f32>,
+}
+
+impl Default for LineDescription {
+    fn default() -> Self {
+        Self {
+            start: Point2::new(0.0,0.0),
+            end: Point2::new(0.0,0.0),
+            width: 1.0,
+            colour: Colour::WHITE,
+            end_colour: None,
+            dash: DashPattern::SOLID,
+            blend_mode: BlendMode::Alpha,
+            depth: None,
+        }
+    }
+}
+
+// How a draw is combined with what is already on the target. Changing mode between draws starts
+// a new batch, so group draws sharing a mode together where possible.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BlendMode {
+    // Normal transparency
+    Alpha,
+    // For colours that already have their alpha multiplied in
+    Premultiplied,
+    // Adds to the target, good for particles and lights
+    Additive,
+    // Darkens the target by the colour
+    Multiply,
+    // Lightens the target by the colour
+    Screen,
+    // Overwrites the target, alpha included
+    Replace,
+}
+
+impl BlendMode {
+    pub const ALL: [BlendMode; 6] = [
+        BlendMode::Alpha,
+        BlendMode::Premultiplied,
+        BlendMode::Additive,
+        BlendMode::Multiply,
+        BlendMode::Screen,
+        BlendMode::Replace,
+    ];
+}
+
+// Which side of the mask later draws are kept on. Masks are hard edged, so are smoothest with
+// multisampling. Text is drawn in its own pass and is never masked.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MaskMode {
+    Inside,
+    Outside,
+}
+
+// How draws interact with the mask as set by the mask commands - each has its own pipelines
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MaskState {
+    Off,
+    Write,
+    Inside,
+    Outside,
+}
+
+impl MaskState {
+    pub const ALL: [MaskState; 4] = [
+        MaskState::Off,
+        MaskState::Write,
+        MaskState::Inside,
+        MaskState::Outside,
+    ];
+}
+
+// Which pass a draw is part of, each with its own pipelines. Depth tested draws are split into an
+// opaque pass that writes depth and a transparent pass that only tests against it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DrawPass {
+    Masked(MaskState),
+    Opaque,
+    Transparent,
+}
+
+// Repeating pattern of dashes and gaps along a line, all lengths in pixels.
+// The pattern runs dash, gap, second dash, second gap - leave the second pair at zero for a
+// simple dashed line. Advancing the phase each frame moves the dashes along the line.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DashPattern {
+    pub dashes: [f32; 2],
+    pub gaps: [f32; 2],
+    pub phase: f32,
+}
+
+impl DashPattern {
+    pub const SOLID: DashPattern = DashPattern {
+        dashes: [0.0, 0.0],
+        gaps: [0.0, 0.0],
+        phase: 0.0,
+    };
+
+    pub fn dashed(dash: f32, gap: f32) -> Self {
+        Self {
+            dashes: [dash, 0.0],
+            gaps: [gap, 0.0],
+            phase: 0.0,
+        }
+    }
+
+    // Dots are square, so usually the size should match the line width
+    pub fn dotted(size: f32, gap: f32) -> Self {
+        Self::dashed(size, gap)
+    }
+
+    pub fn dash_dot(dash: f32, dot: f32, gap: f32) -> Self {
+        Self {
+            dashes: [dash, dot],
+            gaps: [gap, gap],
+            phase: 0.0,
+        }
+    }
+
+    pub fn with_phase(mut self, phase: f32) -> Self {
+        self.phase = phase;
+        self
+    }
+
+    pub fn to_array(&self) -> [f32; 4] {
+        [self.dashes[0], self.gaps[0], self.dashes[1], self.gaps[1]]
+    }
+}
+
+// How the corners between connected line segments are filled
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LineJoin {
+    Miter,
+    Round,
+    Bevel,
+}
+
+// How the open ends of a line are finished
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LineCap {
+    Butt,
+    Round,
+    Square,
 }
 
-// 2D types
+// Description of a run of connected line segments
 #[derive(Debug)]
+pub struct PolylineDescription<'s> {
+    pub points: &'s [Point2],
+    pub width: f32,
+    pub colour: Colour,
+    pub join: LineJoin,
+    pub cap: LineCap,
+    pub closed: bool,
+}
+
+impl <'s> Default for PolylineDescription<'s> {
+    fn default() -> Self {
+        Self {
+            points: &[],
+            width: 1.0,
+            colour: Colour::WHITE,
+            join: LineJoin::Miter,
+            cap: LineCap::Butt,
+            closed: false,
+        }
+    }
+}
+
+// Default largest distance in pixels between a curve and the straight segments drawn for it
+pub const CURVE_TOLERANCE: f32 = 0.25;
+
+// Bezier curve shapes
+#[derive(Debug, Clone, Copy)]
+pub enum Curve {
+    Quadratic {
+        start: Point2,
+        control: Point2,
+        end: Point2,
+    },
+    Cubic {
+        start: Point2,
+        control_1: Point2,
+        control_2: Point2,
+        end: Point2,
+    },
+}
+
+// Description of the curve to be drawn
+pub struct CurveDescription {
+    pub curve: Curve,
+    pub width: f32,
+    pub colour: Colour,
+    pub cap: LineCap,
+    pub tolerance: f32,
+}
+
+impl Default for CurveDescription {
+    fn default() -> Self {
+        Self {
+            curve: Curve::Quadratic {
+                start: Point2::new(0.0,0.0),
+                control: Point2::new(0.0,0.0),
+                end: Point2::new(0.0,0.0),
+            },
+            width: 1.0,
+            colour: Colour::WHITE,
+            cap: LineCap::Butt,
+            tolerance: CURVE_TOLERANCE,
+        }
+    }
+}
+
+// Description of a circular or elliptical arc to be drawn. Angles are in radians measured
+// clockwise from the x axis, and the rotation turns the whole ellipse about its centre.
+pub struct ArcDescription {
+    pub centre: Point2,
+    pub radii: Vector2,
+    pub rotation: f32,
+    pub start_angle: f32,
+    pub end_angle: f32,
+    pub width: f32,
+    pub colour: Colour,
+    pub cap: LineCap,
+    pub tolerance: f32,
+}
+
+impl Default for ArcDescription {
+    fn default() -> Self {
+        Self {
+            centre: Point2::new(0.0,0.0),
+            radii: Vector2::new(0.0,0.0),
+            rotation: 0.0,
+            start_angle: 0.0,
+            end_angle: std::f32::consts::PI * 2.0,
+            width: 1.0,
+            colour: Colour::WHITE,
+            cap: LineCap::Butt,
+            tolerance: CURVE_TOLERANCE,
+        }
+    }
+}
+
+// Used by the path descriptions until a path is given
+static EMPTY_PATH: Path = Path::new();
+
+// Which parts of a path count as inside where it overlaps itself
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FillRule {
+    // Inside wherever the path winds round more times one way than the other
+    NonZero,
+    // Inside wherever the path has been crossed an odd number of times
+    EvenOdd,
+}
+
+// A colour at a point along a gradient, with the offset running from 0 at the start to 1 at the end
+#[derive(Debug, Clone, Copy)]
+pub struct ColourStop {
+    pub offset: f32,
+    pub colour: Colour,
+}
+
+// Where a gradient runs, in the same space as the points it's painted on
+#[derive(Debug, Clone, Copy)]
+pub enum GradientShape {
+    // From the start point to the end point, constant at right angles to the line between them
+    Linear {
+        start: Point2,
+        end: Point2,
+    },
+    // Outwards from the centre, reaching the end at the radius
+    Radial {
+        centre: Point2,
+        radius: f32,
+    },
+}
+
+// Smooth blend between colour stops - beyond the first and last stops their colours carry on
+#[derive(Debug, Clone)]
+pub struct Gradient {
+    pub shape: GradientShape,
+    pub stops: Vec<ColourStop>,
+}
+
+// Description of the path to be filled. Open sub paths are closed with a straight line.
+// Fill edges aren't faded, so turn on multisampling for smooth edges.
+pub struct FillPathDescription<'s> {
+    pub path: &'s Path,
+    pub colour: Colour,
+    pub fill_rule: FillRule,
+    // Paints the fill instead of the colour, which then just tints it
+    pub gradient: Option<&'s Gradient>,
+    pub tolerance: f32,
+}
+
+impl <'s> Default for FillPathDescription<'s> {
+    fn default() -> Self {
+        Self {
+            path: &EMPTY_PATH,
+            colour: Colour::WHITE,
+            fill_rule: FillRule::NonZero,
+            gradient: None,
+            tolerance: CURVE_TOLERANCE,
+        }
+    }
+}
+
+// Description of the path to be outlined
+pub struct StrokePathDescription<'s> {
+    pub path: &'s Path,
+    pub width: f32,
+    pub colour: Colour,
+    pub join: LineJoin,
+    pub cap: LineCap,
+    // Paints the stroke instead of the colour, which then just tints it
+    pub gradient: Option<&'s Gradient>,
+    pub tolerance: f32,
+}
+
+impl <'s> Default for StrokePathDescription<'s> {
+    fn default() -> Self {
+        Self {
+            path: &EMPTY_PATH,
+            width: 1.0,
+            colour: Colour::WHITE,
+            join: LineJoin::Miter,
+            cap: LineCap::Butt,
+            gradient: None,
+            tolerance: CURVE_TOLERANCE,
+        }
+    }
+}
+
+// 2D types. Rectangles, ellipses, capsules, rhombuses, arrows and crosses stretch to fill the size,
+// the rest keep their proportions and fit inside the smaller side. Any fractions are of that side.
+#[derive(Debug, Clone)]
 pub enum TwoDTypes {
     Rectangle,
     Circle,
     Triangle,
     Hexagon,
+    Ellipse,
+    // Rectangle with fully rounded ends
+    Capsule,
+    Ring {
+        thickness: f32,
+    },
+    // Slice between two angles in radians, clockwise from the right. An inner radius above 0 leaves
+    // a segment of a ring.
+    Pie {
+        start_angle: f32,
+        end_angle: f32,
+        inner_radius: f32,
+    },
+    Star {
+        points: u32,
+        inner_radius: f32,
+    },
+    // Regular polygon with a corner at the top
+    Polygon {
+        sides: u32,
+    },
+    Rhombus,
+    // Pointing right, the shaft width is a fraction of the height and the head length of the width
+    Arrow {
+        shaft_width: f32,
+        head_length: f32,
+    },
+    Cross {
+        thickness: f32,
+    },
+    // Corner radii clockwise from the top left
+    RoundedRectangle {
+        radii: [f32; 4],
+    },
 }
 
 impl TwoDTypes {
     pub fn to_int(&self) -> u32 {
         match self {
-            TwoDTypes::Rectangle => { 1 },
-            TwoDTypes::Circle =>    { 2 },
-            TwoDTypes::Triangle =>  { 3 },
-            TwoDTypes::Hexagon =>   { 4 },
+            TwoDTypes::Rectangle =>             { 1 },
+            TwoDTypes::Circle =>                { 2 },
+            TwoDTypes::Triangle =>              { 3 },
+            TwoDTypes::Hexagon =>               { 4 },
+            TwoDTypes::Ellipse =>               { 5 },
+            TwoDTypes::Capsule =>               { 6 },
+            TwoDTypes::Ring{..} =>              { 7 },
+            TwoDTypes::Pie{..} =>               { 8 },
+            TwoDTypes::Star{..} =>              { 9 },
+            TwoDTypes::Polygon{..} =>           { 10 },
+            TwoDTypes::Rhombus =>               { 11 },
+            TwoDTypes::Arrow{..} =>             { 12 },
+            TwoDTypes::Cross{..} =>             { 13 },
+            TwoDTypes::RoundedRectangle{..} =>  { 14 },
+        }
+    }
+
+    // Shape specific values as passed to the shader
+    pub fn to_params(&self) -> [f32; 4] {
+        match *self {
+            TwoDTypes::Ring{thickness} => { [thickness, 0.0, 0.0, 0.0] },
+            TwoDTypes::Pie{start_angle, end_angle, inner_radius} => { [start_angle, end_angle, inner_radius, 0.0] },
+            TwoDTypes::Star{points, inner_radius} => { [points.max(2) as f32, inner_radius, 0.0, 0.0] },
+            TwoDTypes::Polygon{sides} => { [sides.max(3) as f32, 0.0, 0.0, 0.0] },
+            TwoDTypes::Arrow{shaft_width, head_length} => { [shaft_width, head_length, 0.0, 0.0] },
+            TwoDTypes::Cross{thickness} => { [thickness, 0.0, 0.0, 0.0] },
+            TwoDTypes::RoundedRectangle{radii} => { radii },
+            _ => { [0.0; 4] },
+        }
+    }
+}
+
+// How a 2D shape is drawn
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TwoDStyle {
+    Filled,
+    // Just the border, leaving the inside empty
+    Outlined,
+    FilledWithBorder,
+}
+
+impl TwoDStyle {
+    pub fn to_int(&self) -> u32 {
+        match self {
+            TwoDStyle::Filled =>            { 0 },
+            TwoDStyle::Outlined =>          { 1 },
+            TwoDStyle::FilledWithBorder =>  { 2 },
         }
     }
 }
 
+// How the colour of a textured 2D shape tints its texture. The colour's alpha sets how strongly
+// the tint is applied, and the texture's own alpha is always kept. Shapes without a texture just
+// use the colour.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TintMode {
+    // Darken the texture by the colour
+    Multiply,
+    // Swap the texture's colour for the colour, e.g. to flash white
+    Replace,
+    // Brighten the texture by the colour
+    Add,
+    // Push the texture's lights and darks towards the colour, keeping its detail
+    Overlay,
+    TextureOnly,
+    // Fill with the colour, ignoring the texture
+    ColourOnly,
+}
+
+impl TintMode {
+    pub fn to_int(&self) -> u32 {
+        match self {
+            TintMode::Multiply =>       { 0 },
+            TintMode::Replace =>        { 1 },
+            TintMode::Add =>            { 2 },
+            TintMode::Overlay =>        { 3 },
+            TintMode::TextureOnly =>    { 4 },
+            TintMode::ColourOnly =>     { 5 },
+        }
+    }
+}
+
+// What the inside of a 2D shape is painted with. Positions are fractions of the shape's size from
+// its top left, so gradients move and turn with the shape. The radial radius is a fraction of the
+// smaller side so it stays round, and conic angles are in radians clockwise from the right.
+// Gradients take the place of the colour and texture.
+#[derive(Debug, Clone, Copy)]
+pub enum Fill<'s> {
+    Solid,
+    LinearGradient {
+        start: Point2,
+        end: Point2,
+        stops: &'s [ColourStop],
+    },
+    RadialGradient {
+        centre: Point2,
+        radius: f32,
+        stops: &'s [ColourStop],
+    },
+    // Sweeps once round the centre, starting from the angle
+    ConicGradient {
+        centre: Point2,
+        start_angle: f32,
+        stops: &'s [ColourStop],
+    },
+}
+
+impl <'s> Fill<'s> {
+    pub fn to_int(&self) -> u32 {
+        match self {
+            Fill::Solid =>              { 0 },
+            Fill::LinearGradient{..} => { 1 },
+            Fill::RadialGradient{..} => { 2 },
+            Fill::ConicGradient{..} =>  { 3 },
+        }
+    }
+
+    // Where the gradient runs as passed to the shader
+    pub fn to_geometry(&self) -> [f32; 4] {
+        match *self {
+            Fill::Solid => { [0.0; 4] },
+            Fill::LinearGradient{start, end, ..} => { [start.x, start.y, end.x, end.y] },
+            Fill::RadialGradient{centre, radius, ..} => { [centre.x, centre.y, radius, 0.0] },
+            Fill::ConicGradient{centre, start_angle, ..} => { [centre.x, centre.y, start_angle, 0.0] },
+        }
+    }
+
+    pub fn stops(&self) -> Option<&'s [ColourStop]> {
+        match *self {
+            Fill::Solid => { None },
+            Fill::LinearGradient{stops, ..}
+            | Fill::RadialGradient{stops, ..}
+            | Fill::ConicGradient{stops, ..} => { Some(stops) },
+        }
+    }
+}
+
+// Soft shadow cast behind a 2D shape. The offset is in pixels with y pointing down the screen,
+// and the blur is how far in pixels the edge fades out over.
+#[derive(Debug, Clone, Copy)]
+pub struct Shadow {
+    pub offset: Vector2,
+    pub blur: f32,
+    pub colour: Colour,
+}
+
+// Glow around the outside of a 2D shape, fading out over the radius in pixels
+#[derive(Debug, Clone, Copy)]
+pub struct Glow {
+    pub radius: f32,
+    pub colour: Colour,
+}
+
 // Drescription of the 2D thing to be drawn
-#[derive(Debug)]
-pub struct TwoDDescription {
+#[derive(Debug, Clone)]
+pub struct TwoDDescription<'s> {
     pub position: Point2,
     pub size: Vector2,
     pub colour: Colour,
     pub texture: Option<TextureHandle>,
+    pub tint_mode: TintMode,
+    pub fill: Fill<'s>,
+    // Custom material painting the shape in place of the fill, with params passed to its shader
+    pub material: Option<MaterialHandle>,
+    pub material_params: [f32; 4],
     pub opacity: f32,
+    pub style: TwoDStyle,
+    pub border_colour: Colour,
+    // Width of the border in pixels, running just inside the edge of the shape
     pub line_width: f32,
+    // Fraction of the smaller side
     pub corner_radius: f32,
     pub rotation: f32,
     pub shape: TwoDTypes,
+    // Drawn behind the shape, the quad grows to fit them
+    pub shadow: Option<Shadow>,
+    pub glow: Option<Glow>,
+    pub blend_mode: BlendMode,
+    // Between 0 and 1, lower is nearer. Depth tested draws are collected and drawn together where
+    // the first of them is submitted, opaque parts first then the rest blended back to front, so
+    // they can be submitted in any order. They aren't masked.
+    pub depth: Option<f32>,
 }
 
-impl TwoDDescription {
-    pub fn default() -> Self {
+impl <'s> Default for TwoDDescription<'s> {
+    fn default() -> Self {
         Self {
             position: Point2::new(0.0,0.0),
             size: Vector2::new(0.0,0.0),
             colour: Colour::TRANSPARENT,
             texture: None,
+            tint_mode: TintMode::Multiply,
+            fill: Fill::Solid,
+            material: None,
+            material_params: [0.0; 4],
             opacity: 1.0,
-            line_width: 0.0,
+            style: TwoDStyle::Filled,
+            border_colour: Colour::BLACK,
+            line_width: 1.0,
             corner_radius: 0.0,
             rotation: 0.0,
             shape: TwoDTypes::Rectangle,
+            shadow: None,
+            glow: None,
+            blend_mode: BlendMode::Alpha,
+            depth: None,
         }
     }
-}
\ No newline at end of file
+}
+
+// Full screen effects applied in order to the finished frame before it is presented.
+// Any effect in the frame causes the scene to be rendered offscreen first.
+#[derive(Debug, Clone, Copy)]
+pub enum PostEffect {
+    // Separable gaussian blur, radius in pixels
+    GaussianBlur {
+        radius: f32,
+    },
+    // Blurs everything brighter than the threshold and adds it back on top
+    Bloom {
+        threshold: f32,
+        intensity: f32,
+        radius: f32,
+    },
+    // Fades the edges of the frame towards a colour, radius & softness relative to the frame centre
+    Vignette {
+        radius: f32,
+        softness: f32,
+        colour: Colour,
+    },
+    // Colour grading through a 2D lookup texture laid out as N slices of NxN along the x axis
+    ColourGrade {
+        lut: TextureHandle,
+        intensity: f32,
+    },
+    // Splits the red and blue channels outwards from the centre, offset in pixels at the edges
+    ChromaticAberration {
+        offset: f32,
+    },
+    // Scanlines and barrel distortion
+    Crt {
+        scanline_intensity: f32,
+        curvature: f32,
+    },
+    // User provided WGSL with an `fs_main` fragment entry point. The source is appended to a prelude
+    // declaring `VertexOutput` (with `uv`), the `post` uniform (`params`, `colour`, `screen_size`),
+    // `t_Source`, `s_Source` and `t_Aux`. `params` is passed through as `post.params`. The shader can
+    // use #include, #define and #ifdef, and if it fails to compile the error is logged and the effect skipped.
+    Custom {
+        shader: &'static str,
+        params: [f32; 4],
+    },
+}
+// Moves a draw list as a whole - scaled and rotated about the origin, then translated, in pixels
+#[derive(Debug, Clone, Copy)]
+pub struct Transform2D {
+    pub translation: Vector2,
+    pub scale: Vector2,
+    pub rotation: f32,
+}
+
+impl Default for Transform2D {
+    fn default() -> Self {
+        Self {
+            translation: Vector2::new(0.0, 0.0),
+            scale: Vector2::new(1.0, 1.0),
+            rotation: 0.0,
+        }
+    }
+}
+
+impl Transform2D {
+    // Columns of the matrix taking positions in pixels to where they are drawn, one after another
+    pub fn to_columns(&self) -> [f32; 6] {
+        let (sin, cos) = self.rotation.sin_cos();
+        [
+            cos * self.scale.x, sin * self.scale.x,
+            -sin * self.scale.y, cos * self.scale.y,
+            self.translation.x, self.translation.y,
+        ]
+    }
+    // Negative when the transform mirrors what it draws, reversing the winding of its triangles
+    pub fn determinant(&self) -> f32 {
+        let [a, b, c, d, _, _] = self.to_columns();
+        a * d - b * c
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mirrored_transform_reverses_winding() {
+        let transform = Transform2D {
+            translation: Vector2::new(10.0, 20.0),
+            scale: Vector2::new(-2.0, 1.0),
+            rotation: 0.7,
+        };
+        assert!((transform.determinant() + 2.0).abs() < 1e-5);
+        assert!(Transform2D::default().determinant() > 0.0);
+
+        // A counter clockwise triangle comes out clockwise
+        let apply = |x: f32, y: f32| {
+            let [a, b, c, d, tx, ty] = transform.to_columns();
+            (a * x + c * y + tx, b * x + d * y + ty)
+        };
+        let signed_area = |p: [(f32, f32); 3]| {
+            (p[1].0 - p[0].0) * (p[2].1 - p[0].1) - (p[2].0 - p[0].0) * (p[1].1 - p[0].1)
+        };
+        let triangle = [(0.0, 0.0), (1.0, 0.0), (0.0, 1.0)];
+        assert!(signed_area(triangle) > 0.0);
+        assert!(signed_area(triangle.map(|(x, y)| apply(x, y))) < 0.0);
+    }
+}
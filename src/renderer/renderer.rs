@@ -1,9 +1,13 @@
 use super::{
     CommandExecutor,
+    FrameResources,
     CommandManager,
     PipelineManager,
+    PostPipeline,
     TextureManager,
+    MaterialManager,
     SectionManager,
+    ShaderError,
 };
 
 pub struct RendererInstance {
@@ -19,6 +23,11 @@ pub struct RendererInstance {
     frame: Option<wgpu::SurfaceTexture>,
 
     pipeline_manager: PipelineManager,
+    post_pipeline: PostPipeline,
+
+    // Built in shaders are rebuilt when their files change
+    #[cfg(feature = "hot-reload")]
+    shader_watcher: crate::FileWatcher,
 }
 
 impl RendererInstance {
@@ -29,7 +38,8 @@ impl RendererInstance {
         adapter: wgpu::Adapter,
         device: wgpu::Device,
         queue: wgpu::Queue,
-    ) -> Self {
+        sample_count: u32,
+    ) -> Result<Self, ShaderError> {
 
         // Get the preferred format
         let swapchain_format = surface.get_preferred_format(&adapter).unwrap();
@@ -47,10 +57,20 @@ impl RendererInstance {
         surface.configure(&device, &config);
 
         // Create Pipeline Manager
-        let pipeline_manager = PipelineManager::new(&device, &config);
+        let pipeline_manager = PipelineManager::new(&device, &config, sample_count)?;
+
+        // Create the Post Processing Pipeline
+        let post_pipeline = PostPipeline::new(&device, &config)?;
+
+        #[cfg(feature = "hot-reload")]
+        let shader_watcher = {
+            let mut shader_watcher = crate::FileWatcher::new();
+            shader_watcher.watch(std::path::Path::new(super::SHADER_DIRECTORY));
+            shader_watcher
+        };
 
         // Build and return the Render Instance
-        RendererInstance{
+        Ok(RendererInstance{
             _instance: instance,
             size,
             surface,
@@ -60,7 +80,11 @@ impl RendererInstance {
             config,
             frame: None,
             pipeline_manager,
-        }
+            post_pipeline,
+
+            #[cfg(feature = "hot-reload")]
+            shader_watcher,
+        })
     }
 
     pub fn init_new_frame(&mut self) {       
@@ -76,7 +100,11 @@ impl RendererInstance {
         self.frame = Some(frame);
     }
 
-    pub fn build_and_submit<'frame>(&mut self, command_manager: &CommandManager, section_manger:&mut SectionManager<'frame>, texture_manager: &mut TextureManager) {
+    pub fn build_and_submit<'frame>(&mut self, command_manager: &CommandManager, section_manger:&mut SectionManager<'frame>, texture_manager: &mut TextureManager, material_manager: &mut MaterialManager) {
+        // Pick up any shaders and textures changed on disk
+        #[cfg(feature = "hot-reload")]
+        self.hot_reload(texture_manager, material_manager);
+
         // Render on the GPU
         if let Some(frame) = &self.frame {
 
@@ -85,13 +113,16 @@ impl RendererInstance {
             // Create Command Executor
             let mut ce = CommandExecutor::new(
                 &self.device,
-                &self.queue, 
-                frame,
-                &frame_view,
-                command_manager,
-                section_manger,
-                &mut self.pipeline_manager,
-                texture_manager,
+                &self.queue,
+                FrameResources {
+                    frame_view: &frame_view,
+                    command_manager,
+                    section_manager: section_manger,
+                    pipeline_manager: &mut self.pipeline_manager,
+                    post_pipeline: &mut self.post_pipeline,
+                    texture_manager,
+                    material_manager,
+                },
             );
 
             // Run
@@ -102,6 +133,24 @@ impl RendererInstance {
         self.frame.take().unwrap().present();
     }
 
+    #[cfg(feature = "hot-reload")]
+    fn hot_reload(&mut self, texture_manager: &mut TextureManager, material_manager: &mut MaterialManager) {
+        texture_manager.reload_changed_files(&self.queue);
+
+        if self.shader_watcher.changed().is_empty() {
+            return;
+        }
+
+        log::info!("Reloading shaders...");
+
+        let materials = material_manager.reload_shaders();
+        self.pipeline_manager.reload_shaders(&self.device, material_manager, &materials);
+
+        if let Err(error) = self.post_pipeline.reload_shaders(&self.device) {
+            log::error!("Keeping the previous shader: {}", error);
+        }
+    }
+
     pub fn resize(&mut self, new_size: winit::dpi::PhysicalSize<u32>) {
         // Update the physical size
         self.size = new_size;
@@ -116,8 +165,10 @@ impl RendererInstance {
         // Resize the pipelines (i.e. the depth buffers)
         self.pipeline_manager.resize(
             &self.device, 
-            &self.queue, 
             &self.config
         );
+
+        // Drop the offscreen targets so they are recreated at the new size
+        self.post_pipeline.resize(&self.config);
     }
 }
\ No newline at end of file
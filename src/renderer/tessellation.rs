@@ -0,0 +1,692 @@
+use cgmath::InnerSpace;
+
+use crate::{Point2, Vector2};
+
+use super::{FillRule, LineCap, LineJoin, TriangleVertex, gradient::SOLID_PAINT};
+
+// Distance the geometry is pushed out past the stroke so its edges can be faded
+const AA_FRINGE: f32 = 1.0;
+
+// Distance past the line end given to vertices that are nowhere near a cap
+const NO_CAP: f32 = -1.0e4;
+
+// Longest miter allowed, as a multiple of the half width, before falling back to a bevel
+const MITER_LIMIT: f32 = 4.0;
+
+// Largest gap in pixels allowed between a round join or cap and the triangles approximating it
+const ROUND_TOLERANCE: f32 = 0.1;
+
+// Points closer than this are treated as the same point
+const POINT_EPSILON: f32 = 1.0e-4;
+
+// Helper that writes stroke triangles of a single colour and width into the output
+struct Stroker<'o> {
+    output: &'o mut Vec<TriangleVertex>,
+    colour: [f32; 4],
+    half_width: f32,
+    extent: f32,
+}
+
+impl <'o> Stroker<'o> {
+    fn vertex(&mut self, position: Vector2, edge: [f32; 2]) {
+        self.output.push(TriangleVertex {
+            position: [position.x, position.y],
+            colour: self.colour,
+            edge,
+            half_width: self.half_width,
+            paint: SOLID_PAINT,
+        });
+    }
+
+    // Quad between two cross sections of the line, split down the centre line so the inside
+    // of a join meets the neighbouring segment exactly rather than overlapping it
+    fn quad(&mut self, start: CrossSection, end: CrossSection) {
+        let e = self.extent;
+
+        // Left half
+        self.vertex(start.centre, [0.0, start.past_end]);
+        self.vertex(end.centre, [0.0, end.past_end]);
+        self.vertex(end.centre + end.left, [e, end.past_end]);
+
+        self.vertex(start.centre, [0.0, start.past_end]);
+        self.vertex(end.centre + end.left, [e, end.past_end]);
+        self.vertex(start.centre + start.left, [e, start.past_end]);
+
+        // Right half
+        self.vertex(start.centre, [0.0, start.past_end]);
+        self.vertex(start.centre + start.right, [-e, start.past_end]);
+        self.vertex(end.centre + end.right, [-e, end.past_end]);
+
+        self.vertex(start.centre, [0.0, start.past_end]);
+        self.vertex(end.centre + end.right, [-e, end.past_end]);
+        self.vertex(end.centre, [0.0, end.past_end]);
+    }
+
+    // Fan around the centre starting at the offset and rotating through the sweep in radians
+    fn arc(&mut self, centre: Vector2, from: Vector2, sweep: f32) {
+        // Choose the step so the chords stay within tolerance of the true circle
+        let step = 2.0 * (1.0 - ROUND_TOLERANCE / self.extent).max(-1.0).acos();
+        let n_steps = ((sweep.abs() / step.max(0.01)).ceil() as usize).max(1);
+        let angle = sweep / n_steps as f32;
+
+        let mut previous = from;
+        for _ in 0..n_steps {
+            let next = rotate(previous, angle);
+
+            self.vertex(centre, [0.0, NO_CAP]);
+            self.vertex(centre + previous, [self.extent, NO_CAP]);
+            self.vertex(centre + next, [self.extent, NO_CAP]);
+
+            previous = next;
+        }
+    }
+
+    fn triangle(&mut self, centre: Vector2, a: Vector2, b: Vector2) {
+        self.vertex(centre, [0.0, NO_CAP]);
+        self.vertex(centre + a, [self.extent, NO_CAP]);
+        self.vertex(centre + b, [self.extent, NO_CAP]);
+    }
+}
+
+// Point on the line with the offsets to its left and right edges
+struct CrossSection {
+    centre: Vector2,
+    left: Vector2,
+    right: Vector2,
+    past_end: f32,
+}
+
+// A single segment of the polyline
+struct Segment {
+    start: Vector2,
+    end: Vector2,
+    direction: Vector2,
+    normal: Vector2,
+    length: f32,
+
+    // Offsets from the centre line, replaced on the inside of joins
+    start_left: Vector2,
+    start_right: Vector2,
+    end_left: Vector2,
+    end_right: Vector2,
+}
+
+// Generate anti-aliased triangles for a stroked polyline
+pub fn stroke_polyline(
+    points: &[Point2],
+    width: f32,
+    colour: [f32; 4],
+    join: LineJoin,
+    cap: LineCap,
+    closed: bool,
+    output: &mut Vec<TriangleVertex>,
+) {
+    // Remove repeated points as they have no direction
+    let mut points: Vec<Vector2> = points.iter()
+        .map(|p| Vector2::new(p.x, p.y))
+        .collect();
+    points.dedup_by(|a, b| (*a - *b).magnitude() < POINT_EPSILON);
+
+    if closed && points.len() > 2 && (points[0] - points[points.len() - 1]).magnitude() < POINT_EPSILON {
+        points.pop();
+    }
+
+    if points.len() < 2 {
+        return;
+    }
+
+    // Closing two points would just double back on itself
+    let closed = closed && points.len() > 2;
+
+    // Lines thinner than a pixel are drawn a pixel wide and faded instead
+    let mut colour = colour;
+    let half_width = if width < 1.0 {
+        colour[3] *= width.max(0.0);
+        0.5
+    } else {
+        width / 2.0
+    };
+    let extent = half_width + AA_FRINGE;
+
+    // Build the segments
+    let n_segments = if closed { points.len() } else { points.len() - 1 };
+    let mut segments: Vec<Segment> = (0..n_segments)
+        .map(|i| {
+            let start = points[i];
+            let end = points[(i + 1) % points.len()];
+            let length = (end - start).magnitude();
+            let direction = (end - start) / length;
+            let normal = Vector2::new(-direction.y, direction.x);
+
+            Segment {
+                start,
+                end,
+                direction,
+                normal,
+                length,
+                start_left: normal * extent,
+                start_right: -normal * extent,
+                end_left: normal * extent,
+                end_right: -normal * extent,
+            }
+        })
+        .collect();
+
+    let mut stroker = Stroker {
+        output,
+        colour,
+        half_width,
+        extent,
+    };
+
+    // Work out the joins - incoming segment i - 1 meets outgoing segment i
+    let first_join = if closed { 0 } else { 1 };
+    for i in first_join..n_segments {
+        let incoming = if i == 0 { n_segments - 1 } else { i - 1 };
+        let (d0, n0, l0) = (segments[incoming].direction, segments[incoming].normal, segments[incoming].length);
+        let (d1, n1, l1) = (segments[i].direction, segments[i].normal, segments[i].length);
+        let centre = segments[i].start;
+
+        let cross = d0.x * d1.y - d0.y * d1.x;
+        let dot = d0.dot(d1);
+
+        // Straight through - nothing to fill
+        if cross.abs() < POINT_EPSILON && dot > 0.0 {
+            continue;
+        }
+
+        // The outside of the corner is on the opposite side to the turn
+        let outer = if cross > 0.0 { -1.0 } else { 1.0 };
+
+        // Bisector of the two normals, missing if the line doubles back
+        let bisector = if (n0 + n1).magnitude() > POINT_EPSILON {
+            Some((n0 + n1).normalize())
+        } else {
+            None
+        };
+
+        // Pull the inside corner in to where the edges cross so nothing is drawn twice
+        if let Some(bisector) = bisector {
+            let inner_offset = bisector * (-outer * extent / bisector.dot(n0));
+            let along = inner_offset.dot(d0).abs().max(inner_offset.dot(d1).abs());
+
+            if along <= 0.5 * l0.min(l1) {
+                if outer > 0.0 {
+                    segments[incoming].end_right = inner_offset;
+                    segments[i].start_right = inner_offset;
+                } else {
+                    segments[incoming].end_left = inner_offset;
+                    segments[i].start_left = inner_offset;
+                }
+            }
+        }
+
+        // Fill the wedge on the outside of the corner
+        let from = n0 * outer * extent;
+        let to = n1 * outer * extent;
+
+        match join {
+            LineJoin::Miter => {
+                // Miter length relative to the half width
+                let miter = bisector.map(|b| 1.0 / b.dot(n0)).unwrap_or(f32::MAX);
+
+                if let (Some(bisector), true) = (bisector, miter <= MITER_LIMIT) {
+                    let tip = bisector * outer * extent * miter;
+                    stroker.triangle(centre, from, tip);
+                    stroker.triangle(centre, tip, to);
+                } else {
+                    stroker.triangle(centre, from, to);
+                }
+            },
+            LineJoin::Round => {
+                // Sweep round the outside, through the bisector or straight on if doubling back
+                let middle = bisector.map(|b| b * outer).unwrap_or(d0);
+                let sweep = n0.dot(n1).clamp(-1.0, 1.0).acos();
+                let sign = if from.x * middle.y - from.y * middle.x >= 0.0 { 1.0 } else { -1.0 };
+                stroker.arc(centre, from, sweep * sign);
+            },
+            LineJoin::Bevel => {
+                stroker.triangle(centre, from, to);
+            },
+        }
+    }
+
+    // Lengthen the ends for square caps & the fade
+    let cap_extension = match cap {
+        LineCap::Butt => { AA_FRINGE },
+        LineCap::Square => { half_width + AA_FRINGE },
+        LineCap::Round => { 0.0 },
+    };
+    let faded_ends = !closed && cap != LineCap::Round;
+
+    // Emit the segment bodies
+    for (i, segment) in segments.iter().enumerate() {
+        let fade_start = faded_ends && i == 0;
+        let fade_end = faded_ends && i == n_segments - 1;
+
+        if fade_start || fade_end {
+            // Split at the middle so the distance past each end is linear across each half
+            let middle = (segment.start + segment.end) / 2.0;
+            let to_middle = segment.length / 2.0 + cap_extension - AA_FRINGE;
+            let left = segment.normal * extent;
+            let right = -segment.normal * extent;
+
+            let (start, start_past, middle_past_start) = if fade_start {
+                (segment.start - segment.direction * cap_extension, AA_FRINGE, -to_middle)
+            } else {
+                (segment.start, NO_CAP, NO_CAP)
+            };
+
+            let (end, end_past, middle_past_end) = if fade_end {
+                (segment.end + segment.direction * cap_extension, AA_FRINGE, -to_middle)
+            } else {
+                (segment.end, NO_CAP, NO_CAP)
+            };
+
+            stroker.quad(
+                CrossSection { centre: start, left: segment.start_left, right: segment.start_right, past_end: start_past },
+                CrossSection { centre: middle, left, right, past_end: middle_past_start },
+            );
+            stroker.quad(
+                CrossSection { centre: middle, left, right, past_end: middle_past_end },
+                CrossSection { centre: end, left: segment.end_left, right: segment.end_right, past_end: end_past },
+            );
+        } else {
+            stroker.quad(
+                CrossSection { centre: segment.start, left: segment.start_left, right: segment.start_right, past_end: NO_CAP },
+                CrossSection { centre: segment.end, left: segment.end_left, right: segment.end_right, past_end: NO_CAP },
+            );
+        }
+    }
+
+    // Round caps are half circles around each end
+    if !closed && cap == LineCap::Round {
+        let first = &segments[0];
+        let last = &segments[n_segments - 1];
+
+        stroker.arc(first.start, first.normal * extent, std::f32::consts::PI);
+        stroker.arc(last.end, -last.normal * extent, std::f32::consts::PI);
+    }
+}
+
+// An edge of a filled polygon, stored top to bottom with the direction it was drawn in
+struct FillEdge {
+    top: Vector2,
+    bottom: Vector2,
+    winding: i32,
+}
+
+impl FillEdge {
+    fn x_at(&self, y: f32) -> f32 {
+        let t = (y - self.top.y) / (self.bottom.y - self.top.y);
+        self.top.x + (self.bottom.x - self.top.x) * t
+    }
+}
+
+// Generate triangles filling the polygons, where overlaps and holes follow the fill rule.
+// The shapes are swept top to bottom in horizontal bands between corners, keeping the edges
+// crossing the current band in order. Bands are split where neighbouring edges cross, so no edges
+// cross inside a band and each run of the band that is inside the shape is a trapezoid.
+pub fn fill_polygons(
+    polygons: &[&[Point2]],
+    fill_rule: FillRule,
+    colour: [f32; 4],
+    output: &mut Vec<TriangleVertex>,
+) {
+    // Collect the edges, ignoring horizontal ones as they never cross a band
+    let mut edges = Vec::new();
+    for polygon in polygons {
+        for (i, start) in polygon.iter().enumerate() {
+            let end = polygon[(i + 1) % polygon.len()];
+            let (start, end) = (Vector2::new(start.x, start.y), Vector2::new(end.x, end.y));
+
+            if (end.y - start.y).abs() < POINT_EPSILON {
+                continue;
+            }
+
+            edges.push(if start.y < end.y {
+                FillEdge { top: start, bottom: end, winding: 1 }
+            } else {
+                FillEdge { top: end, bottom: start, winding: -1 }
+            });
+        }
+    }
+
+    if edges.is_empty() {
+        return;
+    }
+
+    // Edges join the sweep in order of their tops
+    edges.sort_by(|a, b| a.top.y.partial_cmp(&b.top.y).unwrap_or(std::cmp::Ordering::Equal));
+
+    // Band boundaries at every end point, crossings are found during the sweep
+    let mut ys: Vec<f32> = edges.iter()
+        .flat_map(|edge| [edge.top.y, edge.bottom.y])
+        .collect();
+    ys.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+    ys.dedup_by(|a, b| (*a - *b).abs() < POINT_EPSILON);
+
+    // Every vertex is fully covered, the stroke fading isn't used for fills
+    let mut vertex = |x: f32, y: f32| {
+        output.push(TriangleVertex {
+            position: [x, y],
+            colour,
+            edge: [0.0, NO_CAP],
+            half_width: 1.0,
+            paint: SOLID_PAINT,
+        });
+    };
+
+    // Edges crossing the band, each with where it is across the band's middle
+    let mut active: Vec<(f32, &FillEdge)> = Vec::new();
+    let mut next_edge = 0;
+    let mut next_y = 1;
+    let mut top = ys[0];
+
+    while next_y < ys.len() {
+        let mut bottom = ys[next_y];
+
+        // Edges finished above the band leave it and those starting at its top join it
+        active.retain(|(_, edge)| edge.bottom.y > top + POINT_EPSILON);
+        while next_edge < edges.len() && edges[next_edge].top.y <= top + POINT_EPSILON {
+            active.push((0.0, &edges[next_edge]));
+            next_edge += 1;
+        }
+
+        // Edges can only cross their neighbours first, so the band is cut short at the highest of
+        // those crossings. Below the middle the order is right all the way up to it, otherwise the
+        // shorter band is checked again. The order barely changes between bands, which the sort is
+        // quick for.
+        let mut split = false;
+        loop {
+            let middle = (top + bottom) / 2.0;
+            for (x, edge) in active.iter_mut() {
+                *x = edge.x_at(middle);
+            }
+            active.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap_or(std::cmp::Ordering::Equal));
+
+            let crossing = active.windows(2)
+                .filter_map(|pair| crossing_y(pair[0].1, pair[1].1, top, bottom))
+                .min_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+
+            match crossing {
+                Some(y) => {
+                    bottom = y;
+                    split = true;
+                    if y >= middle {
+                        break;
+                    }
+                },
+                None => { break; },
+            }
+        }
+
+        if !split {
+            next_y += 1;
+        }
+
+        // Walk across the band, keeping a trapezoid each time the shape is left
+        let mut winding = 0;
+        let mut left: Option<&FillEdge> = None;
+        for (_, edge) in active.iter() {
+            winding += edge.winding;
+
+            let inside = match fill_rule {
+                FillRule::NonZero => { winding != 0 },
+                FillRule::EvenOdd => { winding % 2 != 0 },
+            };
+
+            match (left, inside) {
+                (None, true) => { left = Some(edge) },
+                (Some(left_edge), false) => {
+                    let (top_left, bottom_left) = (left_edge.x_at(top), left_edge.x_at(bottom));
+                    let (top_right, bottom_right) = (edge.x_at(top), edge.x_at(bottom));
+
+                    vertex(top_left, top);
+                    vertex(top_right, top);
+                    vertex(bottom_right, bottom);
+
+                    vertex(top_left, top);
+                    vertex(bottom_right, bottom);
+                    vertex(bottom_left, bottom);
+
+                    left = None;
+                },
+                _ => {},
+            }
+        }
+
+        top = bottom;
+    }
+}
+
+// Height at which two edges cross inside the band, if they do
+fn crossing_y(a: &FillEdge, b: &FillEdge, top: f32, bottom: f32) -> Option<f32> {
+    // Horizontal gap between the edges at the top and bottom of the band
+    let gap_top = a.x_at(top) - b.x_at(top);
+    let gap_bottom = a.x_at(bottom) - b.x_at(bottom);
+    if gap_top * gap_bottom >= 0.0 {
+        return None;
+    }
+
+    // Crossings right by the band's ends are left as they are, like corners that close together
+    let y = top + (bottom - top) * gap_top / (gap_top - gap_bottom);
+    if y - top < POINT_EPSILON || bottom - y < POINT_EPSILON {
+        return None;
+    }
+
+    Some(y)
+}
+
+// Rotate a vector anticlockwise by the angle in radians
+fn rotate(v: Vector2, angle: f32) -> Vector2 {
+    let (s, c) = angle.sin_cos();
+    Vector2::new(v.x * c - v.y * s, v.x * s + v.y * c)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WHITE: [f32; 4] = [1.0, 1.0, 1.0, 1.0];
+
+    fn points(coords: &[(f32, f32)]) -> Vec<Point2> {
+        coords.iter().map(|&(x, y)| Point2::new(x, y)).collect()
+    }
+
+    fn fill(polygons: &[Vec<Point2>], fill_rule: FillRule) -> Vec<TriangleVertex> {
+        let polygons: Vec<&[Point2]> = polygons.iter().map(|polygon| polygon.as_slice()).collect();
+        let mut output = Vec::new();
+        fill_polygons(&polygons, fill_rule, WHITE, &mut output);
+        output
+    }
+
+    fn stroke(coords: &[(f32, f32)], join: LineJoin, cap: LineCap, closed: bool) -> Vec<TriangleVertex> {
+        let mut output = Vec::new();
+        stroke_polyline(&points(coords), 4.0, WHITE, join, cap, closed, &mut output);
+        output
+    }
+
+    fn triangles(vertices: &[TriangleVertex]) -> impl Iterator<Item = [[f32; 2]; 3]> + '_ {
+        assert_eq!(vertices.len() % 3, 0);
+        vertices.chunks(3).map(|t| [t[0].position, t[1].position, t[2].position])
+    }
+
+    fn signed_area([a, b, c]: [[f32; 2]; 3]) -> f32 {
+        ((b[0] - a[0]) * (c[1] - a[1]) - (c[0] - a[0]) * (b[1] - a[1])) / 2.0
+    }
+
+    fn area(vertices: &[TriangleVertex]) -> f32 {
+        triangles(vertices).map(|t| signed_area(t).abs()).sum()
+    }
+
+    fn covers(vertices: &[TriangleVertex], x: f32, y: f32) -> bool {
+        triangles(vertices).any(|[a, b, c]| {
+            let sides = [
+                signed_area([a, b, [x, y]]),
+                signed_area([b, c, [x, y]]),
+                signed_area([c, a, [x, y]]),
+            ];
+            sides.iter().all(|s| *s >= 0.0) || sides.iter().all(|s| *s <= 0.0)
+        })
+    }
+
+    fn assert_close(a: f32, b: f32) {
+        assert!((a - b).abs() < 1e-3, "{} != {}", a, b);
+    }
+
+    #[test]
+    fn square() {
+        let square = points(&[(0.0, 0.0), (10.0, 0.0), (10.0, 10.0), (0.0, 10.0)]);
+
+        for fill_rule in [FillRule::NonZero, FillRule::EvenOdd] {
+            let output = fill(&[square.clone()], fill_rule);
+            assert_eq!(output.len(), 6);
+            assert_close(area(&output), 100.0);
+        }
+    }
+
+    #[test]
+    fn square_with_hole() {
+        let outer = points(&[(0.0, 0.0), (10.0, 0.0), (10.0, 10.0), (0.0, 10.0)]);
+        let inner = points(&[(3.0, 3.0), (7.0, 3.0), (7.0, 7.0), (3.0, 7.0)]);
+        let reversed: Vec<Point2> = inner.iter().rev().cloned().collect();
+
+        // Wound the same way the hole is only cut out by even-odd
+        let output = fill(&[outer.clone(), inner.clone()], FillRule::NonZero);
+        assert_close(area(&output), 100.0);
+        assert!(covers(&output, 5.0, 5.0));
+
+        let output = fill(&[outer.clone(), inner], FillRule::EvenOdd);
+        assert_close(area(&output), 84.0);
+        assert!(!covers(&output, 5.0, 5.0));
+
+        // Wound the other way both rules cut it out
+        for fill_rule in [FillRule::NonZero, FillRule::EvenOdd] {
+            let output = fill(&[outer.clone(), reversed.clone()], fill_rule);
+            assert_close(area(&output), 84.0);
+            assert!(!covers(&output, 5.0, 5.0));
+            assert!(covers(&output, 1.0, 1.0));
+        }
+    }
+
+    #[test]
+    fn self_intersecting_star() {
+        // Each point joined to the one two along, so the edges cross and wind round the centre twice
+        let star: Vec<Point2> = (0..5)
+            .map(|i| {
+                let angle = std::f32::consts::FRAC_PI_2 + i as f32 * 4.0 * std::f32::consts::PI / 5.0;
+                Point2::new(10.0 * angle.cos(), 10.0 * angle.sin())
+            })
+            .collect();
+
+        let non_zero = fill(&[star.clone()], FillRule::NonZero);
+        let even_odd = fill(&[star], FillRule::EvenOdd);
+
+        assert!(covers(&non_zero, 0.0, 0.0));
+        assert!(!covers(&even_odd, 0.0, 0.0));
+
+        // The arms are filled by both
+        assert!(covers(&non_zero, 0.0, 9.0));
+        assert!(covers(&even_odd, 0.0, 9.0));
+
+        // Nothing is covered twice, so the difference is the centre pentagon
+        let (tenth, fifth) = (std::f32::consts::PI / 5.0, 2.0 * std::f32::consts::PI / 5.0);
+        let inner_radius = 10.0 * fifth.cos() / tenth.cos();
+        let pentagon = 2.5 * inner_radius * inner_radius * fifth.sin();
+        assert!((area(&non_zero) - area(&even_odd) - pentagon).abs() < 0.01 * pentagon);
+    }
+
+    #[test]
+    fn degenerate_fills() {
+        let cases = [
+            vec![],
+            points(&[(1.0, 1.0)]),
+            points(&[(1.0, 1.0), (1.0, 1.0), (1.0, 1.0)]),
+            points(&[(0.0, 0.0), (5.0, 5.0)]),
+            points(&[(0.0, 0.0), (5.0, 5.0), (10.0, 10.0)]),
+            points(&[(0.0, 0.0), (10.0, 0.0), (5.0, 0.0)]),
+        ];
+
+        for case in cases {
+            for fill_rule in [FillRule::NonZero, FillRule::EvenOdd] {
+                let output = fill(&[case.clone()], fill_rule);
+                assert!(area(&output) < 1e-3);
+                assert!(output.iter().all(|v| v.position.iter().all(|p| p.is_finite())));
+            }
+        }
+        assert!(fill(&[], FillRule::NonZero).is_empty());
+    }
+
+    #[test]
+    fn stroke_caps() {
+        // A width of 4 is drawn 3 out from the centre line to leave room for the fade
+        let line = [(0.0, 0.0), (10.0, 0.0)];
+
+        let butt = stroke(&line, LineJoin::Miter, LineCap::Butt, false);
+        assert_close(area(&butt), 12.0 * 6.0);
+
+        let square = stroke(&line, LineJoin::Miter, LineCap::Square, false);
+        assert_close(area(&square), 16.0 * 6.0);
+        assert!(covers(&square, -2.5, 2.5));
+        assert!(!covers(&butt, -2.5, 2.5));
+
+        let round = stroke(&line, LineJoin::Miter, LineCap::Round, false);
+        let circle = std::f32::consts::PI * 9.0;
+        assert!(area(&round) > 10.0 * 6.0 + 0.95 * circle);
+        assert!(area(&round) <= 10.0 * 6.0 + circle);
+        assert!(covers(&round, -2.0, 2.0));
+        assert!(!covers(&round, -2.5, 2.5));
+    }
+
+    #[test]
+    fn stroke_joins() {
+        // Turning left, so the outside corner is below and to the right of (10, 0)
+        let corner = [(0.0, 0.0), (10.0, 0.0), (10.0, 10.0)];
+
+        let miter = stroke(&corner, LineJoin::Miter, LineCap::Butt, false);
+        let bevel = stroke(&corner, LineJoin::Bevel, LineCap::Butt, false);
+        let round = stroke(&corner, LineJoin::Round, LineCap::Butt, false);
+
+        assert!(covers(&miter, 12.5, -2.5));
+        assert!(!covers(&bevel, 12.5, -2.5));
+        assert!(!covers(&round, 12.5, -2.5));
+        assert!(covers(&round, 11.9, -1.9));
+
+        // The miter adds a triangle onto the bevel, and the inside of the corner isn't doubled up
+        assert_close(area(&miter) - area(&bevel), 4.5);
+        assert!(area(&round) > area(&bevel) && area(&round) < area(&miter));
+
+        // Too sharp a corner falls back to a bevel
+        let spike = [(0.0, 0.0), (10.0, 0.0), (0.0, 1.0)];
+        assert_close(
+            area(&stroke(&spike, LineJoin::Miter, LineCap::Butt, false)),
+            area(&stroke(&spike, LineJoin::Bevel, LineCap::Butt, false)),
+        );
+    }
+
+    #[test]
+    fn degenerate_strokes() {
+        let cases: [&[(f32, f32)]; 5] = [
+            &[],
+            &[(1.0, 1.0)],
+            &[(1.0, 1.0), (1.0, 1.0), (1.0, 1.0)],
+            &[(0.0, 0.0), (10.0, 0.0), (0.0, 0.0)],
+            &[(0.0, 0.0), (10.0, 0.0), (10.0, 0.0), (20.0, 0.0)],
+        ];
+
+        for (i, case) in cases.iter().enumerate() {
+            for join in [LineJoin::Miter, LineJoin::Round, LineJoin::Bevel] {
+                for cap in [LineCap::Butt, LineCap::Square, LineCap::Round] {
+                    for closed in [false, true] {
+                        let output = stroke(case, join, cap, closed);
+                        assert!(output.iter().all(|v| v.position.iter().all(|p| p.is_finite())));
+                        if i < 3 {
+                            assert!(output.is_empty());
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
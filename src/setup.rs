@@ -11,6 +11,7 @@ pub struct Setup {
     pub adapter: wgpu::Adapter,
     pub device: wgpu::Device,
     pub queue: wgpu::Queue,
+    pub sample_count: u32,
 }
 
 pub async fn setup<App: BaseApp>(title: &str) -> Setup {
@@ -86,6 +87,8 @@ pub async fn setup<App: BaseApp>(title: &str) -> Setup {
         .await
         .unwrap();
 
+    let sample_count = supported_sample_count(App::sample_count());
+
     Setup {
         window,
         event_loop,
@@ -95,5 +98,21 @@ pub async fn setup<App: BaseApp>(title: &str) -> Setup {
         adapter,
         device,
         queue,
+        sample_count,
     }
+}
+
+// Every adapter can do 1 or 4 samples. Support for 2 and 8 depends on the adapter and format,
+// which can't be queried, so they are lowered to one of those.
+fn supported_sample_count(requested: u32) -> u32 {
+    let sample_count = match requested {
+        0 | 1 => 1,
+        _ => 4,
+    };
+
+    if sample_count != requested {
+        log::warn!("Sample count of {} not supported, using {}", requested, sample_count);
+    }
+
+    sample_count
 }
\ No newline at end of file
@@ -5,6 +5,7 @@ use super::{
     setup,
     Setup,
     TextureManager,
+    MaterialManager,
     GeometryManager,
     RendererInstance,
     CommandManager,
@@ -27,6 +28,7 @@ fn start<App: BaseApp>(
         adapter,
         device,
         queue,
+        sample_count,
     }: Setup,
 ) {
     #[cfg(not(target_arch = "wasm32"))]
@@ -55,20 +57,28 @@ fn start<App: BaseApp>(
 
     log::info!("Initializing the application...");
     let mut texture_manager = TextureManager::new();
+    let mut material_manager = MaterialManager::new();
     let mut geometry_manager = GeometryManager::new();
 
     let mut command_manager = CommandManager::new();
 
-    let mut renderer = RendererInstance::new(
+    let mut renderer = match RendererInstance::new(
         instance,
         size,
         surface,
         adapter,
         device,
         queue,
-    );
+        sample_count,
+    ) {
+        Ok(renderer) => { renderer },
+        Err(error) => {
+            log::error!("Couldn't build the renderer: {}", error);
+            return;
+        },
+    };
 
-    let mut main_app = App::init(size, &mut geometry_manager, &mut texture_manager);
+    let mut main_app = App::init(size, &mut geometry_manager, &mut texture_manager, &mut material_manager);
 
     log::info!("Creating timer...");
     #[cfg(not(target_arch = "wasm32"))]
@@ -154,6 +164,7 @@ fn start<App: BaseApp>(
                     &mut main_app, 
                     &mut command_manager, 
                     &mut texture_manager, 
+                    &mut material_manager,
                     &mut renderer
                 );
                 
@@ -167,6 +178,7 @@ fn render_frame<'frame, App: BaseApp>(
     main_app: &mut App, 
     command_manager: &mut CommandManager, 
     texture_manager: &mut TextureManager,
+    material_manager: &mut MaterialManager,
     renderer: &mut RendererInstance,
 ) {
     // Start a new frame
@@ -184,7 +196,7 @@ fn render_frame<'frame, App: BaseApp>(
     main_app.draw(cp);
 
     // Build and Submit frame to GPU
-    renderer.build_and_submit(command_manager, &mut section_manager, texture_manager);
+    renderer.build_and_submit(command_manager, &mut section_manager, texture_manager, material_manager);
 }
 
 #[cfg(not(target_arch = "wasm32"))]
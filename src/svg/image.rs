@@ -0,0 +1,206 @@
+use std::fmt;
+
+use cgmath::{InnerSpace, Matrix3, SquareMatrix, Transform};
+
+use crate::{
+    Colour,
+    FillPathDescription,
+    FillRule,
+    Gradient,
+    GradientShape,
+    LineCap,
+    LineJoin,
+    Path,
+    Point2,
+    Renderer,
+    RenderCommand,
+    StrokePathDescription,
+    Vector2,
+};
+
+use super::parse;
+
+// Why an SVG couldn't be loaded
+#[derive(Debug)]
+pub enum SvgError {
+    Io(std::io::Error),
+    Parse(roxmltree::Error),
+}
+
+impl fmt::Display for SvgError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SvgError::Io(error) => { write!(f, "Couldn't open SVG: {}", error) },
+            SvgError::Parse(error) => { write!(f, "Couldn't parse SVG: {}", error) },
+        }
+    }
+}
+
+impl std::error::Error for SvgError {}
+
+impl From<std::io::Error> for SvgError {
+    fn from(error: std::io::Error) -> Self {
+        SvgError::Io(error)
+    }
+}
+
+impl From<roxmltree::Error> for SvgError {
+    fn from(error: roxmltree::Error) -> Self {
+        SvgError::Parse(error)
+    }
+}
+
+// How a fill or stroke is painted - with a gradient the colour just tints it
+#[derive(Debug, Clone)]
+pub struct SvgPaint {
+    pub colour: Colour,
+    pub gradient: Option<Gradient>,
+}
+
+impl SvgPaint {
+    pub fn transformed(&self, transform: &Matrix3<f32>) -> Self {
+        Self {
+            colour: self.colour,
+            gradient: self.gradient.as_ref().map(|gradient| Gradient {
+                shape: transform_gradient_shape(&gradient.shape, transform),
+                stops: gradient.stops.clone(),
+            }),
+        }
+    }
+}
+
+// A single filled and or stroked path from the document
+#[derive(Debug, Clone)]
+pub struct SvgShape {
+    pub path: Path,
+    pub fill: Option<SvgPaint>,
+    pub fill_rule: FillRule,
+    pub stroke: Option<SvgPaint>,
+    pub stroke_width: f32,
+    pub join: LineJoin,
+    pub cap: LineCap,
+}
+
+impl SvgShape {
+    pub fn transformed(&self, transform: &Matrix3<f32>) -> Self {
+        Self {
+            path: self.path.transformed(transform),
+            fill: self.fill.as_ref().map(|paint| paint.transformed(transform)),
+            fill_rule: self.fill_rule,
+            stroke: self.stroke.as_ref().map(|paint| paint.transformed(transform)),
+            stroke_width: self.stroke_width * transform_scale(transform),
+            join: self.join,
+            cap: self.cap,
+        }
+    }
+}
+
+// Vector image loaded from an SVG document, drawn as paths so it stays sharp at any scale.
+// Supports paths, basic shapes, groups with transforms, solid colours and linear and radial gradients.
+// Text, images, clipping, masks, filters and patterns are skipped.
+#[derive(Debug)]
+pub struct SvgImage {
+    size: Vector2,
+
+    // Shapes in document pixels and where they are on screen
+    shapes: Vec<SvgShape>,
+    placed: Vec<SvgShape>,
+}
+
+impl SvgImage {
+    pub fn from_file(filename: &str) -> Result<Self, SvgError> {
+        let data = std::fs::read_to_string(filename)?;
+
+        Self::parse(&data)
+    }
+
+    pub fn parse(data: &str) -> Result<Self, SvgError> {
+        let (size, shapes) = parse::parse_document(data)?;
+
+        Ok(Self {
+            size,
+            placed: shapes.clone(),
+            shapes,
+        })
+    }
+
+    // Size of the document in pixels when drawn at a scale of 1
+    pub fn size(&self) -> Vector2 {
+        self.size
+    }
+
+    // Move the top left of the image to the position and scale it. The paths are rebuilt here
+    // rather than every frame, so only call this when the image actually moves.
+    pub fn set_placement(&mut self, position: Point2, scale: f32) {
+        let transform = parse::affine(scale, 0.0, 0.0, scale, position.x, position.y);
+
+        self.placed = self.shapes.iter()
+            .map(|shape| shape.transformed(&transform))
+            .collect();
+    }
+
+    pub fn draw<'s, R: Renderer<'s>>(&'s self, renderer: &mut R) {
+        for shape in &self.placed {
+            if let Some(fill) = &shape.fill {
+                renderer.add(RenderCommand::FillPath(FillPathDescription {
+                    path: &shape.path,
+                    colour: fill.colour,
+                    fill_rule: shape.fill_rule,
+                    gradient: fill.gradient.as_ref(),
+                    .. FillPathDescription::default()
+                }));
+            }
+
+            if let Some(stroke) = &shape.stroke {
+                renderer.add(RenderCommand::StrokePath(StrokePathDescription {
+                    path: &shape.path,
+                    width: shape.stroke_width,
+                    colour: stroke.colour,
+                    join: shape.join,
+                    cap: shape.cap,
+                    gradient: stroke.gradient.as_ref(),
+                    .. StrokePathDescription::default()
+                }));
+            }
+        }
+    }
+}
+
+// Average amount a transform scales lengths by
+fn transform_scale(transform: &Matrix3<f32>) -> f32 {
+    (transform.x.x * transform.y.y - transform.x.y * transform.y.x).abs().sqrt()
+}
+
+// Linear gradients stay exact, keeping the lines of equal colour lined up after skews and
+// uneven scales. Radial gradients would turn elliptical, so they only keep an average radius.
+fn transform_gradient_shape(shape: &GradientShape, transform: &Matrix3<f32>) -> GradientShape {
+    match *shape {
+        GradientShape::Linear{start, end} => {
+            let new_start = transform.transform_point(start);
+            let new_end = transform.transform_point(end);
+
+            // Lines of equal colour run at right angles to the gradient before the transform
+            let direction = end - start;
+            let level = transform.transform_point(start + Vector2::new(-direction.y, direction.x)) - new_start;
+
+            if level.magnitude2() < f32::EPSILON || transform.determinant().abs() < f32::EPSILON {
+                return GradientShape::Linear{start: new_start, end: new_end};
+            }
+
+            // The gradient now runs at right angles to them, reaching the end where the old end went
+            let normal = Vector2::new(-level.y, level.x).normalize();
+            let length = (new_end - new_start).dot(normal);
+
+            GradientShape::Linear{
+                start: new_start,
+                end: new_start + normal * length,
+            }
+        },
+        GradientShape::Radial{centre, radius} => {
+            GradientShape::Radial{
+                centre: transform.transform_point(centre),
+                radius: radius * transform_scale(transform),
+            }
+        },
+    }
+}
@@ -0,0 +1,7 @@
+mod image;
+mod parse;
+
+pub use image::{SvgImage, SvgError};
+
+// For internal use
+use image::{SvgPaint, SvgShape};
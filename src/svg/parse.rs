@@ -0,0 +1,1141 @@
+use std::collections::HashMap;
+
+use cgmath::{Matrix3, SquareMatrix};
+
+use crate::{
+    Colour,
+    ColourStop,
+    FillRule,
+    Gradient,
+    GradientShape,
+    LineCap,
+    LineJoin,
+    Path,
+    Point2,
+    Vector2,
+};
+
+use super::{SvgPaint, SvgShape};
+
+// Size used when the document doesn't give one, as browsers do
+const DEFAULT_SIZE: Vector2 = Vector2::new(300.0, 150.0);
+
+// Most gradients followed through href links, guarding against loops
+const MAX_GRADIENT_LINKS: usize = 8;
+
+const XLINK_NAMESPACE: &str = "http://www.w3.org/1999/xlink";
+
+// Build a 2D transform from the six numbers of an SVG matrix
+pub fn affine(a: f32, b: f32, c: f32, d: f32, e: f32, f: f32) -> Matrix3<f32> {
+    Matrix3::new(
+        a, b, 0.0,
+        c, d, 0.0,
+        e, f, 1.0,
+    )
+}
+
+// Read the document into its size in pixels and its shapes, already moved into document pixels
+pub fn parse_document(data: &str) -> Result<(Vector2, Vec<SvgShape>), roxmltree::Error> {
+    let document = roxmltree::Document::parse(data)?;
+    let root = document.root_element();
+
+    // The view box is stretched evenly to fit the size and centred
+    let view_box = root.attribute("viewBox")
+        .map(parse_numbers)
+        .filter(|numbers| numbers.len() == 4 && numbers[2] > 0.0 && numbers[3] > 0.0);
+
+    let view_size = view_box.as_ref().map(|numbers| Vector2::new(numbers[2], numbers[3]));
+    let size = Vector2::new(
+        root.attribute("width").and_then(parse_length)
+            .or(view_size.map(|size| size.x))
+            .unwrap_or(DEFAULT_SIZE.x),
+        root.attribute("height").and_then(parse_length)
+            .or(view_size.map(|size| size.y))
+            .unwrap_or(DEFAULT_SIZE.y),
+    );
+
+    let (transform, viewport) = match view_box {
+        Some(numbers) => {
+            let scale = (size.x / numbers[2]).min(size.y / numbers[3]);
+            let offset = Vector2::new(
+                (size.x - numbers[2] * scale) / 2.0 - numbers[0] * scale,
+                (size.y - numbers[3] * scale) / 2.0 - numbers[1] * scale,
+            );
+
+            (affine(scale, 0.0, 0.0, scale, offset.x, offset.y), Vector2::new(numbers[2], numbers[3]))
+        },
+        None => {
+            (Matrix3::identity(), size)
+        },
+    };
+
+    // Gradients can be defined anywhere in the document
+    let gradients = document.descendants()
+        .filter(|node| matches!(node.tag_name().name(), "linearGradient" | "radialGradient"))
+        .filter_map(|node| node.attribute("id").map(|id| (id, node)))
+        .collect();
+
+    let mut parser = Parser {
+        gradients,
+        viewport,
+        shapes: Vec::new(),
+    };
+
+    let style = Style::default().inherit(&root);
+    parser.visit_children(root, &transform, &style);
+
+    Ok((size, parser.shapes))
+}
+
+// Where a fill or stroke gets its paint from
+#[derive(Debug, Clone)]
+enum PaintSource {
+    None,
+    Colour(Colour),
+    Gradient(String),
+}
+
+// Presentation properties passed down from the parents
+#[derive(Debug, Clone)]
+struct Style {
+    fill: PaintSource,
+    fill_opacity: f32,
+    fill_rule: FillRule,
+    stroke: PaintSource,
+    stroke_opacity: f32,
+    stroke_width: f32,
+    join: LineJoin,
+    cap: LineCap,
+    // Group opacity is multiplied into everything inside rather than drawn as a layer
+    opacity: f32,
+}
+
+impl Style {
+    fn default() -> Self {
+        Self {
+            fill: PaintSource::Colour(Colour::BLACK),
+            fill_opacity: 1.0,
+            fill_rule: FillRule::NonZero,
+            stroke: PaintSource::None,
+            stroke_opacity: 1.0,
+            stroke_width: 1.0,
+            join: LineJoin::Miter,
+            cap: LineCap::Butt,
+            opacity: 1.0,
+        }
+    }
+
+    // The style of a child element, keeping anything it doesn't set
+    fn inherit(&self, node: &roxmltree::Node) -> Self {
+        let mut style = self.clone();
+
+        if let Some(fill) = property(node, "fill").and_then(parse_paint) {
+            style.fill = fill;
+        }
+        if let Some(stroke) = property(node, "stroke").and_then(parse_paint) {
+            style.stroke = stroke;
+        }
+        if let Some(opacity) = property(node, "fill-opacity").and_then(parse_opacity) {
+            style.fill_opacity = opacity;
+        }
+        if let Some(opacity) = property(node, "stroke-opacity").and_then(parse_opacity) {
+            style.stroke_opacity = opacity;
+        }
+        if let Some(width) = property(node, "stroke-width").and_then(parse_length) {
+            style.stroke_width = width;
+        }
+        if let Some(opacity) = property(node, "opacity").and_then(parse_opacity) {
+            style.opacity *= opacity;
+        }
+
+        match property(node, "fill-rule") {
+            Some("nonzero") => { style.fill_rule = FillRule::NonZero; },
+            Some("evenodd") => { style.fill_rule = FillRule::EvenOdd; },
+            _ => {},
+        }
+
+        match property(node, "stroke-linejoin") {
+            Some("miter") | Some("miter-clip") => { style.join = LineJoin::Miter; },
+            Some("round") => { style.join = LineJoin::Round; },
+            Some("bevel") => { style.join = LineJoin::Bevel; },
+            _ => {},
+        }
+
+        match property(node, "stroke-linecap") {
+            Some("butt") => { style.cap = LineCap::Butt; },
+            Some("round") => { style.cap = LineCap::Round; },
+            Some("square") => { style.cap = LineCap::Square; },
+            _ => {},
+        }
+
+        style
+    }
+}
+
+struct Parser<'a, 'input> {
+    gradients: HashMap<&'a str, roxmltree::Node<'a, 'input>>,
+    // Size percentages are measured against
+    viewport: Vector2,
+    shapes: Vec<SvgShape>,
+}
+
+impl <'a, 'input> Parser<'a, 'input> {
+    fn visit_children(&mut self, node: roxmltree::Node, transform: &Matrix3<f32>, style: &Style) {
+        for child in node.children().filter(|child| child.is_element()) {
+            if property(&child, "display") == Some("none") {
+                continue;
+            }
+
+            let style = style.inherit(&child);
+            let transform = match child.attribute("transform") {
+                Some(value) => { transform * parse_transform(value) },
+                None => { *transform },
+            };
+
+            match child.tag_name().name() {
+                // Nested documents are treated as groups, ignoring their own view box
+                "g" | "svg" | "a" => {
+                    self.visit_children(child, &transform, &style);
+                },
+                _ => {
+                    if let Some(path) = self.shape_path(&child) {
+                        self.add_shape(path, &transform, &style);
+                    }
+                },
+            }
+        }
+    }
+
+    // Outline of a shape element in its own space, none for anything that isn't drawn
+    fn shape_path(&self, node: &roxmltree::Node) -> Option<Path> {
+        let length = |name: &str, reference: f32| {
+            node.attribute(name)
+                .and_then(|value| parse_coordinate(value, reference))
+                .unwrap_or(0.0)
+        };
+        let (width, height) = (self.viewport.x, self.viewport.y);
+        let diagonal = normalised_diagonal(self.viewport);
+
+        let mut path = Path::new();
+
+        match node.tag_name().name() {
+            "path" => {
+                path = parse_path_data(node.attribute("d")?);
+            },
+            "rect" => {
+                let (x, y) = (length("x", width), length("y", height));
+                let (w, h) = (length("width", width), length("height", height));
+                if w <= 0.0 || h <= 0.0 {
+                    return None;
+                }
+
+                // A missing corner radius copies the other one
+                let rx = node.attribute("rx").and_then(|value| parse_coordinate(value, width));
+                let ry = node.attribute("ry").and_then(|value| parse_coordinate(value, height));
+                let rx = rx.or(ry).unwrap_or(0.0).clamp(0.0, w / 2.0);
+                let ry = ry.or(Some(rx)).unwrap_or(0.0).clamp(0.0, h / 2.0);
+
+                if rx > 0.0 && ry > 0.0 {
+                    let radii = Vector2::new(rx, ry);
+                    path.move_to(Point2::new(x + rx, y))
+                        .line_to(Point2::new(x + w - rx, y))
+                        .arc_to(radii, 0.0, false, true, Point2::new(x + w, y + ry))
+                        .line_to(Point2::new(x + w, y + h - ry))
+                        .arc_to(radii, 0.0, false, true, Point2::new(x + w - rx, y + h))
+                        .line_to(Point2::new(x + rx, y + h))
+                        .arc_to(radii, 0.0, false, true, Point2::new(x, y + h - ry))
+                        .line_to(Point2::new(x, y + ry))
+                        .arc_to(radii, 0.0, false, true, Point2::new(x + rx, y))
+                        .close();
+                } else {
+                    path.move_to(Point2::new(x, y))
+                        .line_to(Point2::new(x + w, y))
+                        .line_to(Point2::new(x + w, y + h))
+                        .line_to(Point2::new(x, y + h))
+                        .close();
+                }
+            },
+            "circle" | "ellipse" => {
+                let (cx, cy) = (length("cx", width), length("cy", height));
+                let (rx, ry) = if node.tag_name().name() == "circle" {
+                    let r = length("r", diagonal);
+                    (r, r)
+                } else {
+                    (length("rx", width), length("ry", height))
+                };
+                if rx <= 0.0 || ry <= 0.0 {
+                    return None;
+                }
+
+                // Two half turns
+                let radii = Vector2::new(rx, ry);
+                path.move_to(Point2::new(cx + rx, cy))
+                    .arc_to(radii, 0.0, false, true, Point2::new(cx - rx, cy))
+                    .arc_to(radii, 0.0, false, true, Point2::new(cx + rx, cy))
+                    .close();
+            },
+            "line" => {
+                path.move_to(Point2::new(length("x1", width), length("y1", height)))
+                    .line_to(Point2::new(length("x2", width), length("y2", height)));
+            },
+            "polyline" | "polygon" => {
+                let numbers = parse_numbers(node.attribute("points")?);
+                for (i, pair) in numbers.chunks_exact(2).enumerate() {
+                    let point = Point2::new(pair[0], pair[1]);
+                    if i == 0 {
+                        path.move_to(point);
+                    } else {
+                        path.line_to(point);
+                    }
+                }
+
+                if node.tag_name().name() == "polygon" {
+                    path.close();
+                }
+            },
+            _ => {},
+        }
+
+        if path.is_empty() {
+            None
+        } else {
+            Some(path)
+        }
+    }
+
+    fn add_shape(&mut self, path: Path, transform: &Matrix3<f32>, style: &Style) {
+        let bounds = path.bounds();
+
+        let fill = self.paint(&style.fill, style.fill_opacity * style.opacity, bounds);
+        let stroke = if style.stroke_width > 0.0 {
+            self.paint(&style.stroke, style.stroke_opacity * style.opacity, bounds)
+        } else {
+            None
+        };
+
+        if fill.is_none() && stroke.is_none() {
+            return;
+        }
+
+        let shape = SvgShape {
+            path,
+            fill,
+            fill_rule: style.fill_rule,
+            stroke,
+            stroke_width: style.stroke_width,
+            join: style.join,
+            cap: style.cap,
+        };
+
+        self.shapes.push(shape.transformed(transform));
+    }
+
+    // Paint in the shape's own space
+    fn paint(&self, source: &PaintSource, opacity: f32, bounds: Option<(Point2, Point2)>) -> Option<SvgPaint> {
+        match source {
+            PaintSource::None => { None },
+            PaintSource::Colour(colour) => {
+                Some(SvgPaint {
+                    colour: Colour {
+                        a: colour.a * opacity as f64,
+                        .. *colour
+                    },
+                    gradient: None,
+                })
+            },
+            PaintSource::Gradient(id) => {
+                let (gradient, transform) = self.gradient(id, bounds)?;
+
+                let paint = SvgPaint {
+                    colour: Colour {
+                        a: opacity as f64,
+                        .. Colour::WHITE
+                    },
+                    gradient: Some(gradient),
+                };
+
+                Some(paint.transformed(&transform))
+            },
+        }
+    }
+
+    // Gradient in its own space along with the transform into the shape's space
+    fn gradient(&self, id: &str, bounds: Option<(Point2, Point2)>) -> Option<(Gradient, Matrix3<f32>)> {
+        let node = *self.gradients.get(id)?;
+
+        // Linked gradients pass on any attributes and stops they don't set themselves
+        let mut chain = vec![node];
+        while chain.len() < MAX_GRADIENT_LINKS {
+            let link = chain.last()
+                .and_then(|node| node.attribute((XLINK_NAMESPACE, "href")).or_else(|| node.attribute("href")))
+                .and_then(|href| href.strip_prefix('#'))
+                .and_then(|id| self.gradients.get(id));
+
+            match link {
+                Some(linked) => { chain.push(*linked); },
+                None => { break; },
+            }
+        }
+        let attribute = |name: &str| chain.iter().find_map(|node| node.attribute(name));
+
+        // Bounding box units run from 0 to 1 across the shape
+        let bounding_box = attribute("gradientUnits") != Some("userSpaceOnUse");
+        let (reference, units) = if bounding_box {
+            let (min, max) = bounds?;
+            let size = max - min;
+            if size.x <= 0.0 || size.y <= 0.0 {
+                return None;
+            }
+
+            (Vector2::new(1.0, 1.0), affine(size.x, 0.0, 0.0, size.y, min.x, min.y))
+        } else {
+            (self.viewport, Matrix3::identity())
+        };
+
+        let coordinate = |name: &str, reference: f32, default: f32| {
+            attribute(name)
+                .and_then(|value| parse_coordinate(value, reference))
+                .unwrap_or(default)
+        };
+
+        let shape = if node.tag_name().name() == "linearGradient" {
+            GradientShape::Linear {
+                start: Point2::new(coordinate("x1", reference.x, 0.0), coordinate("y1", reference.y, 0.0)),
+                end: Point2::new(coordinate("x2", reference.x, reference.x), coordinate("y2", reference.y, 0.0)),
+            }
+        } else {
+            // The focal point isn't supported, so the gradient always centres on cx and cy
+            GradientShape::Radial {
+                centre: Point2::new(
+                    coordinate("cx", reference.x, reference.x * 0.5),
+                    coordinate("cy", reference.y, reference.y * 0.5),
+                ),
+                radius: coordinate("r", normalised_diagonal(reference), normalised_diagonal(reference) * 0.5),
+            }
+        };
+
+        // Stops come from the first gradient in the chain that has any
+        let mut stops: Vec<ColourStop> = Vec::new();
+        let stop_nodes = chain.iter()
+            .map(|node| node.children().filter(|child| child.tag_name().name() == "stop").collect::<Vec<_>>())
+            .find(|stops| !stops.is_empty())?;
+
+        for stop in stop_nodes {
+            // Offsets can't go backwards
+            let offset = stop.attribute("offset")
+                .and_then(|value| parse_coordinate(value, 1.0))
+                .unwrap_or(0.0)
+                .clamp(0.0, 1.0)
+                .max(stops.last().map(|stop| stop.offset).unwrap_or(0.0));
+
+            let mut colour = match property(&stop, "stop-color").and_then(parse_paint) {
+                Some(PaintSource::Colour(colour)) => { colour },
+                _ => { Colour::BLACK },
+            };
+            if let Some(opacity) = property(&stop, "stop-opacity").and_then(parse_opacity) {
+                colour.a *= opacity as f64;
+            }
+
+            stops.push(ColourStop{offset, colour});
+        }
+
+        let gradient_transform = attribute("gradientTransform")
+            .map(parse_transform)
+            .unwrap_or_else(Matrix3::identity);
+
+        Some((Gradient{shape, stops}, units * gradient_transform))
+    }
+}
+
+// Length used for percentages that aren't clearly horizontal or vertical
+fn normalised_diagonal(size: Vector2) -> f32 {
+    ((size.x * size.x + size.y * size.y) / 2.0).sqrt()
+}
+
+// Look up a presentation property, with the style attribute taking priority
+fn property<'a>(node: &roxmltree::Node<'a, '_>, name: &str) -> Option<&'a str> {
+    let from_style = node.attribute("style").and_then(|style| {
+        style.split(';')
+            .filter_map(|declaration| declaration.split_once(':'))
+            .filter(|(key, _)| key.trim() == name)
+            .map(|(_, value)| value.trim())
+            .next_back()
+    });
+
+    from_style
+        .or_else(|| node.attribute(name).map(str::trim))
+        .filter(|value| *value != "inherit")
+}
+
+// Path data in the SVG mini language, stopping at the first error as browsers do
+fn parse_path_data(data: &str) -> Path {
+    let mut path = Path::new();
+    parse_path_commands(&mut Lexer::new(data), &mut path);
+    path
+}
+
+fn parse_path_commands(lexer: &mut Lexer, path: &mut Path) -> Option<()> {
+    let origin = Point2::new(0.0, 0.0);
+    let mut current = origin;
+    let mut start = origin;
+
+    // Control points reflected by the smooth curve commands
+    let mut last_cubic_control: Option<Point2> = None;
+    let mut last_quad_control: Option<Point2> = None;
+
+    let mut command: Option<u8> = None;
+
+    loop {
+        lexer.skip_separators();
+
+        // Numbers without a command repeat the last one
+        match lexer.command() {
+            Some(letter) => { command = Some(letter); },
+            None if lexer.at_end() => { return Some(()); },
+            None => {},
+        }
+        let letter = command?;
+
+        let relative = letter.is_ascii_lowercase();
+        let point = |lexer: &mut Lexer| -> Option<Point2> {
+            let (x, y) = (lexer.number()?, lexer.number()?);
+            Some(if relative {
+                Point2::new(current.x + x, current.y + y)
+            } else {
+                Point2::new(x, y)
+            })
+        };
+
+        let mut cubic_control = None;
+        let mut quad_control = None;
+
+        match letter.to_ascii_uppercase() {
+            b'M' => {
+                current = point(lexer)?;
+                start = current;
+                path.move_to(current);
+
+                // Extra points after a move are lines
+                command = Some(if relative { b'l' } else { b'L' });
+            },
+            b'L' => {
+                current = point(lexer)?;
+                path.line_to(current);
+            },
+            b'H' => {
+                let x = lexer.number()?;
+                current.x = if relative { current.x + x } else { x };
+                path.line_to(current);
+            },
+            b'V' => {
+                let y = lexer.number()?;
+                current.y = if relative { current.y + y } else { y };
+                path.line_to(current);
+            },
+            b'C' | b'S' => {
+                let control_1 = if letter.eq_ignore_ascii_case(&b'C') {
+                    point(lexer)?
+                } else {
+                    reflect(last_cubic_control, current)
+                };
+                let control_2 = point(lexer)?;
+                let end = point(lexer)?;
+
+                path.cubic_to(control_1, control_2, end);
+                cubic_control = Some(control_2);
+                current = end;
+            },
+            b'Q' | b'T' => {
+                let control = if letter.eq_ignore_ascii_case(&b'Q') {
+                    point(lexer)?
+                } else {
+                    reflect(last_quad_control, current)
+                };
+                let end = point(lexer)?;
+
+                path.quad_to(control, end);
+                quad_control = Some(control);
+                current = end;
+            },
+            b'A' => {
+                let radii = Vector2::new(lexer.number()?, lexer.number()?);
+                let rotation = lexer.number()?.to_radians();
+                let large_arc = lexer.flag()?;
+                let sweep = lexer.flag()?;
+                let end = point(lexer)?;
+
+                path.arc_to(radii, rotation, large_arc, sweep, end);
+                current = end;
+            },
+            b'Z' => {
+                path.close();
+                current = start;
+
+                // Numbers straight after a close are an error
+                command = None;
+            },
+            _ => { return None; },
+        }
+
+        last_cubic_control = cubic_control;
+        last_quad_control = quad_control;
+    }
+}
+
+// Mirror the last control point through the current point, or use the current point if there isn't one
+fn reflect(control: Option<Point2>, current: Point2) -> Point2 {
+    match control {
+        Some(control) => { Point2::new(2.0 * current.x - control.x, 2.0 * current.y - control.y) },
+        None => { current },
+    }
+}
+
+// A list of transform functions, applied right to left as written
+fn parse_transform(data: &str) -> Matrix3<f32> {
+    let mut transform = Matrix3::identity();
+
+    for function in data.split(')') {
+        let (name, arguments) = match function.split_once('(') {
+            Some(function) => { function },
+            None => { break; },
+        };
+        let name = name.trim_matches(|c: char| c.is_whitespace() || c == ',');
+        let numbers = parse_numbers(arguments);
+        let argument = |i: usize| numbers.get(i).copied();
+
+        let next = match (name, numbers.len()) {
+            ("matrix", 6) => {
+                affine(numbers[0], numbers[1], numbers[2], numbers[3], numbers[4], numbers[5])
+            },
+            ("translate", 1) | ("translate", 2) => {
+                affine(1.0, 0.0, 0.0, 1.0, numbers[0], argument(1).unwrap_or(0.0))
+            },
+            ("scale", 1) | ("scale", 2) => {
+                affine(numbers[0], 0.0, 0.0, argument(1).unwrap_or(numbers[0]), 0.0, 0.0)
+            },
+            ("rotate", 1) | ("rotate", 3) => {
+                // Rotating about a point moves it to the origin and back
+                let (sin, cos) = numbers[0].to_radians().sin_cos();
+                let (cx, cy) = (argument(1).unwrap_or(0.0), argument(2).unwrap_or(0.0));
+
+                affine(1.0, 0.0, 0.0, 1.0, cx, cy)
+                    * affine(cos, sin, -sin, cos, 0.0, 0.0)
+                    * affine(1.0, 0.0, 0.0, 1.0, -cx, -cy)
+            },
+            ("skewX", 1) => {
+                affine(1.0, 0.0, numbers[0].to_radians().tan(), 1.0, 0.0, 0.0)
+            },
+            ("skewY", 1) => {
+                affine(1.0, numbers[0].to_radians().tan(), 0.0, 1.0, 0.0, 0.0)
+            },
+            // Anything broken makes the whole transform invalid
+            _ => { return Matrix3::identity(); },
+        };
+
+        transform = transform * next;
+    }
+
+    transform
+}
+
+fn parse_paint(value: &str) -> Option<PaintSource> {
+    if value == "none" || value == "transparent" {
+        return Some(PaintSource::None);
+    }
+
+    // A url may be followed by a fallback, which is ignored
+    if let Some(reference) = value.strip_prefix("url(") {
+        let id = reference.split(')').next()?.trim().trim_matches(|c| c == '\'' || c == '"');
+        return Some(PaintSource::Gradient(id.strip_prefix('#')?.to_string()));
+    }
+
+    let [r, g, b, a] = parse_colour(value)?;
+    Some(PaintSource::Colour(Colour {
+        r: srgb_to_linear(r),
+        g: srgb_to_linear(g),
+        b: srgb_to_linear(b),
+        a: a as f64,
+    }))
+}
+
+// An sRGB colour with each part from 0 to 1
+fn parse_colour(value: &str) -> Option<[f32; 4]> {
+    if let Some(hex) = value.strip_prefix('#') {
+        let digits = hex.chars()
+            .map(|c| c.to_digit(16).map(|digit| digit as f32))
+            .collect::<Option<Vec<f32>>>()?;
+
+        return match digits.len() {
+            // Short forms repeat each digit
+            3 | 4 => {
+                let part = |i: usize| digits.get(i).map(|digit| digit * 17.0 / 255.0).unwrap_or(1.0);
+                Some([part(0), part(1), part(2), part(3)])
+            },
+            6 | 8 => {
+                let part = |i: usize| {
+                    digits.get(i * 2).map(|high| (high * 16.0 + digits[i * 2 + 1]) / 255.0).unwrap_or(1.0)
+                };
+                Some([part(0), part(1), part(2), part(3)])
+            },
+            _ => { None },
+        };
+    }
+
+    let function = value.strip_prefix("rgba(").or_else(|| value.strip_prefix("rgb("));
+    if let Some(arguments) = function {
+        let parts: Vec<&str> = arguments.trim_end_matches(')')
+            .split(|c: char| c == ',' || c == '/' || c.is_whitespace())
+            .filter(|part| !part.is_empty())
+            .collect();
+        if parts.len() < 3 {
+            return None;
+        }
+
+        let channel = |part: &str| parse_coordinate(part, 255.0).map(|value| (value / 255.0).clamp(0.0, 1.0));
+        let alpha = match parts.get(3) {
+            Some(part) => { parse_coordinate(part, 1.0)?.clamp(0.0, 1.0) },
+            None => { 1.0 },
+        };
+
+        return Some([channel(parts[0])?, channel(parts[1])?, channel(parts[2])?, alpha]);
+    }
+
+    let [r, g, b] = named_colour(&value.to_ascii_lowercase())?;
+    Some([r as f32 / 255.0, g as f32 / 255.0, b as f32 / 255.0, 1.0])
+}
+
+// The more common colour keywords. Without a text colour to inherit, current colour is black.
+fn named_colour(name: &str) -> Option<[u8; 3]> {
+    let colour = match name {
+        "black" | "currentcolor" => { [0, 0, 0] },
+        "white" =>                  { [255, 255, 255] },
+        "red" =>                    { [255, 0, 0] },
+        "lime" =>                   { [0, 255, 0] },
+        "green" =>                  { [0, 128, 0] },
+        "blue" =>                   { [0, 0, 255] },
+        "yellow" =>                 { [255, 255, 0] },
+        "cyan" | "aqua" =>          { [0, 255, 255] },
+        "magenta" | "fuchsia" =>    { [255, 0, 255] },
+        "gray" | "grey" =>          { [128, 128, 128] },
+        "silver" =>                 { [192, 192, 192] },
+        "lightgray" | "lightgrey" => { [211, 211, 211] },
+        "darkgray" | "darkgrey" =>  { [169, 169, 169] },
+        "maroon" =>                 { [128, 0, 0] },
+        "olive" =>                  { [128, 128, 0] },
+        "navy" =>                   { [0, 0, 128] },
+        "purple" =>                 { [128, 0, 128] },
+        "teal" =>                   { [0, 128, 128] },
+        "orange" =>                 { [255, 165, 0] },
+        "pink" =>                   { [255, 192, 203] },
+        "brown" =>                  { [165, 42, 42] },
+        "gold" =>                   { [255, 215, 0] },
+        _ => { return None; },
+    };
+
+    Some(colour)
+}
+
+fn srgb_to_linear(value: f32) -> f64 {
+    let value = value as f64;
+    if value <= 0.04045 {
+        value / 12.92
+    } else {
+        ((value + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+fn parse_opacity(value: &str) -> Option<f32> {
+    parse_coordinate(value, 1.0).map(|opacity| opacity.clamp(0.0, 1.0))
+}
+
+// A length in pixels. Units other than pixels and percentages are read as pixels.
+fn parse_length(value: &str) -> Option<f32> {
+    if value.trim_end().ends_with('%') {
+        return None;
+    }
+
+    Lexer::new(value).number()
+}
+
+// A number or a percentage of the reference
+fn parse_coordinate(value: &str, reference: f32) -> Option<f32> {
+    let number = Lexer::new(value).number()?;
+
+    if value.trim_end().ends_with('%') {
+        Some(number / 100.0 * reference)
+    } else {
+        Some(number)
+    }
+}
+
+// Every number in a whitespace or comma separated list
+fn parse_numbers(data: &str) -> Vec<f32> {
+    let mut lexer = Lexer::new(data);
+    std::iter::from_fn(|| lexer.number()).collect()
+}
+
+// Reads numbers and command letters from attribute values
+struct Lexer<'d> {
+    data: &'d [u8],
+    position: usize,
+}
+
+impl <'d> Lexer<'d> {
+    fn new(data: &'d str) -> Self {
+        Self {
+            data: data.as_bytes(),
+            position: 0,
+        }
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.data.get(self.position).copied()
+    }
+
+    fn at_end(&self) -> bool {
+        self.position >= self.data.len()
+    }
+
+    fn skip_separators(&mut self) {
+        while let Some(c) = self.peek() {
+            if c.is_ascii_whitespace() || c == b',' {
+                self.position += 1;
+            } else {
+                break;
+            }
+        }
+    }
+
+    // Command letter if one is next
+    fn command(&mut self) -> Option<u8> {
+        let c = self.peek()?;
+        if c.is_ascii_alphabetic() && c != b'e' && c != b'E' {
+            self.position += 1;
+            Some(c)
+        } else {
+            None
+        }
+    }
+
+    // Arc flags are a single digit and don't need separating from what follows
+    fn flag(&mut self) -> Option<bool> {
+        self.skip_separators();
+        let flag = match self.peek()? {
+            b'0' => { false },
+            b'1' => { true },
+            _ => { return None; },
+        };
+        self.position += 1;
+
+        Some(flag)
+    }
+
+    fn number(&mut self) -> Option<f32> {
+        self.skip_separators();
+        let start = self.position;
+
+        let digits = |lexer: &mut Self| {
+            let before = lexer.position;
+            while lexer.peek().map(|c| c.is_ascii_digit()).unwrap_or(false) {
+                lexer.position += 1;
+            }
+            lexer.position > before
+        };
+
+        if let Some(b'+') | Some(b'-') = self.peek() {
+            self.position += 1;
+        }
+
+        let mut has_digits = digits(self);
+        if self.peek() == Some(b'.') {
+            self.position += 1;
+            has_digits |= digits(self);
+        }
+
+        if !has_digits {
+            self.position = start;
+            return None;
+        }
+
+        // Only take an exponent if it has digits, so units like em are left alone
+        if let Some(b'e') | Some(b'E') = self.peek() {
+            let mantissa_end = self.position;
+            self.position += 1;
+            if let Some(b'+') | Some(b'-') = self.peek() {
+                self.position += 1;
+            }
+            if !digits(self) {
+                self.position = mantissa_end;
+            }
+        }
+
+        std::str::from_utf8(&self.data[start..self.position]).ok()?.parse().ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::PathCommand;
+
+    fn close(a: f32, b: f32) -> bool {
+        (a - b).abs() < 1e-5
+    }
+
+    fn close_point(point: Point2, x: f32, y: f32) -> bool {
+        close(point.x, x) && close(point.y, y)
+    }
+
+    // Where each command ends, with none for a close
+    fn end_points(path: &Path) -> Vec<Option<Point2>> {
+        path.commands().iter()
+            .map(|command| match *command {
+                PathCommand::MoveTo(point) | PathCommand::LineTo(point) => { Some(point) },
+                PathCommand::QuadTo{end, ..} | PathCommand::CubicTo{end, ..} | PathCommand::ArcTo{end, ..} => { Some(end) },
+                PathCommand::Close => { None },
+            })
+            .collect()
+    }
+
+    #[test]
+    fn lexer_numbers() {
+        assert_eq!(parse_numbers("1,2 3\t-4"), vec![1.0, 2.0, 3.0, -4.0]);
+
+        // Signs and second decimal points start a new number
+        assert_eq!(parse_numbers("1-2.5.5+3"), vec![1.0, -2.5, 0.5, 3.0]);
+
+        assert_eq!(parse_numbers("1e2 -1.5E-1 .5"), vec![100.0, -0.15, 0.5]);
+
+        // An exponent without digits is a unit, not part of the number
+        assert_eq!(Lexer::new("2em").number(), Some(2.0));
+
+        assert_eq!(Lexer::new("-").number(), None);
+        assert_eq!(Lexer::new(".").number(), None);
+    }
+
+    #[test]
+    fn lexer_commands() {
+        let mut lexer = Lexer::new("M1e1L2");
+
+        assert_eq!(lexer.command(), Some(b'M'));
+        assert_eq!(lexer.number(), Some(10.0));
+        assert_eq!(lexer.command(), Some(b'L'));
+        assert_eq!(lexer.command(), None);
+        assert_eq!(lexer.number(), Some(2.0));
+        assert!(lexer.at_end());
+    }
+
+    #[test]
+    fn lengths_and_coordinates() {
+        assert_eq!(parse_length("12px"), Some(12.0));
+        assert_eq!(parse_length("50%"), None);
+        assert_eq!(parse_coordinate("50%", 30.0), Some(15.0));
+        assert_eq!(parse_coordinate("7", 30.0), Some(7.0));
+    }
+
+    #[test]
+    fn path_commands() {
+        let path = parse_path_data("M10 10 h5 v5 H0 V0 z");
+        let ends = end_points(&path);
+
+        assert_eq!(ends.len(), 6);
+        assert!(close_point(ends[0].unwrap(), 10.0, 10.0));
+        assert!(close_point(ends[1].unwrap(), 15.0, 10.0));
+        assert!(close_point(ends[2].unwrap(), 15.0, 15.0));
+        assert!(close_point(ends[3].unwrap(), 0.0, 15.0));
+        assert!(close_point(ends[4].unwrap(), 0.0, 0.0));
+        assert!(ends[5].is_none());
+    }
+
+    #[test]
+    fn implicit_command_repetition() {
+        // Extra points after a move are lines, relative if the move was
+        let path = parse_path_data("m1 1 2 0 0 2");
+        let ends = end_points(&path);
+
+        assert!(matches!(path.commands()[0], PathCommand::MoveTo(_)));
+        assert!(matches!(path.commands()[1], PathCommand::LineTo(_)));
+        assert!(matches!(path.commands()[2], PathCommand::LineTo(_)));
+        assert!(close_point(ends[1].unwrap(), 3.0, 1.0));
+        assert!(close_point(ends[2].unwrap(), 3.0, 3.0));
+
+        // Other commands repeat themselves
+        let path = parse_path_data("M0 0 Q1 1 2 0 3 -1 4 0");
+        assert_eq!(path.commands().len(), 3);
+        assert!(matches!(path.commands()[2], PathCommand::QuadTo{..}));
+        assert!(close_point(end_points(&path)[2].unwrap(), 4.0, 0.0));
+    }
+
+    #[test]
+    fn smooth_curves_reflect_control_points() {
+        let path = parse_path_data("M0 0 C0 1 1 1 1 0 S2 -1 2 0");
+
+        match path.commands()[2] {
+            PathCommand::CubicTo{control_1, ..} => { assert!(close_point(control_1, 1.0, -1.0)); },
+            _ => { panic!("Expected a cubic curve"); },
+        }
+    }
+
+    #[test]
+    fn errors_stop_the_path() {
+        // Everything before the error is kept
+        let path = parse_path_data("M0 0 L1 1 L2 X 3 3");
+        assert_eq!(path.commands().len(), 2);
+
+        // Numbers straight after a close are an error
+        let path = parse_path_data("M0 0 L1 0 Z 5 5");
+        assert_eq!(path.commands().len(), 3);
+
+        // Paths have to start with a command
+        assert!(parse_path_data("1 1 L2 2").is_empty());
+    }
+
+    #[test]
+    fn arc_flags() {
+        // Flags don't need separating from each other or from the end point
+        let path = parse_path_data("M0 0 A5 5 30 1110 10");
+
+        match path.commands()[1] {
+            PathCommand::ArcTo{radii, rotation, large_arc, sweep, end} => {
+                assert!(close(radii.x, 5.0) && close(radii.y, 5.0));
+                assert!(close(rotation, 30f32.to_radians()));
+                assert!(large_arc);
+                assert!(sweep);
+                assert!(close_point(end, 10.0, 10.0));
+            },
+            _ => { panic!("Expected an arc"); },
+        }
+
+        let path = parse_path_data("M0 0 a5,5 0 0,1 10,0");
+        match path.commands()[1] {
+            PathCommand::ArcTo{large_arc, sweep, end, ..} => {
+                assert!(!large_arc);
+                assert!(sweep);
+                assert!(close_point(end, 10.0, 0.0));
+            },
+            _ => { panic!("Expected an arc"); },
+        }
+
+        // Flags other than 0 or 1 are an error
+        let path = parse_path_data("M0 0 A5 5 0 2 0 10 10");
+        assert_eq!(path.commands().len(), 1);
+    }
+
+    fn apply(transform: &Matrix3<f32>, x: f32, y: f32) -> Point2 {
+        let point = transform * cgmath::Vector3::new(x, y, 1.0);
+        Point2::new(point.x, point.y)
+    }
+
+    #[test]
+    fn transform_lists() {
+        let transform = parse_transform("translate(10, 20) scale(2)");
+        assert!(close_point(apply(&transform, 1.0, 1.0), 12.0, 22.0));
+
+        // Functions apply right to left
+        let transform = parse_transform("scale(2) translate(10 20)");
+        assert!(close_point(apply(&transform, 1.0, 1.0), 22.0, 42.0));
+
+        let transform = parse_transform("rotate(90 5 5)");
+        assert!(close_point(apply(&transform, 10.0, 5.0), 5.0, 10.0));
+
+        let transform = parse_transform("matrix(1 0 0 1 3 4)");
+        assert!(close_point(apply(&transform, 0.0, 0.0), 3.0, 4.0));
+
+        let transform = parse_transform("skewX(45)");
+        assert!(close_point(apply(&transform, 0.0, 1.0), 1.0, 1.0));
+
+        // Anything broken makes the whole transform invalid
+        let transform = parse_transform("translate(10 20) scale(1 2 3)");
+        assert!(close_point(apply(&transform, 1.0, 1.0), 1.0, 1.0));
+    }
+
+    #[test]
+    fn colours() {
+        assert_eq!(parse_colour("#fff"), Some([1.0, 1.0, 1.0, 1.0]));
+        assert_eq!(parse_colour("#ff000080"), Some([1.0, 0.0, 0.0, 128.0 / 255.0]));
+        assert_eq!(parse_colour("#f008"), Some([1.0, 0.0, 0.0, 136.0 / 255.0]));
+        assert_eq!(parse_colour("#12345"), None);
+        assert_eq!(parse_colour("#ggg"), None);
+
+        assert_eq!(parse_colour("rgb(255, 0, 0)"), Some([1.0, 0.0, 0.0, 1.0]));
+        assert_eq!(parse_colour("rgba(0 100% 0 / 0.5)"), Some([0.0, 1.0, 0.0, 0.5]));
+        assert_eq!(parse_colour("rgb(1, 2)"), None);
+
+        assert_eq!(parse_colour("Blue"), Some([0.0, 0.0, 1.0, 1.0]));
+        assert_eq!(parse_colour("notacolour"), None);
+    }
+
+    #[test]
+    fn paints() {
+        assert!(matches!(parse_paint("none"), Some(PaintSource::None)));
+        assert!(matches!(parse_paint("url(#shine) red"), Some(PaintSource::Gradient(id)) if id == "shine"));
+
+        // Colours come out linear
+        match parse_paint("#808080") {
+            Some(PaintSource::Colour(colour)) => {
+                assert!((colour.r - 0.2158605).abs() < 1e-4);
+                assert!((colour.a - 1.0).abs() < 1e-9);
+            },
+            _ => { panic!("Expected a colour"); },
+        }
+    }
+
+    fn gradients<'a, 'input>(document: &'a roxmltree::Document<'input>) -> Parser<'a, 'input> {
+        Parser {
+            gradients: document.descendants()
+                .filter_map(|node| node.attribute("id").map(|id| (id, node)))
+                .collect(),
+            viewport: Vector2::new(100.0, 100.0),
+            shapes: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn gradient_href_chains() {
+        let data = r##"<svg xmlns="http://www.w3.org/2000/svg" xmlns:xlink="http://www.w3.org/1999/xlink">
+            <linearGradient id="stops">
+                <stop offset="0" stop-color="red"/>
+                <stop offset="1" stop-color="blue"/>
+            </linearGradient>
+            <linearGradient id="units" xlink:href="#stops" gradientUnits="userSpaceOnUse" x2="50"/>
+            <linearGradient id="top" href="#units" x1="10"/>
+            <linearGradient id="loop" href="#loop" gradientUnits="userSpaceOnUse"/>
+        </svg>"##;
+        let document = roxmltree::Document::parse(data).unwrap();
+        let parser = gradients(&document);
+
+        // Attributes and stops come from along the chain, nearest first
+        let (gradient, transform) = parser.gradient("top", None).unwrap();
+        match gradient.shape {
+            GradientShape::Linear{start, end} => {
+                assert!(close_point(start, 10.0, 0.0));
+                assert!(close_point(end, 50.0, 0.0));
+            },
+            _ => { panic!("Expected a linear gradient"); },
+        }
+        assert_eq!(gradient.stops.len(), 2);
+        assert!(close_point(apply(&transform, 1.0, 1.0), 1.0, 1.0));
+
+        // Loops are cut off, leaving a gradient with no stops
+        assert!(parser.gradient("loop", None).is_none());
+
+        // Bounding box units need the shape's bounds
+        assert!(parser.gradient("stops", None).is_none());
+        let bounds = Some((Point2::new(0.0, 0.0), Point2::new(4.0, 2.0)));
+        let (_, transform) = parser.gradient("stops", bounds).unwrap();
+        assert!(close_point(apply(&transform, 1.0, 1.0), 4.0, 2.0));
+    }
+
+    #[test]
+    fn documents() {
+        let (size, shapes) = parse_document(r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 10 10" width="20">
+            <rect width="5" height="5" fill="red"/>
+            <circle r="1" fill="none"/>
+        </svg>"#).unwrap();
+
+        assert!(close(size.x, 20.0) && close(size.y, 10.0));
+        assert_eq!(shapes.len(), 1);
+
+        assert!(parse_document("<svg").is_err());
+    }
+}